@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use clap::Parser;
+use opportunity_market_cli::{
+    context::{default_keypair_path, Context},
+    crank::crank_market,
+    error::CliError,
+    output,
+};
+
+#[derive(Parser)]
+#[command(about = "Reveals and tallies share accounts of opportunity_market markets during their reveal period")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, env = "RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Solana keypair paying for the crank transactions, defaults to ~/.config/solana/id.json
+    #[arg(long, env = "DEPLOYER_KEYPAIR_PATH")]
+    keypair: Option<PathBuf>,
+
+    /// Arcium cluster reveal computations are queued on
+    #[arg(long, env = "ARCIUM_CLUSTER_OFFSET", default_value_t = 456)]
+    cluster_offset: u32,
+
+    /// Markets to crank, repeat for several
+    #[arg(long = "market", required = true)]
+    markets: Vec<Pubkey>,
+
+    /// Seconds between passes
+    #[arg(long, default_value_t = 30)]
+    interval: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Print pass reports as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Simulate transactions instead of sending them, implies --once
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Statuses after which a market never needs cranking again
+const FINISHED: [&str; 2] = ["cancelled", "reveal_ended"];
+
+fn run(cli: Cli) -> Result<(), CliError> {
+    let keypair = cli.keypair.unwrap_or_else(default_keypair_path);
+    let ctx = Context::new(&cli.url, &keypair, None, cli.cluster_offset, cli.dry_run)?;
+
+    let mut markets = cli.markets;
+    loop {
+        let mut active = Vec::new();
+        for market in markets {
+            let report = crank_market(&ctx, &market)?;
+            output::print(&report, cli.json);
+            if !FINISHED.contains(&report["status"].as_str().unwrap_or_default()) {
+                active.push(market);
+            }
+        }
+        markets = active;
+
+        if markets.is_empty() || cli.once || cli.dry_run {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(cli.interval));
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
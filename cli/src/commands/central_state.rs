@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Args, Subcommand};
+use opportunity_market_client::{
+    accounts::CentralState,
+    instructions::{
+        init_central_state, transfer_central_state_authority, update_central_state, InitCentralStateParams,
+        TransferCentralStateAuthorityParams, UpdateCentralStateParams,
+    },
+    pda::central_state_address,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{deserialize_pubkey, required, with_transaction};
+use crate::context::{read_json, Context};
+use crate::error::CliError;
+
+#[derive(Subcommand)]
+pub enum CentralStateCommand {
+    /// Create the central state. Fees go to the signer unless --treasury is given.
+    Init(CentralStateArgs),
+
+    /// Update the central state, omitted parameters keep their current value
+    Update(CentralStateArgs),
+
+    /// Hand the central state authority over to another account
+    Transfer {
+        #[arg(long)]
+        new_authority: Pubkey,
+    },
+
+    /// Print the central state
+    Show,
+}
+
+#[derive(Args, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CentralStateArgs {
+    /// JSON file shaped like `scripts/centralState.json`, flags take precedence over it
+    #[arg(long)]
+    #[serde(skip)]
+    config: Option<PathBuf>,
+
+    #[arg(long)]
+    earliness_cutoff_seconds: Option<u64>,
+
+    #[arg(long)]
+    min_option_deposit: Option<u64>,
+
+    #[arg(long)]
+    reward_reclaim_grace_seconds: Option<u64>,
+
+    #[arg(long)]
+    protocol_fee_bps: Option<u16>,
+
+    #[arg(long)]
+    #[serde(default, deserialize_with = "deserialize_pubkey")]
+    treasury: Option<Pubkey>,
+}
+
+impl CentralStateArgs {
+    fn resolve(self) -> Result<Self, CliError> {
+        let Some(path) = &self.config else {
+            return Ok(self);
+        };
+        let file: Self = read_json(path)?;
+        Ok(Self {
+            config: None,
+            earliness_cutoff_seconds: self.earliness_cutoff_seconds.or(file.earliness_cutoff_seconds),
+            min_option_deposit: self.min_option_deposit.or(file.min_option_deposit),
+            reward_reclaim_grace_seconds: self.reward_reclaim_grace_seconds.or(file.reward_reclaim_grace_seconds),
+            protocol_fee_bps: self.protocol_fee_bps.or(file.protocol_fee_bps),
+            treasury: self.treasury.or(file.treasury),
+        })
+    }
+}
+
+pub fn run(ctx: &Context, command: CentralStateCommand) -> Result<Value, CliError> {
+    let central_state = central_state_address().0;
+    match command {
+        CentralStateCommand::Init(args) => {
+            let args = args.resolve()?;
+            let ix = init_central_state(InitCentralStateParams {
+                payer: ctx.pubkey(),
+                earliness_cutoff_seconds: required(args.earliness_cutoff_seconds, "earliness-cutoff-seconds")?,
+                min_option_deposit: required(args.min_option_deposit, "min-option-deposit")?,
+                reward_reclaim_grace_seconds: required(
+                    args.reward_reclaim_grace_seconds,
+                    "reward-reclaim-grace-seconds",
+                )?,
+                protocol_fee_bps: args.protocol_fee_bps.unwrap_or(0),
+                treasury: args.treasury.unwrap_or(ctx.pubkey()),
+            });
+            Ok(with_transaction(
+                json!({ "central_state": central_state.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        CentralStateCommand::Update(args) => {
+            let args = args.resolve()?;
+            let current: CentralState = ctx.fetch(&central_state)?;
+            let ix = update_central_state(UpdateCentralStateParams {
+                authority: ctx.pubkey(),
+                earliness_cutoff_seconds: args
+                    .earliness_cutoff_seconds
+                    .unwrap_or(current.earliness_cutoff_seconds),
+                min_option_deposit: args.min_option_deposit.unwrap_or(current.min_option_deposit),
+                reward_reclaim_grace_seconds: args
+                    .reward_reclaim_grace_seconds
+                    .unwrap_or(current.reward_reclaim_grace_seconds),
+                protocol_fee_bps: args.protocol_fee_bps.unwrap_or(current.protocol_fee_bps),
+                treasury: args.treasury.unwrap_or(current.treasury),
+            });
+            Ok(with_transaction(
+                json!({ "central_state": central_state.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        CentralStateCommand::Transfer { new_authority } => {
+            let ix = transfer_central_state_authority(TransferCentralStateAuthorityParams {
+                authority: ctx.pubkey(),
+                new_authority,
+            });
+            Ok(with_transaction(
+                json!({ "central_state": central_state.to_string(), "new_authority": new_authority.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        CentralStateCommand::Show => {
+            let state: CentralState = ctx.fetch(&central_state)?;
+            Ok(json!({
+                "central_state": central_state.to_string(),
+                "authority": state.authority.to_string(),
+                "earliness_cutoff_seconds": state.earliness_cutoff_seconds,
+                "min_option_deposit": state.min_option_deposit,
+                "reward_reclaim_grace_seconds": state.reward_reclaim_grace_seconds,
+                "protocol_fee_bps": state.protocol_fee_bps,
+                "treasury": state.treasury.to_string(),
+            }))
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::Pubkey;
+use clap::Subcommand;
+use opportunity_market_client::{
+    accounts::EncryptedTokenAccount,
+    instructions::{
+        init_encrypted_token_account, unwrap_encrypted_tokens, wrap_encrypted_tokens, InitEncryptedTokenAccountParams,
+        UnwrapEncryptedTokensParams, WrapEncryptedTokensParams,
+    },
+    pda::encrypted_token_account_address,
+    x25519::random_nonce,
+};
+use serde_json::{json, Value};
+
+use super::with_transaction;
+use crate::context::Context;
+use crate::error::CliError;
+
+#[derive(Subcommand)]
+pub enum EtaCommand {
+    /// Create the signer's encrypted token account for a mint
+    Init {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+
+    /// Deposit tokens from the signer's associated token account
+    Wrap {
+        #[arg(long)]
+        mint: Pubkey,
+
+        #[arg(long)]
+        amount: u64,
+    },
+
+    /// Withdraw tokens to the signer's associated token account
+    Unwrap {
+        #[arg(long)]
+        mint: Pubkey,
+
+        #[arg(long)]
+        amount: u64,
+    },
+
+    /// Decrypt the signer's balance
+    Balance {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+}
+
+pub fn run(ctx: &Context, command: EtaCommand) -> Result<Value, CliError> {
+    match command {
+        EtaCommand::Init { mint } => {
+            let eta = encrypted_token_account_address(&mint, &ctx.pubkey()).0;
+            let ix = init_encrypted_token_account(InitEncryptedTokenAccountParams {
+                signer: ctx.pubkey(),
+                token_mint: mint,
+                user_pubkey: ctx.x25519()?.public_key,
+                state_nonce: random_nonce(),
+            });
+            Ok(with_transaction(
+                json!({ "encrypted_token_account": eta.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        EtaCommand::Wrap { mint, amount } => {
+            let eta = encrypted_token_account_address(&mint, &ctx.pubkey()).0;
+            let token_program = ctx.token_program(&mint)?;
+            let (signer_token_account, _) = ctx.token_account(&ctx.pubkey(), &mint, &token_program);
+            let ix = wrap_encrypted_tokens(
+                WrapEncryptedTokensParams {
+                    signer: ctx.pubkey(),
+                    token_mint: mint,
+                    encrypted_token_account: eta,
+                    signer_token_account,
+                    token_program,
+                    amount,
+                },
+                ctx.arcium_config(),
+            );
+            Ok(with_transaction(
+                json!({ "encrypted_token_account": eta.to_string(), "amount": amount }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        EtaCommand::Unwrap { mint, amount } => {
+            let eta = encrypted_token_account_address(&mint, &ctx.pubkey()).0;
+            let token_program = ctx.token_program(&mint)?;
+            let (user_token_account, create_ata) = ctx.token_account(&ctx.pubkey(), &mint, &token_program);
+            let ix = unwrap_encrypted_tokens(
+                UnwrapEncryptedTokensParams {
+                    signer: ctx.pubkey(),
+                    token_mint: mint,
+                    encrypted_token_account: eta,
+                    user_token_account,
+                    token_program,
+                    amount,
+                },
+                ctx.arcium_config(),
+            );
+            Ok(with_transaction(
+                json!({ "encrypted_token_account": eta.to_string(), "amount": amount }),
+                ctx.send(&[create_ata, ix])?,
+            ))
+        }
+        EtaCommand::Balance { mint } => {
+            let address = encrypted_token_account_address(&mint, &ctx.pubkey()).0;
+            let eta: EncryptedTokenAccount = ctx.fetch(&address)?;
+            // No encrypted state is written until the first callback credits the account
+            let balance = match eta.is_initialized {
+                true => ctx.cipher()?.decrypt_balance(&eta)?,
+                false => 0,
+            };
+            Ok(json!({
+                "encrypted_token_account": address.to_string(),
+                "owner": eta.owner.to_string(),
+                "mint": eta.token_mint.to_string(),
+                "balance": balance,
+                "pending_deposit": eta.pending_deposit,
+                "locked": eta.locked,
+            }))
+        }
+    }
+}
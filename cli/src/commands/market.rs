@@ -0,0 +1,770 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022::instruction::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use clap::{Args, Subcommand};
+use opportunity_market::{MAX_SCORE_TIERS, MAX_WINNING_OPTIONS};
+use opportunity_market_client::{
+    accounts::{
+        MarketParams, MarketTemplate, OpportunityMarket, OpportunityMarketOption, OptionTallies, ScoreTier,
+        ScoringConfig, WinningOption,
+    },
+    instructions::{
+        activate_market, close_market_sponsor, create_market, create_market_from_template, create_market_template,
+        disclose_option_tallies, extend_reveal_period, open_market, refund_sponsor, select_option,
+        select_winning_options, sponsor_market, ActivateMarketParams, CloseMarketSponsorParams,
+        CreateMarketFromTemplateParams, CreateMarketParams, CreateMarketTemplateParams, DiscloseOptionTalliesParams,
+        ExtendRevealPeriodParams, OpenMarketParams, RefundSponsorParams, SelectOptionParams,
+        SelectWinningOptionsParams, SponsorMarketParams,
+    },
+    pda::{
+        market_template_address, market_token_address, opportunity_market_address, opportunity_market_option_address,
+        option_tallies_address, template_market_address,
+    },
+    x25519::random_nonce,
+};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+use super::{deserialize_pubkey, required, with_transaction};
+use crate::context::{read_json, read_x25519_keypair, unix_now, Context};
+use crate::error::CliError;
+
+#[derive(Subcommand)]
+pub enum MarketCommand {
+    /// Create a market owned by the signer
+    Create(CreateMarketArgs),
+
+    /// Save market parameters as the signer's template `--template-id`, `--market-index` is ignored.
+    /// Markets of its series are created with `create --template-id`.
+    Template(CreateMarketArgs),
+
+    /// Open a created market for staking
+    Open {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// Unix timestamp staking starts at, defaults to one minute from now
+        #[arg(long)]
+        open_timestamp: Option<u64>,
+
+        /// Transfer whatever the market token account lacks of the reward from the signer first
+        #[arg(long)]
+        fund: bool,
+    },
+
+    /// Open a funded market created with --planned-open-timestamp, any signer can do this
+    Activate {
+        #[arg(long)]
+        market: Pubkey,
+    },
+
+    /// Add tokens from the signer to a market's reward pool
+    Sponsor {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// Amount in base units
+        #[arg(long)]
+        amount: u64,
+    },
+
+    /// Refund a sponsorship of an unopened, cancelled or winnerless market to the sponsor's token account
+    RefundSponsor {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// Defaults to the signer
+        #[arg(long)]
+        sponsor: Option<Pubkey>,
+    },
+
+    /// Close the sponsorship account of a market that paid out, returning its rent to the sponsor
+    CloseSponsor {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// Defaults to the signer
+        #[arg(long)]
+        sponsor: Option<Pubkey>,
+    },
+
+    /// Select the winning option, or several weighted winners with --winners
+    Select {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long, required_unless_present = "winners", conflicts_with = "winners")]
+        option: Option<u16>,
+
+        /// Winners in rank order as `option_index:weight`, e.g. `2:3,1:1`
+        #[arg(long, value_delimiter = ',')]
+        winners: Vec<WinningOptionArg>,
+    },
+
+    /// Extend the reveal period
+    Extend {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// New reveal period length in seconds, counted from the end of staking
+        #[arg(long)]
+        time_to_reveal: u64,
+    },
+
+    /// Print the market, its phase and its options
+    Status {
+        #[arg(long)]
+        market: Pubkey,
+    },
+
+    /// Decrypt the last disclosure of the running stake per option, as the authorized reader
+    Tallies {
+        #[arg(long)]
+        market: Pubkey,
+
+        /// Queue a new disclosure instead, signed by the creator or market authority.
+        /// Its callback lands a few seconds later.
+        #[arg(long)]
+        disclose: bool,
+    },
+}
+
+#[derive(Args, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMarketArgs {
+    /// JSON file shaped like `scripts/market.json`, flags take precedence over it
+    #[arg(long)]
+    #[serde(skip)]
+    config: Option<PathBuf>,
+
+    /// Ignored with `--template-id`, the template's series index then picks the market address
+    #[arg(long)]
+    market_index: Option<u64>,
+
+    /// With `create`, creates the next market of this template's series and takes every
+    /// other parameter from the template
+    #[arg(long)]
+    #[serde(skip)]
+    template_id: Option<u64>,
+
+    /// Unix timestamp anyone may open the market at with `activate` once it is funded
+    #[arg(long)]
+    #[serde(skip)]
+    planned_open_timestamp: Option<u64>,
+
+    #[arg(long)]
+    #[serde(default, deserialize_with = "deserialize_pubkey")]
+    token_mint: Option<Pubkey>,
+
+    /// Mint the reward is paid in, defaults to --token-mint
+    #[arg(long)]
+    #[serde(default, deserialize_with = "deserialize_pubkey")]
+    reward_mint: Option<Pubkey>,
+
+    #[arg(long)]
+    reward_amount: Option<u64>,
+
+    #[arg(long)]
+    time_to_stake: Option<u64>,
+
+    #[arg(long)]
+    time_to_reveal: Option<u64>,
+
+    #[arg(long)]
+    unstake_delay_seconds: Option<u64>,
+
+    /// x25519 keypair file of the reader stake disclosures are encrypted to,
+    /// defaults to the signer's x25519 keypair
+    #[arg(long)]
+    authorized_reader_keypair_path: Option<PathBuf>,
+
+    #[arg(long)]
+    allow_closing_early: Option<bool>,
+
+    /// Overrides the central state earliness cutoff
+    #[arg(long)]
+    earliness_cutoff_seconds: Option<u64>,
+
+    #[arg(long)]
+    #[serde(default, deserialize_with = "deserialize_pubkey")]
+    market_authority: Option<Pubkey>,
+
+    /// `linear` (default), `flat`, `exponential-decay:<half_life_seconds>` or
+    /// `step-tiers:<until_seconds>=<factor>,...` with factors scaled by 10000
+    #[arg(long)]
+    #[serde(default, deserialize_with = "deserialize_scoring")]
+    scoring: Option<ScoringArg>,
+
+    #[arg(long)]
+    losing_stake_slash_bps: Option<u16>,
+
+    /// Smallest amount the initial stake may carry. Defaults to 0, which accepts even empty stakes
+    #[arg(long)]
+    min_stake: Option<u64>,
+
+    /// Largest position a share account may hold, top-ups included. Unbounded if omitted
+    #[arg(long)]
+    max_stake: Option<u64>,
+}
+
+impl CreateMarketArgs {
+    fn resolve(self) -> Result<Self, CliError> {
+        let Some(path) = &self.config else {
+            return Ok(self);
+        };
+        let file: Self = read_json(path)?;
+        Ok(Self {
+            config: None,
+            market_index: self.market_index.or(file.market_index),
+            template_id: self.template_id,
+            planned_open_timestamp: self.planned_open_timestamp,
+            token_mint: self.token_mint.or(file.token_mint),
+            reward_mint: self.reward_mint.or(file.reward_mint),
+            reward_amount: self.reward_amount.or(file.reward_amount),
+            time_to_stake: self.time_to_stake.or(file.time_to_stake),
+            time_to_reveal: self.time_to_reveal.or(file.time_to_reveal),
+            unstake_delay_seconds: self.unstake_delay_seconds.or(file.unstake_delay_seconds),
+            authorized_reader_keypair_path: self
+                .authorized_reader_keypair_path
+                .or(file.authorized_reader_keypair_path),
+            allow_closing_early: self.allow_closing_early.or(file.allow_closing_early),
+            earliness_cutoff_seconds: self.earliness_cutoff_seconds.or(file.earliness_cutoff_seconds),
+            market_authority: self.market_authority.or(file.market_authority),
+            scoring: self.scoring.or(file.scoring),
+            losing_stake_slash_bps: self.losing_stake_slash_bps.or(file.losing_stake_slash_bps),
+            min_stake: self.min_stake.or(file.min_stake),
+            max_stake: self.max_stake.or(file.max_stake),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoringArg(ScoringConfig);
+
+impl FromStr for ScoringArg {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (kind, params) = value.split_once(':').unwrap_or((value, ""));
+        let parse = |v: &str| v.trim().parse::<u64>().map_err(|_| format!("invalid number `{v}`"));
+        let config = match kind {
+            "linear" => ScoringConfig::Linear,
+            "flat" => ScoringConfig::Flat,
+            "exponential-decay" => ScoringConfig::ExponentialDecay {
+                half_life_seconds: parse(params)?,
+            },
+            "step-tiers" => {
+                let mut tiers = [ScoreTier {
+                    until_seconds: 0,
+                    factor: 0,
+                }; MAX_SCORE_TIERS];
+                let entries: Vec<&str> = params.split(',').collect();
+                if entries.len() > MAX_SCORE_TIERS {
+                    return Err(format!("at most {MAX_SCORE_TIERS} tiers"));
+                }
+                for (tier, entry) in tiers.iter_mut().zip(entries) {
+                    let (until, factor) = entry
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected `<until_seconds>=<factor>`, got `{entry}`"))?;
+                    *tier = ScoreTier {
+                        until_seconds: parse(until)?,
+                        factor: parse(factor)?,
+                    };
+                }
+                ScoringConfig::StepTiers { tiers }
+            }
+            _ => return Err(format!("unknown scoring config `{kind}`")),
+        };
+        Ok(Self(config))
+    }
+}
+
+fn deserialize_scoring<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<ScoringArg>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WinningOptionArg(WinningOption);
+
+impl FromStr for WinningOptionArg {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (option_index, weight) = value.split_once(':').unwrap_or((value, "1"));
+        Ok(Self(WinningOption {
+            option_index: option_index
+                .parse()
+                .map_err(|_| format!("invalid option index `{option_index}`"))?,
+            weight: weight.parse().map_err(|_| format!("invalid weight `{weight}`"))?,
+        }))
+    }
+}
+
+pub fn run(ctx: &Context, command: MarketCommand) -> Result<Value, CliError> {
+    match command {
+        MarketCommand::Create(args) => create(ctx, args.resolve()?),
+        MarketCommand::Template(args) => create_template(ctx, args.resolve()?),
+        MarketCommand::Open {
+            market,
+            open_timestamp,
+            fund,
+        } => {
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let token_program = ctx.token_program(&account.reward_mint)?;
+            let market_token_ata = market_token_address(&market, &account.reward_mint, &token_program);
+            let open_timestamp = open_timestamp.unwrap_or(unix_now() + 60);
+
+            let mut ixs = Vec::new();
+            if fund {
+                let funded = ctx
+                    .fetch_optional::<TokenAccount>(&market_token_ata)?
+                    .map_or(0, |ata| ata.amount);
+                if funded < account.reward_amount {
+                    let mint: Mint = ctx.fetch(&account.reward_mint)?;
+                    let (source, _) = ctx.token_account(&ctx.pubkey(), &account.reward_mint, &token_program);
+                    ixs.push(
+                        transfer_checked(
+                            &token_program,
+                            &source,
+                            &account.reward_mint,
+                            &market_token_ata,
+                            &ctx.pubkey(),
+                            &[],
+                            account.reward_amount - funded,
+                            mint.decimals,
+                        )
+                        .map_err(|err| CliError::InvalidArgument(err.to_string()))?,
+                    );
+                }
+            }
+            ixs.push(open_market(OpenMarketParams {
+                creator: ctx.pubkey(),
+                market,
+                reward_mint: account.reward_mint,
+                token_program,
+                open_timestamp,
+            }));
+            Ok(with_transaction(
+                json!({ "market": market.to_string(), "open_timestamp": open_timestamp }),
+                ctx.send(&ixs)?,
+            ))
+        }
+        MarketCommand::Activate { market } => {
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let ix = activate_market(ActivateMarketParams {
+                signer: ctx.pubkey(),
+                market,
+                reward_mint: account.reward_mint,
+                token_program: ctx.token_program(&account.reward_mint)?,
+            });
+            Ok(with_transaction(
+                json!({
+                    "market": market.to_string(),
+                    "planned_open_timestamp": account.planned_open_timestamp,
+                }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        MarketCommand::Sponsor { market, amount } => {
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let token_program = ctx.token_program(&account.reward_mint)?;
+            let (sponsor_token_account, _) = ctx.token_account(&ctx.pubkey(), &account.reward_mint, &token_program);
+            let ix = sponsor_market(SponsorMarketParams {
+                sponsor: ctx.pubkey(),
+                market,
+                reward_mint: account.reward_mint,
+                sponsor_token_account,
+                token_program,
+                amount,
+            });
+            Ok(with_transaction(
+                json!({
+                    "market": market.to_string(),
+                    "amount": amount,
+                    "reward_amount": account.reward_amount + amount,
+                }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        MarketCommand::RefundSponsor { market, sponsor } => {
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let sponsor = sponsor.unwrap_or(ctx.pubkey());
+            let token_program = ctx.token_program(&account.reward_mint)?;
+            // The refund goes to the sponsor's ATA, which may have been closed since
+            let (_, create_ata_ix) = ctx.token_account(&sponsor, &account.reward_mint, &token_program);
+            let ix = refund_sponsor(RefundSponsorParams {
+                signer: ctx.pubkey(),
+                sponsor,
+                market,
+                reward_mint: account.reward_mint,
+                token_program,
+            });
+            Ok(with_transaction(
+                json!({ "market": market.to_string(), "sponsor": sponsor.to_string() }),
+                ctx.send(&[create_ata_ix, ix])?,
+            ))
+        }
+        MarketCommand::CloseSponsor { market, sponsor } => {
+            let sponsor = sponsor.unwrap_or(ctx.pubkey());
+            let ix = close_market_sponsor(CloseMarketSponsorParams {
+                signer: ctx.pubkey(),
+                sponsor,
+                market,
+            });
+            Ok(with_transaction(
+                json!({ "market": market.to_string(), "sponsor": sponsor.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        MarketCommand::Select {
+            market,
+            option,
+            winners,
+        } => {
+            let ix = match option {
+                Some(option_index) => select_option(SelectOptionParams {
+                    authority: ctx.pubkey(),
+                    market,
+                    option_index,
+                }),
+                None => {
+                    if winners.len() > MAX_WINNING_OPTIONS {
+                        return Err(CliError::InvalidArgument(format!(
+                            "at most {MAX_WINNING_OPTIONS} winning options"
+                        )));
+                    }
+                    select_winning_options(SelectWinningOptionsParams {
+                        authority: ctx.pubkey(),
+                        market,
+                        winning_options: winners.into_iter().map(|winner| winner.0).collect(),
+                    })
+                }
+            };
+            Ok(with_transaction(
+                json!({ "market": market.to_string() }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        MarketCommand::Extend { market, time_to_reveal } => {
+            let ix = extend_reveal_period(ExtendRevealPeriodParams {
+                authority: ctx.pubkey(),
+                market,
+                new_time_to_reveal: time_to_reveal,
+            });
+            Ok(with_transaction(
+                json!({ "market": market.to_string(), "time_to_reveal": time_to_reveal }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        MarketCommand::Status { market } => status(ctx, market),
+        MarketCommand::Tallies { market, disclose } => tallies(ctx, market, disclose),
+    }
+}
+
+fn create(ctx: &Context, args: CreateMarketArgs) -> Result<Value, CliError> {
+    if let Some(template_id) = args.template_id {
+        return create_from_template(ctx, template_id, args.planned_open_timestamp);
+    }
+    let market_index = required(args.market_index, "market-index")?;
+    let token_mint = required(args.token_mint, "token-mint")?;
+    let reward_mint = args.reward_mint.unwrap_or(token_mint);
+    let token_program = ctx.token_program(&reward_mint)?;
+    let params = market_params(ctx, &args)?;
+
+    let ix = create_market(CreateMarketParams {
+        creator: ctx.pubkey(),
+        token_mint,
+        reward_mint,
+        token_program,
+        market_index,
+        reward_amount: params.reward_amount,
+        time_to_stake: params.time_to_stake,
+        time_to_reveal: params.time_to_reveal,
+        market_authority: params.market_authority,
+        unstake_delay_seconds: params.unstake_delay_seconds,
+        authorized_reader_pubkey: params.authorized_reader_pubkey,
+        allow_closing_early: params.allow_closing_early,
+        earliness_cutoff_seconds: params.earliness_cutoff_seconds,
+        scoring_config: params.scoring_config,
+        losing_stake_slash_bps: params.losing_stake_slash_bps,
+        min_stake: params.min_stake,
+        max_stake: params.max_stake,
+        planned_open_timestamp: args.planned_open_timestamp,
+    });
+
+    let market = opportunity_market_address(&ctx.pubkey(), market_index).0;
+    Ok(with_transaction(
+        json!({
+            "market": market.to_string(),
+            "market_token_ata": market_token_address(&market, &reward_mint, &token_program).to_string(),
+            "authorized_reader_pubkey": hex::encode(params.authorized_reader_pubkey),
+        }),
+        ctx.send(&[ix])?,
+    ))
+}
+
+fn create_template(ctx: &Context, args: CreateMarketArgs) -> Result<Value, CliError> {
+    let template_id = required(args.template_id, "template-id")?;
+    let token_mint = required(args.token_mint, "token-mint")?;
+    let params = market_params(ctx, &args)?;
+
+    let ix = create_market_template(CreateMarketTemplateParams {
+        creator: ctx.pubkey(),
+        token_mint,
+        reward_mint: args.reward_mint.unwrap_or(token_mint),
+        template_id,
+        params,
+    });
+    Ok(with_transaction(
+        json!({
+            "template": market_template_address(&ctx.pubkey(), template_id).0.to_string(),
+            "authorized_reader_pubkey": hex::encode(params.authorized_reader_pubkey),
+        }),
+        ctx.send(&[ix])?,
+    ))
+}
+
+fn create_from_template(
+    ctx: &Context,
+    template_id: u64,
+    planned_open_timestamp: Option<u64>,
+) -> Result<Value, CliError> {
+    let template_address = market_template_address(&ctx.pubkey(), template_id).0;
+    let template: MarketTemplate = ctx.fetch(&template_address)?;
+    let token_program = ctx.token_program(&template.reward_mint)?;
+
+    let ix = create_market_from_template(CreateMarketFromTemplateParams {
+        creator: ctx.pubkey(),
+        template_id,
+        token_mint: template.mint,
+        reward_mint: template.reward_mint,
+        token_program,
+        series_index: template.next_series_index,
+        planned_open_timestamp,
+    });
+
+    let market = template_market_address(&template_address, template.next_series_index).0;
+    Ok(with_transaction(
+        json!({
+            "market": market.to_string(),
+            "market_token_ata": market_token_address(&market, &template.reward_mint, &token_program).to_string(),
+            "template": template_address.to_string(),
+            "series_index": template.next_series_index,
+        }),
+        ctx.send(&[ix])?,
+    ))
+}
+
+fn market_params(ctx: &Context, args: &CreateMarketArgs) -> Result<MarketParams, CliError> {
+    let authorized_reader = match &args.authorized_reader_keypair_path {
+        Some(path) => read_x25519_keypair(path)?,
+        None => ctx.x25519()?,
+    };
+    Ok(MarketParams {
+        reward_amount: required(args.reward_amount, "reward-amount")?,
+        time_to_stake: required(args.time_to_stake, "time-to-stake")?,
+        time_to_reveal: required(args.time_to_reveal, "time-to-reveal")?,
+        market_authority: args.market_authority,
+        unstake_delay_seconds: args.unstake_delay_seconds.unwrap_or(0),
+        authorized_reader_pubkey: authorized_reader.public_key,
+        allow_closing_early: args.allow_closing_early.unwrap_or(false),
+        earliness_cutoff_seconds: args.earliness_cutoff_seconds,
+        scoring_config: args.scoring.map_or(ScoringConfig::Linear, |scoring| scoring.0),
+        losing_stake_slash_bps: args.losing_stake_slash_bps.unwrap_or(0),
+        min_stake: args.min_stake.unwrap_or(0),
+        max_stake: args.max_stake,
+    })
+}
+
+fn status(ctx: &Context, address: Pubkey) -> Result<Value, CliError> {
+    let market: OpportunityMarket = ctx.fetch(&address)?;
+
+    let mut options = Vec::new();
+    for index in 1..=market.total_options {
+        let option_address = opportunity_market_option_address(&address, index).0;
+        // Options closed through `close_market_option` no longer exist
+        if let Some(option) = ctx.fetch_optional::<OpportunityMarketOption>(&option_address)? {
+            options.push(json!({
+                "index": option.index,
+                "address": option_address.to_string(),
+                "name": option.name,
+                "creator": option.creator.to_string(),
+                "initialized": option.initialized,
+                "total_shares": option.total_shares,
+                "total_score": option.total_score,
+            }));
+        }
+    }
+
+    Ok(json!({
+        "market": address.to_string(),
+        "phase": phase(&market, unix_now()),
+        "creator": market.creator.to_string(),
+        "index": market.index,
+        "mint": market.mint.to_string(),
+        "reward_mint": market.reward_mint.to_string(),
+        "market_authority": market.market_authority.map(|authority| authority.to_string()),
+        "open_timestamp": market.open_timestamp,
+        "planned_open_timestamp": market.planned_open_timestamp,
+        "time_to_stake": market.time_to_stake,
+        "time_to_reveal": market.time_to_reveal,
+        "reward_amount": market.reward_amount,
+        "total_sponsored": market.total_sponsored,
+        "total_rewards_claimed": market.total_rewards_claimed,
+        "protocol_fee_bps": market.protocol_fee_bps,
+        "protocol_fees_accrued": market.protocol_fees_accrued,
+        "losing_stake_slash_bps": market.losing_stake_slash_bps,
+        "total_slashed": market.total_slashed,
+        "earliness_cutoff_seconds": market.earliness_cutoff_seconds,
+        "scoring_config": scoring_json(&market.scoring_config),
+        "unstake_delay_seconds": market.unstake_delay_seconds,
+        "min_stake": market.min_stake,
+        "max_stake": market.max_stake,
+        "template": market.template.map(|template| template.to_string()),
+        "series_index": market.series_index,
+        "allow_closing_early": market.allow_closing_early,
+        "selected_option": market.selected_option,
+        "winning_options": market
+            .winning_options
+            .iter()
+            .map(|winner| json!({ "option_index": winner.option_index, "weight": winner.weight }))
+            .collect::<Vec<_>>(),
+        "open_share_accounts": market.open_share_accounts,
+        "cancelled": market.cancelled,
+        "rewards_reclaimed": market.rewards_reclaimed,
+        "options": options,
+    }))
+}
+
+fn tallies(ctx: &Context, market: Pubkey, disclose: bool) -> Result<Value, CliError> {
+    if disclose {
+        let ix = disclose_option_tallies(
+            DiscloseOptionTalliesParams {
+                authority: ctx.pubkey(),
+                market,
+                authorized_reader_nonce: random_nonce(),
+            },
+            ctx.arcium_config(),
+        );
+        return Ok(with_transaction(
+            json!({ "market": market.to_string() }),
+            ctx.send(&[ix])?,
+        ));
+    }
+
+    let option_tallies: OptionTallies = ctx.fetch(&option_tallies_address(&market).0)?;
+    let Some(disclosed_at) = option_tallies.disclosed_at_timestamp else {
+        return Ok(json!({ "market": market.to_string(), "disclosed_at": null, "tallies": null }));
+    };
+    let amounts = ctx.cipher()?.decrypt_option_tallies_disclosure(&option_tallies)?;
+    Ok(json!({
+        "market": market.to_string(),
+        "disclosed_at": disclosed_at,
+        "tallies": amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| json!({ "option_index": i + 1, "staked": amount }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+/// Where the market is in its life cycle at `now`, see `docs/market-life-cycle.md`
+fn phase(market: &OpportunityMarket, now: u64) -> &'static str {
+    let Some(open_timestamp) = market.open_timestamp else {
+        return if market.cancelled { "cancelled" } else { "created" };
+    };
+    let stake_end = open_timestamp + market.time_to_stake;
+    if market.cancelled {
+        "cancelled"
+    } else if now < open_timestamp {
+        "scheduled"
+    } else if now < stake_end {
+        "staking"
+    } else if now < stake_end + market.time_to_reveal {
+        "revealing"
+    } else {
+        "resolved"
+    }
+}
+
+fn scoring_json(config: &ScoringConfig) -> Value {
+    match config {
+        ScoringConfig::Linear => json!({ "kind": "linear" }),
+        ScoringConfig::ExponentialDecay { half_life_seconds } => {
+            json!({ "kind": "exponential_decay", "half_life_seconds": half_life_seconds })
+        }
+        ScoringConfig::StepTiers { tiers } => json!({
+            "kind": "step_tiers",
+            "tiers": tiers
+                .iter()
+                .filter(|tier| tier.until_seconds != 0)
+                .map(|tier| json!({ "until_seconds": tier.until_seconds, "factor": tier.factor }))
+                .collect::<Vec<_>>(),
+        }),
+        ScoringConfig::Flat => json!({ "kind": "flat" }),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scoring_configs() {
+        assert_eq!("linear".parse(), Ok(ScoringArg(ScoringConfig::Linear)));
+        assert_eq!(
+            "exponential-decay:3600".parse(),
+            Ok(ScoringArg(ScoringConfig::ExponentialDecay {
+                half_life_seconds: 3600
+            }))
+        );
+
+        let ScoringArg(ScoringConfig::StepTiers { tiers }) = "step-tiers:60=20000,600=15000".parse().unwrap() else {
+            panic!("expected step tiers");
+        };
+        assert_eq!(
+            tiers[0],
+            ScoreTier {
+                until_seconds: 60,
+                factor: 20000
+            }
+        );
+        assert_eq!(
+            tiers[1],
+            ScoreTier {
+                until_seconds: 600,
+                factor: 15000
+            }
+        );
+        assert_eq!(
+            tiers[2],
+            ScoreTier {
+                until_seconds: 0,
+                factor: 0
+            }
+        );
+
+        assert!("step-tiers:1=1,2=2,3=3,4=4,5=5".parse::<ScoringArg>().is_err());
+        assert!("quadratic".parse::<ScoringArg>().is_err());
+    }
+
+    #[test]
+    fn parses_winning_options() {
+        let WinningOptionArg(winner) = "2:3".parse().unwrap();
+        assert_eq!((winner.option_index, winner.weight), (2, 3));
+
+        let WinningOptionArg(winner) = "4".parse().unwrap();
+        assert_eq!((winner.option_index, winner.weight), (4, 1));
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use opportunity_market_client::{
+    instructions::{init_share_account, InitShareAccountParams},
+    pda::share_account_address,
+    x25519::random_nonce,
+};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::context::Context;
+use crate::error::CliError;
+
+pub mod central_state;
+pub mod eta;
+pub mod market;
+pub mod option;
+pub mod share;
+
+/// Value given on the command line or in the config file
+fn required<T>(value: Option<T>, flag: &str) -> Result<T, CliError> {
+    value.ok_or_else(|| CliError::InvalidArgument(format!("--{flag} is required")))
+}
+
+/// Base58 pubkeys in config files
+fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Appends the fields of `sent` (signature or simulation result) to `output`
+fn with_transaction(mut output: Value, sent: Value) -> Value {
+    if let (Value::Object(output), Value::Object(sent)) = (&mut output, sent) {
+        output.extend(sent);
+    }
+    output
+}
+
+/// Share account to stake through, defaulting to the signer's first unused id, plus the
+/// instruction initializing it when it does not exist yet
+fn prepare_share_account(
+    ctx: &Context,
+    market: &Pubkey,
+    share_account_id: Option<u32>,
+) -> Result<(u32, Option<Instruction>), CliError> {
+    let owner = ctx.pubkey();
+    let exists = |id| ctx.exists(&share_account_address(&owner, market, id).0);
+
+    let (share_account_id, initialized) = match share_account_id {
+        Some(id) => (id, exists(id)?),
+        None => {
+            let mut id = 0;
+            while exists(id)? {
+                id += 1;
+            }
+            (id, false)
+        }
+    };
+
+    let init = (!initialized).then(|| {
+        init_share_account(InitShareAccountParams {
+            signer: owner,
+            market: *market,
+            state_nonce: random_nonce(),
+            share_account_id,
+        })
+    });
+    Ok((share_account_id, init))
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::Pubkey;
+use clap::Subcommand;
+use opportunity_market_client::{
+    accounts::OpportunityMarket,
+    instructions::{
+        add_market_option, add_market_option_as_creator, AddMarketOptionAsCreatorParams, AddMarketOptionParams,
+    },
+    pda::{encrypted_token_account_address, opportunity_market_option_address},
+    x25519::random_nonce,
+};
+use serde_json::{json, Value};
+
+use super::{prepare_share_account, with_transaction};
+use crate::context::Context;
+use crate::error::CliError;
+
+#[derive(Subcommand)]
+pub enum OptionCommand {
+    /// Propose an option, staking --deposit from the signer's encrypted token account.
+    /// With --as-creator the market creator adds it without a deposit.
+    Add {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long)]
+        name: String,
+
+        #[arg(long, required_unless_present = "as_creator", conflicts_with = "as_creator")]
+        deposit: Option<u64>,
+
+        #[arg(long)]
+        as_creator: bool,
+
+        /// Share account holding the deposit, defaults to the first unused id
+        #[arg(long)]
+        share_account_id: Option<u32>,
+    },
+}
+
+pub fn run(ctx: &Context, command: OptionCommand) -> Result<Value, CliError> {
+    let OptionCommand::Add {
+        market,
+        name,
+        deposit,
+        as_creator: _,
+        share_account_id,
+    } = command;
+    let account: OpportunityMarket = ctx.fetch(&market)?;
+    let option_index = account.total_options + 1;
+    let option = opportunity_market_option_address(&market, option_index).0;
+
+    let Some(deposit) = deposit else {
+        let ix = add_market_option_as_creator(AddMarketOptionAsCreatorParams {
+            creator: ctx.pubkey(),
+            market,
+            option_index,
+            name,
+        });
+        return Ok(with_transaction(
+            json!({ "option": option.to_string(), "option_index": option_index }),
+            ctx.send(&[ix])?,
+        ));
+    };
+
+    let input = ctx.cipher()?.encrypt_amount_input(deposit);
+    let (share_account_id, init) = prepare_share_account(ctx, &market, share_account_id)?;
+
+    let mut ixs: Vec<_> = init.into_iter().collect();
+    ixs.push(add_market_option(
+        AddMarketOptionParams {
+            creator: ctx.pubkey(),
+            market,
+            source_eta: encrypted_token_account_address(&account.mint, &ctx.pubkey()).0,
+            option_index,
+            share_account_id,
+            name,
+            amount_ciphertext: input.amount_ciphertext,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: random_nonce(),
+        },
+        ctx.arcium_config(),
+    ));
+
+    Ok(with_transaction(
+        json!({
+            "option": option.to_string(),
+            "option_index": option_index,
+            "share_account_id": share_account_id,
+        }),
+        ctx.send(&ixs)?,
+    ))
+}
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::Pubkey;
+use clap::Subcommand;
+use opportunity_market_client::{
+    accounts::{OpportunityMarket, ShareAccount},
+    instructions::{
+        close_share_account, increment_option_tally, reveal_shares, stake, CloseShareAccountParams,
+        IncrementOptionTallyParams, RevealSharesParams, StakeParams,
+    },
+    pda::{encrypted_token_account_address, share_account_address},
+    x25519::random_nonce,
+};
+use serde_json::{json, Value};
+
+use super::{prepare_share_account, with_transaction};
+use crate::context::Context;
+use crate::error::CliError;
+
+#[derive(Subcommand)]
+pub enum ShareCommand {
+    /// Stake on an option from the signer's encrypted token account
+    Stake {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long)]
+        option: u16,
+
+        #[arg(long)]
+        amount: u64,
+
+        /// Defaults to the first unused share account id
+        #[arg(long)]
+        share_account_id: Option<u32>,
+    },
+
+    /// Reveal a share account once the staking period is over
+    Reveal {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long)]
+        share_account_id: u32,
+
+        /// Share account owner, defaults to the signer
+        #[arg(long)]
+        owner: Option<Pubkey>,
+
+        /// Also add the share account to its option's tally in the reveal callback. The option
+        /// is decrypted from the share account, so only the owner can pass this.
+        #[arg(long)]
+        tally: bool,
+    },
+
+    /// Add a revealed share account to its option's tally
+    Tally {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long)]
+        share_account_id: u32,
+
+        /// Share account owner, defaults to the signer
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+
+    /// Close a share account, paying out its reward to the signer's associated token account
+    Claim {
+        #[arg(long)]
+        market: Pubkey,
+
+        #[arg(long)]
+        share_account_id: u32,
+
+        /// Option the share account staked on. Only needed on cancelled markets, where
+        /// share accounts are closed without being revealed.
+        #[arg(long)]
+        option: Option<u16>,
+    },
+}
+
+pub fn run(ctx: &Context, command: ShareCommand) -> Result<Value, CliError> {
+    match command {
+        ShareCommand::Stake {
+            market,
+            option,
+            amount,
+            share_account_id,
+        } => {
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let input = ctx.cipher()?.encrypt_stake_input(amount, option);
+            let (share_account_id, init) = prepare_share_account(ctx, &market, share_account_id)?;
+
+            let mut ixs: Vec<_> = init.into_iter().collect();
+            ixs.push(stake(
+                StakeParams {
+                    signer: ctx.pubkey(),
+                    market,
+                    user_eta: encrypted_token_account_address(&account.mint, &ctx.pubkey()).0,
+                    share_account_id,
+                    amount_ciphertext: input.amount_ciphertext,
+                    selected_option_ciphertext: input.selected_option_ciphertext,
+                    input_nonce: input.input_nonce,
+                    authorized_reader_nonce: random_nonce(),
+                },
+                ctx.arcium_config(),
+            ));
+            Ok(with_transaction(
+                json!({
+                    "share_account": share_account_address(&ctx.pubkey(), &market, share_account_id).0.to_string(),
+                    "share_account_id": share_account_id,
+                }),
+                ctx.send(&ixs)?,
+            ))
+        }
+        ShareCommand::Reveal {
+            market,
+            share_account_id,
+            owner,
+            tally,
+        } => {
+            let owner = owner.unwrap_or(ctx.pubkey());
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let address = share_account_address(&owner, &market, share_account_id).0;
+            let tally_option_index = if tally {
+                let share_account: ShareAccount = ctx.fetch(&address)?;
+                Some(ctx.cipher()?.decrypt_share_state(&share_account)?.selected_option)
+            } else {
+                None
+            };
+            let ix = reveal_shares(
+                RevealSharesParams {
+                    signer: ctx.pubkey(),
+                    owner,
+                    market,
+                    user_eta: encrypted_token_account_address(&account.mint, &owner).0,
+                    share_account_id,
+                    token_mint: account.mint,
+                    reward_mint: account.reward_mint,
+                    token_program: ctx.token_program(&account.mint)?,
+                    tally_option_index,
+                },
+                ctx.arcium_config(),
+            );
+            Ok(with_transaction(
+                json!({ "share_account": address.to_string(), "tally_option_index": tally_option_index }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        ShareCommand::Tally {
+            market,
+            share_account_id,
+            owner,
+        } => {
+            let owner = owner.unwrap_or(ctx.pubkey());
+            let address = share_account_address(&owner, &market, share_account_id).0;
+            let share_account: ShareAccount = ctx.fetch(&address)?;
+            let option_index = share_account
+                .revealed_option
+                .ok_or_else(|| CliError::InvalidArgument(format!("share account {address} is not revealed")))?;
+            let ix = increment_option_tally(IncrementOptionTallyParams {
+                signer: ctx.pubkey(),
+                owner,
+                market,
+                option_index,
+                share_account_id,
+            });
+            Ok(with_transaction(
+                json!({ "share_account": address.to_string(), "option_index": option_index }),
+                ctx.send(&[ix])?,
+            ))
+        }
+        ShareCommand::Claim {
+            market,
+            share_account_id,
+            option,
+        } => {
+            let address = share_account_address(&ctx.pubkey(), &market, share_account_id).0;
+            let share_account: ShareAccount = ctx.fetch(&address)?;
+            let option_index = share_account.revealed_option.or(option).ok_or_else(|| {
+                CliError::InvalidArgument(format!("share account {address} is not revealed, pass --option"))
+            })?;
+            let account: OpportunityMarket = ctx.fetch(&market)?;
+            let token_program = ctx.token_program(&account.reward_mint)?;
+            let (owner_token_account, create_ata) =
+                ctx.token_account(&ctx.pubkey(), &account.reward_mint, &token_program);
+            let ix = close_share_account(CloseShareAccountParams {
+                owner: ctx.pubkey(),
+                market,
+                reward_mint: account.reward_mint,
+                owner_token_account,
+                token_program,
+                option_index,
+                share_account_id,
+            });
+            Ok(with_transaction(
+                json!({ "share_account": address.to_string(), "option_index": option_index }),
+                ctx.send(&[create_ata, ix])?,
+            ))
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, AccountDeserialize};
+use anchor_spl::associated_token::{
+    get_associated_token_address_with_program_id,
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+};
+use arcium_client::idl::arcium::{accounts::MXEAccount, types::SetUnset};
+use opportunity_market_client::{
+    accounts::deserialize_account,
+    arcium::{mxe_address, ArciumConfig},
+    x25519::{Cipher, X25519Keypair},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::error::CliError;
+
+/// Message signed to derive the x25519 keypair when no `--x25519-keypair` is given.
+/// Same as the demo app, so the CLI and a browser wallet share encrypted token accounts.
+pub const X25519_SIGN_MESSAGE: &[u8] = b"Sign this message to generate an encryption keypair for secure voting";
+
+/// x25519 keypair file, as written by the scripts (`scripts/example-x25519-keypair.json`)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct X25519KeypairFile {
+    secret_key: Vec<u8>,
+}
+
+pub struct Context {
+    pub rpc: RpcClient,
+    pub signer: Keypair,
+    pub dry_run: bool,
+    cluster_offset: u32,
+    x25519_keypair: Option<PathBuf>,
+}
+
+impl Context {
+    pub fn new(
+        url: &str,
+        keypair: &Path,
+        x25519_keypair: Option<PathBuf>,
+        cluster_offset: u32,
+        dry_run: bool,
+    ) -> Result<Self, CliError> {
+        let signer = read_keypair_file(keypair)
+            .map_err(|err| CliError::InvalidFile(keypair.display().to_string(), err.to_string()))?;
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            signer,
+            dry_run,
+            cluster_offset,
+            x25519_keypair,
+        })
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    /// Arcium accounts for a new computation, under a random computation offset
+    pub fn arcium_config(&self) -> ArciumConfig {
+        ArciumConfig {
+            cluster_offset: self.cluster_offset,
+            computation_offset: rand::random(),
+        }
+    }
+
+    /// The signer's x25519 keypair, read from `--x25519-keypair` or derived from a signature
+    pub fn x25519(&self) -> Result<X25519Keypair, CliError> {
+        match &self.x25519_keypair {
+            Some(path) => read_x25519_keypair(path),
+            None => Ok(X25519Keypair::from_signature(
+                self.signer.sign_message(X25519_SIGN_MESSAGE).as_ref(),
+            )),
+        }
+    }
+
+    /// Cipher shared between the signer's x25519 key and the MXE
+    pub fn cipher(&self) -> Result<Cipher, CliError> {
+        let mxe: MXEAccount = self.fetch(&mxe_address())?;
+        let SetUnset::Set(keys) = mxe.utility_pubkeys else {
+            return Err(CliError::MxeKeyUnavailable);
+        };
+        Ok(Cipher::new(&self.x25519()?.secret_key, &keys.x25519_pubkey)?)
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T, CliError> {
+        self.fetch_optional(address)?.ok_or(CliError::AccountNotFound(*address))
+    }
+
+    pub fn fetch_optional<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>, CliError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value;
+        Ok(account.map(|account| deserialize_account(&account.data)).transpose()?)
+    }
+
+    pub fn exists(&self, address: &Pubkey) -> Result<bool, CliError> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .is_some())
+    }
+
+    /// Token program owning `mint`, either SPL Token or Token-2022
+    pub fn token_program(&self, mint: &Pubkey) -> Result<Pubkey, CliError> {
+        let account = self.rpc.get_account_with_commitment(mint, self.rpc.commitment())?.value;
+        Ok(account.ok_or(CliError::AccountNotFound(*mint))?.owner)
+    }
+
+    /// Associated token account of `owner`, plus an instruction creating it if missing
+    pub fn token_account(&self, owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> (Pubkey, Instruction) {
+        (
+            get_associated_token_address_with_program_id(owner, mint, token_program),
+            create_associated_token_account_idempotent(&self.pubkey(), owner, mint, token_program),
+        )
+    }
+
+    /// Signs and sends `instructions` in one transaction, or only simulates it with `--dry-run`
+    pub fn send(&self, instructions: &[Instruction]) -> Result<Value, CliError> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&self.pubkey()), &[&self.signer], blockhash);
+
+        if self.dry_run {
+            let result = self.rpc.simulate_transaction(&transaction)?.value;
+            return Ok(json!({
+                "dry_run": true,
+                "err": result.err.map(|err| err.to_string()),
+                "units_consumed": result.units_consumed,
+                "logs": result.logs.unwrap_or_default(),
+            }));
+        }
+
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(json!({ "signature": signature.to_string() }))
+    }
+}
+
+/// Solana CLI default keypair, `~/.config/solana/id.json`
+pub fn default_keypair_path() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config/solana/id.json")
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let contents = fs::read_to_string(path).map_err(|err| CliError::Io(path.display().to_string(), err))?;
+    serde_json::from_str(&contents).map_err(|err| CliError::InvalidFile(path.display().to_string(), err.to_string()))
+}
+
+pub fn read_x25519_keypair(path: &Path) -> Result<X25519Keypair, CliError> {
+    let file: X25519KeypairFile = read_json(path)?;
+    let secret_key = file
+        .secret_key
+        .try_into()
+        .map_err(|_| CliError::InvalidFile(path.display().to_string(), "secretKey must be 32 bytes".into()))?;
+    Ok(X25519Keypair::from_secret_key(secret_key))
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
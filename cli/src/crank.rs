@@ -0,0 +1,288 @@
+//! Drives a market's share accounts through `sync_option_tally` during the staking period, and
+//! through `reveal_shares` and `increment_option_tally` during the reveal period, so the running
+//! tallies stay current and stakers don't lose their reward by forgetting a step. All three
+//! instructions are permissionless. Closing the share account still needs the owner.
+
+use std::collections::{HashMap, HashSet};
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use opportunity_market_client::{
+    accounts::{deserialize_share_account, OpportunityMarket, ShareAccount},
+    instructions::{
+        increment_option_tally, reveal_shares, sync_option_tally, IncrementOptionTallyParams, RevealSharesParams,
+        SyncOptionTallyParams,
+    },
+    pda::{encrypted_token_account_address, share_account_address},
+    OPPORTUNITY_MARKET_PROGRAM_ID,
+};
+use serde_json::{json, Value};
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+use crate::context::{unix_now, Context};
+use crate::error::CliError;
+
+/// Offset of `ShareAccount::market`, after the discriminator, encrypted state, nonce, bump and owner
+const SHARE_ACCOUNT_MARKET_OFFSET: usize = 8 + 32 * 3 + 16 + 1 + 32;
+
+/// Share account ids are picked by the owner and not stored on chain, so they are recovered by
+/// deriving addresses up to this id
+pub const MAX_SHARE_ACCOUNT_ID: u32 = 1024;
+
+/// Tallies sent per transaction, reveals queue a computation each and go one per transaction
+const TALLY_BATCH_SIZE: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrankAction {
+    Sync,
+    Reveal,
+    Tally { option_index: u16 },
+}
+
+/// Next step for a share account, `None` once it is synced or tallied, while a callback is
+/// pending or if it never staked
+pub fn next_action(share_account: &ShareAccount, staking: bool) -> Option<CrankAction> {
+    if share_account.locked || share_account.staked_at_timestamp.is_none() {
+        return None;
+    }
+    if staking {
+        return share_account.tally_pending.then_some(CrankAction::Sync);
+    }
+    match share_account.revealed_option {
+        None => Some(CrankAction::Reveal),
+        Some(option_index) if !share_account.total_incremented => Some(CrankAction::Tally { option_index }),
+        Some(_) => None,
+    }
+}
+
+pub struct PendingShareAccount {
+    pub address: Pubkey,
+    pub owner: Pubkey,
+    pub share_account_id: u32,
+    pub action: CrankAction,
+}
+
+/// Every share account of `market` that still needs a sync while `staking`, or a reveal or tally
+pub fn pending_share_accounts(
+    ctx: &Context,
+    market: &Pubkey,
+    staking: bool,
+) -> Result<Vec<PendingShareAccount>, CliError> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, ShareAccount::DISCRIMINATOR)),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(SHARE_ACCOUNT_MARKET_OFFSET, market.as_ref())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            commitment: Some(ctx.rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = ctx
+        .rpc
+        .get_program_accounts_with_config(&OPPORTUNITY_MARKET_PROGRAM_ID, config)?;
+
+    let mut pending = Vec::new();
+    for (address, account) in accounts {
+        let share_account = deserialize_share_account(&account.data)?;
+        if let Some(action) = next_action(&share_account, staking) {
+            pending.push((address, share_account.owner, action));
+        }
+    }
+
+    let ids = share_account_ids(market, pending.iter().map(|(address, owner, _)| (*address, *owner)));
+    Ok(pending
+        .into_iter()
+        .filter_map(|(address, owner, action)| {
+            ids.get(&address).map(|&share_account_id| PendingShareAccount {
+                address,
+                owner,
+                share_account_id,
+                action,
+            })
+        })
+        .collect())
+}
+
+/// Recovers the id of each share account from its address. Accounts with an id above
+/// `MAX_SHARE_ACCOUNT_ID` are left out.
+pub fn share_account_ids(
+    market: &Pubkey,
+    accounts: impl IntoIterator<Item = (Pubkey, Pubkey)>,
+) -> HashMap<Pubkey, u32> {
+    let mut by_owner: HashMap<Pubkey, HashSet<Pubkey>> = HashMap::new();
+    for (address, owner) in accounts {
+        by_owner.entry(owner).or_default().insert(address);
+    }
+
+    let mut ids = HashMap::new();
+    for (owner, mut remaining) in by_owner {
+        for share_account_id in 0..=MAX_SHARE_ACCOUNT_ID {
+            if remaining.is_empty() {
+                break;
+            }
+            let address = share_account_address(&owner, market, share_account_id).0;
+            if remaining.remove(&address) {
+                ids.insert(address, share_account_id);
+            }
+        }
+    }
+    ids
+}
+
+/// One crank pass over `market`. Reveals land through MPC callbacks, so their tallies are sent
+/// on a later pass.
+pub fn crank_market(ctx: &Context, market_address: &Pubkey) -> Result<Value, CliError> {
+    let market: OpportunityMarket = ctx.fetch(market_address)?;
+    let now = unix_now();
+    let report = |status: &str| json!({ "market": market_address.to_string(), "status": status });
+
+    let Some(open_timestamp) = market.open_timestamp else {
+        return Ok(report("not_open"));
+    };
+    let reveal_start = open_timestamp + market.time_to_stake;
+    let reveal_end = reveal_start + market.time_to_reveal;
+    if market.cancelled {
+        return Ok(report("cancelled"));
+    }
+    if now < reveal_start {
+        return sync_market(ctx, market_address, reveal_start);
+    }
+    if now >= reveal_end {
+        return Ok(report("reveal_ended"));
+    }
+    // Slashing markets only reveal once the winners are known
+    if market.losing_stake_slash_bps > 0 && market.selected_option.is_none() {
+        return Ok(report("awaiting_selection"));
+    }
+
+    let token_program = ctx.token_program(&market.mint)?;
+    let pending = pending_share_accounts(ctx, market_address, false)?;
+    let mut revealed = Vec::new();
+    let mut tallied = Vec::new();
+    let mut failed = Vec::new();
+
+    for share in pending.iter().filter(|share| share.action == CrankAction::Reveal) {
+        let ix = reveal_shares(
+            RevealSharesParams {
+                signer: ctx.pubkey(),
+                owner: share.owner,
+                market: *market_address,
+                user_eta: encrypted_token_account_address(&market.mint, &share.owner).0,
+                share_account_id: share.share_account_id,
+                token_mint: market.mint,
+                reward_mint: market.reward_mint,
+                token_program,
+                // The selected option is encrypted to the owner, so the tally follows on a later pass
+                tally_option_index: None,
+            },
+            ctx.arcium_config(),
+        );
+        match outcome(ctx.send(&[ix])) {
+            Ok(()) => revealed.push(share.address.to_string()),
+            Err(error) => failed.push(json!({ "share_account": share.address.to_string(), "error": error })),
+        }
+    }
+
+    let tallies: Vec<_> = pending
+        .iter()
+        .filter_map(|share| match share.action {
+            CrankAction::Tally { option_index } => Some((share, option_index)),
+            CrankAction::Sync | CrankAction::Reveal => None,
+        })
+        .collect();
+    for batch in tallies.chunks(TALLY_BATCH_SIZE) {
+        let ixs: Vec<_> = batch
+            .iter()
+            .map(|(share, option_index)| {
+                increment_option_tally(IncrementOptionTallyParams {
+                    signer: ctx.pubkey(),
+                    owner: share.owner,
+                    market: *market_address,
+                    option_index: *option_index,
+                    share_account_id: share.share_account_id,
+                })
+            })
+            .collect();
+        let addresses = batch.iter().map(|(share, _)| share.address.to_string());
+        match outcome(ctx.send(&ixs)) {
+            Ok(()) => tallied.extend(addresses),
+            Err(error) => failed.extend(addresses.map(|address| json!({ "share_account": address, "error": error }))),
+        }
+    }
+
+    Ok(json!({
+        "market": market_address.to_string(),
+        "status": "revealing",
+        "reveal_end": reveal_end,
+        "revealed": revealed,
+        "tallied": tallied,
+        "failed": failed,
+    }))
+}
+
+/// Moves pending stakes into the market's running tallies. Each sync queues a computation and
+/// locks the tallies until its callback, so only one is sent per pass.
+fn sync_market(ctx: &Context, market_address: &Pubkey, reveal_start: u64) -> Result<Value, CliError> {
+    let pending = pending_share_accounts(ctx, market_address, true)?;
+    let mut synced = Vec::new();
+    let mut failed = Vec::new();
+
+    if let Some(share) = pending.first() {
+        let ix = sync_option_tally(
+            SyncOptionTallyParams {
+                signer: ctx.pubkey(),
+                owner: share.owner,
+                market: *market_address,
+                share_account_id: share.share_account_id,
+            },
+            ctx.arcium_config(),
+        );
+        match outcome(ctx.send(&[ix])) {
+            Ok(()) => synced.push(share.address.to_string()),
+            Err(error) => failed.push(json!({ "share_account": share.address.to_string(), "error": error })),
+        }
+    }
+
+    Ok(json!({
+        "market": market_address.to_string(),
+        "status": "staking",
+        "reveal_start": reveal_start,
+        "synced": synced,
+        "pending_syncs": pending.len().saturating_sub(synced.len()),
+        "failed": failed,
+    }))
+}
+
+/// Sent transactions and successful simulations are ok, anything else is reported and skipped
+fn outcome(sent: Result<Value, CliError>) -> Result<(), String> {
+    match sent {
+        Ok(result) => match result.get("err") {
+            Some(Value::Null) | None => Ok(()),
+            Some(err) => Err(err.to_string()),
+        },
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_share_account_ids() {
+        let market = Pubkey::new_unique();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let accounts = [(alice, 0), (alice, 7), (bob, 3), (bob, MAX_SHARE_ACCOUNT_ID + 1)]
+            .map(|(owner, id)| (share_account_address(&owner, &market, id).0, owner));
+
+        let ids = share_account_ids(&market, accounts);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[&accounts[0].0], 0);
+        assert_eq!(ids[&accounts[1].0], 7);
+        assert_eq!(ids[&accounts[2].0], 3);
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use opportunity_market_client::ClientError;
+use solana_client::client_error::ClientError as RpcError;
+
+#[derive(Debug)]
+pub enum CliError {
+    // Keypair or config file could not be read
+    Io(String, std::io::Error),
+
+    // Config or keypair file is not valid JSON of the expected shape
+    InvalidFile(String, String),
+
+    // Argument missing from both the command line and the config file, or out of range
+    InvalidArgument(String),
+
+    // RPC request or transaction submission failed
+    Rpc(Box<RpcError>),
+
+    // Expected account does not exist
+    AccountNotFound(Pubkey),
+
+    // Account deserialization or encryption failed
+    Client(ClientError),
+
+    // MXE account has no x25519 key set yet, so inputs cannot be encrypted for it
+    MxeKeyUnavailable,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(path, err) => write!(f, "Failed to read {path}: {err}"),
+            CliError::InvalidFile(path, reason) => write!(f, "Invalid file {path}: {reason}"),
+            CliError::InvalidArgument(reason) => write!(f, "Invalid argument: {reason}"),
+            CliError::Rpc(err) => write!(f, "RPC error: {err}"),
+            CliError::AccountNotFound(address) => write!(f, "Account not found: {address}"),
+            CliError::Client(err) => write!(f, "{err}"),
+            CliError::MxeKeyUnavailable => write!(f, "MXE x25519 public key is not set"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<RpcError> for CliError {
+    fn from(err: RpcError) -> Self {
+        CliError::Rpc(Box::new(err))
+    }
+}
+
+impl From<ClientError> for CliError {
+    fn from(err: ClientError) -> Self {
+        CliError::Client(err)
+    }
+}
@@ -0,0 +1,8 @@
+//! Command-line tools for the `opportunity_market` program: the `opportunity-market` operator CLI
+//! and the `opportunity-market-crank` reveal and tally crank.
+
+pub mod commands;
+pub mod context;
+pub mod crank;
+pub mod error;
+pub mod output;
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+
+use opportunity_market_cli::{
+    commands::{
+        self, central_state::CentralStateCommand, eta::EtaCommand, market::MarketCommand, option::OptionCommand,
+        share::ShareCommand,
+    },
+    context::{default_keypair_path, Context},
+    error::CliError,
+    output,
+};
+
+#[derive(Parser)]
+#[command(about = "Operates opportunity_market markets from the command line")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, env = "RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Solana keypair signing and paying for transactions, defaults to ~/.config/solana/id.json
+    #[arg(long, env = "DEPLOYER_KEYPAIR_PATH")]
+    keypair: Option<PathBuf>,
+
+    /// x25519 keypair file (`{ "secretKey": [..], "publicKey": [..] }`). Derived from a
+    /// signature of the Solana keypair by default, matching the demo app.
+    #[arg(long)]
+    x25519_keypair: Option<PathBuf>,
+
+    /// Arcium cluster computations are queued on
+    #[arg(long, env = "ARCIUM_CLUSTER_OFFSET", default_value_t = 456)]
+    cluster_offset: u32,
+
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand)]
+    CentralState(CentralStateCommand),
+
+    #[command(subcommand)]
+    Market(MarketCommand),
+
+    #[command(subcommand)]
+    Option(OptionCommand),
+
+    /// Encrypted token accounts
+    #[command(subcommand)]
+    Eta(EtaCommand),
+
+    #[command(flatten)]
+    Share(ShareCommand),
+}
+
+fn run(cli: Cli) -> Result<Value, CliError> {
+    let keypair = cli.keypair.unwrap_or_else(default_keypair_path);
+    let ctx = Context::new(&cli.url, &keypair, cli.x25519_keypair, cli.cluster_offset, cli.dry_run)?;
+    match cli.command {
+        Command::CentralState(command) => commands::central_state::run(&ctx, command),
+        Command::Market(command) => commands::market::run(&ctx, command),
+        Command::Option(command) => commands::option::run(&ctx, command),
+        Command::Eta(command) => commands::eta::run(&ctx, command),
+        Command::Share(command) => commands::share::run(&ctx, command),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+    match run(cli) {
+        Ok(result) => {
+            output::print(&result, json);
+            // A failed `--dry-run` simulation still prints its logs
+            match result.get("err") {
+                Some(Value::Null) | None => ExitCode::SUCCESS,
+                Some(_) => ExitCode::FAILURE,
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use serde_json::Value;
+
+/// Prints a command result, as pretty JSON with `--json` or as `key: value` lines otherwise
+pub fn print(value: &Value, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                match value {
+                    // Simulation logs and market options read better one per line
+                    Value::Array(items) if !items.is_empty() => {
+                        println!("{key}:");
+                        for item in items {
+                            println!("  {}", text(item));
+                        }
+                    }
+                    value => println!("{key}: {}", text(value)),
+                }
+            }
+        }
+        value => println!("{}", text(value)),
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
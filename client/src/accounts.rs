@@ -0,0 +1,58 @@
+use anchor_lang::AccountDeserialize;
+
+pub use opportunity_market::{
+    CentralState, EncryptedTokenAccount, MarketParams, MarketSponsor, MarketTemplate, OpportunityMarket,
+    OpportunityMarketOption, OpportunityMarketShare, OptionTallies, ScoreTier, ScoringConfig, ShareAccount, StakeDelegation, TokenVault,
+    WinningOption, MAX_TALLIED_OPTIONS,
+};
+
+use crate::error::ClientError;
+
+/// Deserializes raw account data, checking the account discriminator.
+pub fn deserialize_account<T: AccountDeserialize>(data: &[u8]) -> Result<T, ClientError> {
+    T::try_deserialize(&mut &data[..]).map_err(|_| ClientError::InvalidAccountData)
+}
+
+pub fn deserialize_central_state(data: &[u8]) -> Result<CentralState, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_token_vault(data: &[u8]) -> Result<TokenVault, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_opportunity_market(data: &[u8]) -> Result<OpportunityMarket, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_market_template(data: &[u8]) -> Result<MarketTemplate, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_market_sponsor(data: &[u8]) -> Result<MarketSponsor, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_opportunity_market_option(data: &[u8]) -> Result<OpportunityMarketOption, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_opportunity_market_share(data: &[u8]) -> Result<OpportunityMarketShare, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_encrypted_token_account(data: &[u8]) -> Result<EncryptedTokenAccount, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_share_account(data: &[u8]) -> Result<ShareAccount, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_option_tallies(data: &[u8]) -> Result<OptionTallies, ClientError> {
+    deserialize_account(data)
+}
+
+pub fn deserialize_stake_delegation(data: &[u8]) -> Result<StakeDelegation, ClientError> {
+    deserialize_account(data)
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::Pubkey;
+use arcium_client::pda::{
+    clock_acc, cluster_acc, comp_def_offset, computation_acc, computation_definition_acc, execpool_acc,
+    fee_pool_acc, mempool_acc, mxe_acc, mxe_lut_acc, signer_acc,
+};
+use opportunity_market::ID;
+
+pub use arcium_client::ARCIUM_PROGRAM_ID;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArciumConfig {
+    pub cluster_offset: u32,
+    pub computation_offset: u64,
+}
+
+/// Arcium accounts shared by every instruction that queues an MPC computation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeAccounts {
+    pub sign_pda_account: Pubkey,
+    pub mxe_account: Pubkey,
+    pub mempool_account: Pubkey,
+    pub executing_pool: Pubkey,
+    pub computation_account: Pubkey,
+    pub comp_def_account: Pubkey,
+    pub cluster_account: Pubkey,
+    pub pool_account: Pubkey,
+    pub clock_account: Pubkey,
+    pub computation_offset: u64,
+}
+
+pub fn get_compute_accounts(circuit_name: &str, config: ArciumConfig) -> ComputeAccounts {
+    let ArciumConfig { cluster_offset, computation_offset } = config;
+    ComputeAccounts {
+        sign_pda_account: signer_acc(&ID),
+        mxe_account: mxe_acc(&ID),
+        mempool_account: mempool_acc(cluster_offset),
+        executing_pool: execpool_acc(cluster_offset),
+        computation_account: computation_acc(cluster_offset, computation_offset),
+        comp_def_account: comp_def_address(circuit_name),
+        cluster_account: cluster_acc(cluster_offset),
+        pool_account: fee_pool_acc(),
+        clock_account: clock_acc(),
+        computation_offset,
+    }
+}
+
+pub fn comp_def_address(circuit_name: &str) -> Pubkey {
+    computation_definition_acc(&ID, comp_def_offset(circuit_name))
+}
+
+pub fn mxe_address() -> Pubkey {
+    mxe_acc(&ID)
+}
+
+/// MXE address lookup table, `lut_offset_slot` is read from the MXE account
+pub fn mxe_lut_address(lut_offset_slot: u64) -> Pubkey {
+    mxe_lut_acc(&ID, lut_offset_slot)
+}
@@ -0,0 +1,25 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientError {
+    // Bytes are not a valid x25519 public key
+    InvalidX25519PublicKey,
+
+    // Ciphertext is not a canonical base field element
+    InvalidCiphertext,
+
+    // Account data could not be deserialized as the requested account type
+    InvalidAccountData,
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidX25519PublicKey => write!(f, "Invalid x25519 public key"),
+            ClientError::InvalidCiphertext => write!(f, "Invalid ciphertext"),
+            ClientError::InvalidAccountData => write!(f, "Invalid account data"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
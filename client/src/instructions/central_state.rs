@@ -0,0 +1,93 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use opportunity_market::{accounts, instruction};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::pda::{central_state_address, token_vault_address};
+
+pub struct InitCentralStateParams {
+    pub payer: Pubkey,
+    pub earliness_cutoff_seconds: u64,
+    pub min_option_deposit: u64,
+    pub reward_reclaim_grace_seconds: u64,
+    pub protocol_fee_bps: u16,
+    pub treasury: Pubkey,
+}
+
+pub fn init_central_state(input: InitCentralStateParams) -> Instruction {
+    build(
+        accounts::InitCentralState {
+            payer: input.payer,
+            central_state: central_state_address().0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitCentralState {
+            earliness_cutoff_seconds: input.earliness_cutoff_seconds,
+            min_option_deposit: input.min_option_deposit,
+            reward_reclaim_grace_seconds: input.reward_reclaim_grace_seconds,
+            protocol_fee_bps: input.protocol_fee_bps,
+            treasury: input.treasury,
+        },
+    )
+}
+
+pub struct UpdateCentralStateParams {
+    pub authority: Pubkey,
+    pub earliness_cutoff_seconds: u64,
+    pub min_option_deposit: u64,
+    pub reward_reclaim_grace_seconds: u64,
+    pub protocol_fee_bps: u16,
+    pub treasury: Pubkey,
+}
+
+pub fn update_central_state(input: UpdateCentralStateParams) -> Instruction {
+    build(
+        accounts::UpdateCentralState {
+            authority: input.authority,
+            central_state: central_state_address().0,
+        },
+        instruction::UpdateCentralState {
+            earliness_cutoff_seconds: input.earliness_cutoff_seconds,
+            min_option_deposit: input.min_option_deposit,
+            reward_reclaim_grace_seconds: input.reward_reclaim_grace_seconds,
+            protocol_fee_bps: input.protocol_fee_bps,
+            treasury: input.treasury,
+        },
+    )
+}
+
+pub struct TransferCentralStateAuthorityParams {
+    pub authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+pub fn transfer_central_state_authority(input: TransferCentralStateAuthorityParams) -> Instruction {
+    build(
+        accounts::TransferCentralStateAuthority {
+            authority: input.authority,
+            central_state: central_state_address().0,
+        },
+        instruction::TransferCentralStateAuthority {
+            new_authority: input.new_authority,
+        },
+    )
+}
+
+pub struct InitTokenVaultParams {
+    /// The signer/payer for the transaction
+    pub payer: Pubkey,
+    /// Address that can withdraw tokens from the vault
+    pub fund_manager: Pubkey,
+}
+
+pub fn init_token_vault(input: InitTokenVaultParams) -> Instruction {
+    build(
+        accounts::InitTokenVault {
+            payer: input.payer,
+            token_vault: token_vault_address().0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitTokenVault {
+            fund_manager: input.fund_manager,
+        },
+    )
+}
@@ -0,0 +1,99 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use arcium_anchor::LUT_PROGRAM_ID;
+use opportunity_market::{accounts, instruction};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::arcium::{comp_def_address, mxe_address, mxe_lut_address, ARCIUM_PROGRAM_ID};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompDefCircuit {
+    WrapEncryptedTokens,
+    UnwrapEncryptedTokens,
+    TransferEncryptedTokens,
+    BuyOpportunityMarketShares,
+    RevealShares,
+    UnstakeEarly,
+    AddOptionStake,
+    CloseEphemeralEncryptedTokenAccount,
+    IncreaseStake,
+    DiscloseOptionTallies,
+    SyncOptionTally,
+    DelegatedBuyOpportunityMarketShares,
+    DelegatedAddOptionStake,
+}
+
+pub const ALL_COMP_DEF_CIRCUITS: [CompDefCircuit; 13] = [
+    CompDefCircuit::WrapEncryptedTokens,
+    CompDefCircuit::UnwrapEncryptedTokens,
+    CompDefCircuit::TransferEncryptedTokens,
+    CompDefCircuit::BuyOpportunityMarketShares,
+    CompDefCircuit::RevealShares,
+    CompDefCircuit::UnstakeEarly,
+    CompDefCircuit::AddOptionStake,
+    CompDefCircuit::CloseEphemeralEncryptedTokenAccount,
+    CompDefCircuit::IncreaseStake,
+    CompDefCircuit::DiscloseOptionTallies,
+    CompDefCircuit::SyncOptionTally,
+    CompDefCircuit::DelegatedBuyOpportunityMarketShares,
+    CompDefCircuit::DelegatedAddOptionStake,
+];
+
+impl CompDefCircuit {
+    pub fn name(self) -> &'static str {
+        match self {
+            CompDefCircuit::WrapEncryptedTokens => "wrap_encrypted_tokens",
+            CompDefCircuit::UnwrapEncryptedTokens => "unwrap_encrypted_tokens",
+            CompDefCircuit::TransferEncryptedTokens => "transfer_encrypted_tokens",
+            CompDefCircuit::BuyOpportunityMarketShares => "buy_opportunity_market_shares",
+            CompDefCircuit::RevealShares => "reveal_shares",
+            CompDefCircuit::UnstakeEarly => "unstake_early",
+            CompDefCircuit::AddOptionStake => "add_option_stake",
+            CompDefCircuit::CloseEphemeralEncryptedTokenAccount => "close_ephemeral_encrypted_token_account",
+            CompDefCircuit::IncreaseStake => "increase_stake",
+            CompDefCircuit::DiscloseOptionTallies => "disclose_option_tallies",
+            CompDefCircuit::SyncOptionTally => "sync_option_tally",
+            CompDefCircuit::DelegatedBuyOpportunityMarketShares => "delegated_buy_opportunity_market_shares",
+            CompDefCircuit::DelegatedAddOptionStake => "delegated_add_option_stake",
+        }
+    }
+}
+
+macro_rules! comp_def_ix {
+    ($name:ident, $payer:expr, $circuit:expr, $lut:expr) => {
+        build(
+            accounts::$name {
+                payer: $payer,
+                mxe_account: mxe_address(),
+                comp_def_account: comp_def_address($circuit.name()),
+                address_lookup_table: $lut,
+                lut_program: LUT_PROGRAM_ID,
+                arcium_program: ARCIUM_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            },
+            instruction::$name {},
+        )
+    };
+}
+
+/// Initializes the computation definition of `circuit`.
+/// `lut_offset_slot` is read from the MXE account.
+pub fn init_comp_def(payer: Pubkey, circuit: CompDefCircuit, lut_offset_slot: u64) -> Instruction {
+    let lut = mxe_lut_address(lut_offset_slot);
+    match circuit {
+        CompDefCircuit::WrapEncryptedTokens => comp_def_ix!(WrapEncryptedTokensCompDef, payer, circuit, lut),
+        CompDefCircuit::UnwrapEncryptedTokens => comp_def_ix!(UnwrapEncryptedTokensCompDef, payer, circuit, lut),
+        CompDefCircuit::TransferEncryptedTokens => comp_def_ix!(TransferEncryptedTokensCompDef, payer, circuit, lut),
+        CompDefCircuit::BuyOpportunityMarketShares => comp_def_ix!(BuyOpportunityMarketSharesCompDef, payer, circuit, lut),
+        CompDefCircuit::RevealShares => comp_def_ix!(RevealSharesCompDef, payer, circuit, lut),
+        CompDefCircuit::UnstakeEarly => comp_def_ix!(UnstakeEarlyCompDef, payer, circuit, lut),
+        CompDefCircuit::AddOptionStake => comp_def_ix!(AddOptionStakeCompDef, payer, circuit, lut),
+        CompDefCircuit::CloseEphemeralEncryptedTokenAccount => comp_def_ix!(CloseEphemeralEncryptedTokenAccountCompDef, payer, circuit, lut),
+        CompDefCircuit::IncreaseStake => comp_def_ix!(IncreaseStakeCompDef, payer, circuit, lut),
+        CompDefCircuit::DiscloseOptionTallies => comp_def_ix!(DiscloseOptionTalliesCompDef, payer, circuit, lut),
+        CompDefCircuit::SyncOptionTally => comp_def_ix!(SyncOptionTallyCompDef, payer, circuit, lut),
+        CompDefCircuit::DelegatedBuyOpportunityMarketShares => {
+            comp_def_ix!(DelegatedBuyOpportunityMarketSharesCompDef, payer, circuit, lut)
+        }
+        CompDefCircuit::DelegatedAddOptionStake => comp_def_ix!(DelegatedAddOptionStakeCompDef, payer, circuit, lut),
+    }
+}
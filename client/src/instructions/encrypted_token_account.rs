@@ -0,0 +1,259 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use opportunity_market::{accounts, instruction};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::arcium::{get_compute_accounts, ArciumConfig, ARCIUM_PROGRAM_ID};
+use crate::pda::{
+    encrypted_token_account_address, encrypted_token_account_address_with_index, token_vault_address,
+    token_vault_token_address,
+};
+
+pub struct InitEncryptedTokenAccountParams {
+    /// The signer/payer for the transaction
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    /// User's x25519 public key for encryption
+    pub user_pubkey: [u8; 32],
+    /// Random u128 nonce for initial encrypted state
+    pub state_nonce: u128,
+}
+
+pub fn init_encrypted_token_account(input: InitEncryptedTokenAccountParams) -> Instruction {
+    build(
+        accounts::InitEncryptedTokenAccount {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            encrypted_token_account: encrypted_token_account_address(&input.token_mint, &input.signer).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitEncryptedTokenAccount {
+            user_pubkey: input.user_pubkey,
+            state_nonce: input.state_nonce,
+        },
+    )
+}
+
+pub struct InitEphemeralEncryptedTokenAccountParams {
+    /// The signer/payer for the transaction (permissionless - anyone can call)
+    pub signer: Pubkey,
+    /// The owner of the ETA (does not need to sign)
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    /// The index for the ephemeral ETA (must be non-zero)
+    pub index: u64,
+    /// Random u128 nonce for initial encrypted state
+    pub state_nonce: u128,
+}
+
+pub fn init_ephemeral_encrypted_token_account(input: InitEphemeralEncryptedTokenAccountParams) -> Instruction {
+    build(
+        accounts::InitEphemeralEncryptedTokenAccount {
+            signer: input.signer,
+            owner: input.owner,
+            token_mint: input.token_mint,
+            source_encrypted_token_account: encrypted_token_account_address(&input.token_mint, &input.owner).0,
+            ephemeral_encrypted_token_account: encrypted_token_account_address_with_index(
+                &input.token_mint,
+                &input.owner,
+                input.index,
+            )
+            .0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitEphemeralEncryptedTokenAccount {
+            index: input.index,
+            state_nonce: input.state_nonce,
+        },
+    )
+}
+
+pub struct ClaimPendingDepositParams {
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub signer_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn claim_pending_deposit(input: ClaimPendingDepositParams) -> Instruction {
+    build(
+        accounts::ClaimPendingDeposit {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            encrypted_token_account: input.encrypted_token_account,
+            token_vault: token_vault_address().0,
+            token_vault_ata: token_vault_token_address(&input.token_mint, &input.token_program),
+            signer_token_account: input.signer_token_account,
+            token_program: input.token_program,
+        },
+        instruction::ClaimPendingDeposit {},
+    )
+}
+
+pub struct WrapEncryptedTokensParams {
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    /// The EncryptedTokenAccount to wrap tokens into
+    pub encrypted_token_account: Pubkey,
+    pub signer_token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+pub fn wrap_encrypted_tokens(input: WrapEncryptedTokensParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("wrap_encrypted_tokens", config);
+    build(
+        accounts::WrapEncryptedTokens {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            encrypted_token_account: input.encrypted_token_account,
+            signer_token_account: input.signer_token_account,
+            token_vault: token_vault_address().0,
+            token_vault_ata: token_vault_token_address(&input.token_mint, &input.token_program),
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            token_program: input.token_program,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::WrapEncryptedTokens {
+            computation_offset: arcium.computation_offset,
+            amount: input.amount,
+        },
+    )
+}
+
+pub struct UnwrapEncryptedTokensParams {
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    /// The EncryptedTokenAccount to unwrap tokens from
+    pub encrypted_token_account: Pubkey,
+    pub user_token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+pub fn unwrap_encrypted_tokens(input: UnwrapEncryptedTokensParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("unwrap_encrypted_tokens", config);
+    build(
+        accounts::UnwrapEncryptedTokens {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            encrypted_token_account: input.encrypted_token_account,
+            token_vault: token_vault_address().0,
+            token_vault_ata: token_vault_token_address(&input.token_mint, &input.token_program),
+            user_token_account: input.user_token_account,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            token_program: input.token_program,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::UnwrapEncryptedTokens {
+            computation_offset: arcium.computation_offset,
+            amount: input.amount,
+        },
+    )
+}
+
+pub struct TransferEncryptedTokensParams {
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    /// The signer's EncryptedTokenAccount to debit
+    pub sender_encrypted_token_account: Pubkey,
+    /// The EncryptedTokenAccount to credit
+    pub recipient_encrypted_token_account: Pubkey,
+    /// Transfer amount encrypted with the sender's x25519 key
+    pub amount_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+}
+
+pub fn transfer_encrypted_tokens(input: TransferEncryptedTokensParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("transfer_encrypted_tokens", config);
+    build(
+        accounts::TransferEncryptedTokens {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            sender_encrypted_token_account: input.sender_encrypted_token_account,
+            recipient_encrypted_token_account: input.recipient_encrypted_token_account,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::TransferEncryptedTokens {
+            computation_offset: arcium.computation_offset,
+            amount_ciphertext: input.amount_ciphertext,
+            input_nonce: input.input_nonce,
+        },
+    )
+}
+
+pub struct CloseEphemeralEncryptedTokenAccountParams {
+    /// Signer must be the ETA owner
+    pub signer: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_program: Pubkey,
+    /// Index of the ephemeral ETA to close (must be non-zero)
+    pub index: u64,
+    /// Address that will receive the rent lamports (must match rent_payer stored in the ephemeral ETA)
+    pub rent_recipient: Pubkey,
+}
+
+pub fn close_ephemeral_encrypted_token_account(
+    input: CloseEphemeralEncryptedTokenAccountParams,
+    config: ArciumConfig,
+) -> Instruction {
+    let arcium = get_compute_accounts("close_ephemeral_encrypted_token_account", config);
+    build(
+        accounts::CloseEphemeralEncryptedTokenAccount {
+            signer: input.signer,
+            token_mint: input.token_mint,
+            regular_encrypted_token_account: encrypted_token_account_address(&input.token_mint, &input.signer).0,
+            ephemeral_encrypted_token_account: encrypted_token_account_address_with_index(
+                &input.token_mint,
+                &input.signer,
+                input.index,
+            )
+            .0,
+            rent_recipient: input.rent_recipient,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            token_program: input.token_program,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::CloseEphemeralEncryptedTokenAccount {
+            computation_offset: arcium.computation_offset,
+            index: input.index,
+        },
+    )
+}
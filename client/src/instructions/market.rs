@@ -0,0 +1,586 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+};
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, ID as ASSOCIATED_TOKEN_PROGRAM_ID};
+use opportunity_market::{accounts, instruction, MarketParams, ScoringConfig, WinningOption};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::arcium::{get_compute_accounts, ArciumConfig, ARCIUM_PROGRAM_ID};
+use crate::pda::{
+    central_state_address, market_sponsor_address, market_template_address, market_token_address,
+    opportunity_market_address, opportunity_market_option_address, option_tallies_address, share_account_address,
+    template_market_address,
+};
+
+pub struct CreateMarketParams {
+    pub creator: Pubkey,
+    /// Mint staked in the market
+    pub token_mint: Pubkey,
+    /// Mint the reward is paid in, usually `token_mint`
+    pub reward_mint: Pubkey,
+    /// Token program of the reward mint
+    pub token_program: Pubkey,
+    pub market_index: u64,
+    pub reward_amount: u64,
+    pub time_to_stake: u64,
+    pub time_to_reveal: u64,
+    pub market_authority: Option<Pubkey>,
+    pub unstake_delay_seconds: u64,
+    pub authorized_reader_pubkey: [u8; 32],
+    pub allow_closing_early: bool,
+    /// Overrides the central state earliness cutoff for this market
+    pub earliness_cutoff_seconds: Option<u64>,
+    /// Earliness curve used for scoring
+    pub scoring_config: ScoringConfig,
+    /// Basis points of stake on non-winning options moved to the reward pool
+    pub losing_stake_slash_bps: u16,
+    /// Smallest amount the initial stake may carry, 0 for none (a stake of 0 is then accepted)
+    pub min_stake: u64,
+    /// Largest position a share account may hold, including top-ups
+    pub max_stake: Option<u64>,
+    /// Lets anyone open the market at this time through `activate_market` once it is funded
+    pub planned_open_timestamp: Option<u64>,
+}
+
+pub fn create_market(input: CreateMarketParams) -> Instruction {
+    let market = opportunity_market_address(&input.creator, input.market_index).0;
+    build(
+        accounts::CreateMarket {
+            creator: input.creator,
+            token_mint: input.token_mint,
+            reward_mint: input.reward_mint,
+            central_state: central_state_address().0,
+            market,
+            market_token_ata: market_token_address(&market, &input.reward_mint, &input.token_program),
+            option_tallies: option_tallies_address(&market).0,
+            system_program: SYSTEM_PROGRAM_ID,
+            token_program: input.token_program,
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        },
+        instruction::CreateMarket {
+            market_index: input.market_index,
+            reward_amount: input.reward_amount,
+            time_to_stake: input.time_to_stake,
+            time_to_reveal: input.time_to_reveal,
+            market_authority: input.market_authority,
+            unstake_delay_seconds: input.unstake_delay_seconds,
+            authorized_reader_pubkey: input.authorized_reader_pubkey,
+            allow_closing_early: input.allow_closing_early,
+            earliness_cutoff_seconds: input.earliness_cutoff_seconds,
+            scoring_config: input.scoring_config,
+            losing_stake_slash_bps: input.losing_stake_slash_bps,
+            min_stake: input.min_stake,
+            max_stake: input.max_stake,
+            planned_open_timestamp: input.planned_open_timestamp,
+        },
+    )
+}
+
+pub struct CreateMarketTemplateParams {
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub template_id: u64,
+    /// Applied to every market created from the template
+    pub params: MarketParams,
+}
+
+pub fn create_market_template(input: CreateMarketTemplateParams) -> Instruction {
+    build(
+        accounts::CreateMarketTemplate {
+            creator: input.creator,
+            token_mint: input.token_mint,
+            reward_mint: input.reward_mint,
+            central_state: central_state_address().0,
+            template: market_template_address(&input.creator, input.template_id).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::CreateMarketTemplate {
+            template_id: input.template_id,
+            params: input.params,
+        },
+    )
+}
+
+pub struct CreateMarketFromTemplateParams {
+    pub creator: Pubkey,
+    pub template_id: u64,
+    /// Must be the template's mints
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Token program of the reward mint
+    pub token_program: Pubkey,
+    /// The template's `next_series_index`, the market PDA is derived from it
+    pub series_index: u64,
+    /// Lets anyone open the market at this time through `activate_market` once it is funded
+    pub planned_open_timestamp: Option<u64>,
+}
+
+pub fn create_market_from_template(input: CreateMarketFromTemplateParams) -> Instruction {
+    let template = market_template_address(&input.creator, input.template_id).0;
+    let market = template_market_address(&template, input.series_index).0;
+    build(
+        accounts::CreateMarketFromTemplate {
+            creator: input.creator,
+            template,
+            token_mint: input.token_mint,
+            reward_mint: input.reward_mint,
+            central_state: central_state_address().0,
+            market,
+            market_token_ata: market_token_address(&market, &input.reward_mint, &input.token_program),
+            option_tallies: option_tallies_address(&market).0,
+            system_program: SYSTEM_PROGRAM_ID,
+            token_program: input.token_program,
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        },
+        instruction::CreateMarketFromTemplate {
+            planned_open_timestamp: input.planned_open_timestamp,
+        },
+    )
+}
+
+pub struct AddMarketOptionParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    pub source_eta: Pubkey,
+    pub option_index: u16,
+    pub share_account_id: u32,
+    pub name: String,
+    pub amount_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn add_market_option(input: AddMarketOptionParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("add_option_stake", config);
+    build(
+        accounts::AddMarketOption {
+            creator: input.creator,
+            market: input.market,
+            central_state: central_state_address().0,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            source_eta: input.source_eta,
+            share_account: share_account_address(&input.creator, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::AddMarketOption {
+            computation_offset: arcium.computation_offset,
+            option_index: input.option_index,
+            share_account_id: input.share_account_id,
+            name: input.name,
+            amount_ciphertext: input.amount_ciphertext,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
+
+pub struct AddMarketOptionAsCreatorParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    pub option_index: u16,
+    pub name: String,
+}
+
+pub fn add_market_option_as_creator(input: AddMarketOptionAsCreatorParams) -> Instruction {
+    build(
+        accounts::AddMarketOptionAsCreator {
+            creator: input.creator,
+            market: input.market,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::AddMarketOptionAsCreator {
+            option_index: input.option_index,
+            name: input.name,
+        },
+    )
+}
+
+pub struct CloseMarketOptionParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    /// Account that paid for the option, receives its rent
+    pub option_creator: Pubkey,
+    pub option_index: u16,
+}
+
+pub fn close_market_option(input: CloseMarketOptionParams) -> Instruction {
+    build(
+        accounts::CloseMarketOption {
+            creator: input.creator,
+            market: input.market,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            option_creator: input.option_creator,
+        },
+        instruction::CloseMarketOption {
+            option_index: input.option_index,
+        },
+    )
+}
+
+pub struct CloseMarketParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub creator_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn close_market(input: CloseMarketParams) -> Instruction {
+    build(
+        accounts::CloseMarket {
+            creator: input.creator,
+            market: input.market,
+            option_tallies: option_tallies_address(&input.market).0,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            creator_token_account: input.creator_token_account,
+            token_program: input.token_program,
+        },
+        instruction::CloseMarket {},
+    )
+}
+
+pub struct CancelMarketParams {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub creator_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn cancel_market(input: CancelMarketParams) -> Instruction {
+    build(
+        accounts::CancelMarket {
+            authority: input.authority,
+            market: input.market,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            creator_token_account: input.creator_token_account,
+            token_program: input.token_program,
+        },
+        instruction::CancelMarket {},
+    )
+}
+
+pub struct ReclaimMarketRewardsParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub creator_token_account: Pubkey,
+    pub token_program: Pubkey,
+    /// Option indices of `market.winning_options`, in the same order
+    pub winning_options: Vec<u16>,
+}
+
+pub fn reclaim_market_rewards(input: ReclaimMarketRewardsParams) -> Instruction {
+    let mut ix = build(
+        accounts::ReclaimMarketRewards {
+            creator: input.creator,
+            market: input.market,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            creator_token_account: input.creator_token_account,
+            token_program: input.token_program,
+        },
+        instruction::ReclaimMarketRewards {},
+    );
+    ix.accounts.extend(input.winning_options.iter().map(|&option_index| {
+        AccountMeta::new_readonly(opportunity_market_option_address(&input.market, option_index).0, false)
+    }));
+    ix
+}
+
+pub struct ClaimProtocolFeesParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Token account owned by the central state's treasury
+    pub treasury_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn claim_protocol_fees(input: ClaimProtocolFeesParams) -> Instruction {
+    build(
+        accounts::ClaimProtocolFees {
+            signer: input.signer,
+            central_state: central_state_address().0,
+            market: input.market,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            treasury_token_account: input.treasury_token_account,
+            token_program: input.token_program,
+        },
+        instruction::ClaimProtocolFees {},
+    )
+}
+
+pub struct OpenMarketParams {
+    pub creator: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub open_timestamp: u64,
+}
+
+pub fn open_market(input: OpenMarketParams) -> Instruction {
+    build(
+        accounts::OpenMarket {
+            creator: input.creator,
+            market: input.market,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            token_program: input.token_program,
+        },
+        instruction::OpenMarket {
+            open_timestamp: input.open_timestamp,
+        },
+    )
+}
+
+pub struct ActivateMarketParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn activate_market(input: ActivateMarketParams) -> Instruction {
+    build(
+        accounts::ActivateMarket {
+            signer: input.signer,
+            market: input.market,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            token_program: input.token_program,
+        },
+        instruction::ActivateMarket {},
+    )
+}
+
+pub struct SponsorMarketParams {
+    pub sponsor: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub sponsor_token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+pub fn sponsor_market(input: SponsorMarketParams) -> Instruction {
+    build(
+        accounts::SponsorMarket {
+            sponsor: input.sponsor,
+            market: input.market,
+            market_sponsor: market_sponsor_address(&input.market, &input.sponsor).0,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            sponsor_token_account: input.sponsor_token_account,
+            token_program: input.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::SponsorMarket { amount: input.amount },
+    )
+}
+
+pub struct RefundSponsorParams {
+    /// Any signer, the refund always goes to the sponsor's ATA
+    pub signer: Pubkey,
+    pub sponsor: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn refund_sponsor(input: RefundSponsorParams) -> Instruction {
+    build(
+        accounts::RefundSponsor {
+            signer: input.signer,
+            sponsor: input.sponsor,
+            market: input.market,
+            market_sponsor: market_sponsor_address(&input.market, &input.sponsor).0,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            sponsor_token_account: get_associated_token_address_with_program_id(
+                &input.sponsor,
+                &input.reward_mint,
+                &input.token_program,
+            ),
+            token_program: input.token_program,
+        },
+        instruction::RefundSponsor {},
+    )
+}
+
+pub struct CloseMarketSponsorParams {
+    /// Any signer, the rent always goes to the sponsor
+    pub signer: Pubkey,
+    pub sponsor: Pubkey,
+    pub market: Pubkey,
+}
+
+pub fn close_market_sponsor(input: CloseMarketSponsorParams) -> Instruction {
+    build(
+        accounts::CloseMarketSponsor {
+            signer: input.signer,
+            sponsor: input.sponsor,
+            market: input.market,
+            market_sponsor: market_sponsor_address(&input.market, &input.sponsor).0,
+        },
+        instruction::CloseMarketSponsor {},
+    )
+}
+
+pub struct SelectOptionParams {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub option_index: u16,
+}
+
+pub fn select_option(input: SelectOptionParams) -> Instruction {
+    build(
+        accounts::SelectOption {
+            authority: input.authority,
+            market: input.market,
+        },
+        instruction::SelectOption {
+            option_index: input.option_index,
+        },
+    )
+}
+
+pub struct SelectWinningOptionsParams {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    /// Winners in rank order, each taking `weight / total_weight` of the reward
+    pub winning_options: Vec<WinningOption>,
+}
+
+pub fn select_winning_options(input: SelectWinningOptionsParams) -> Instruction {
+    build(
+        accounts::SelectOption {
+            authority: input.authority,
+            market: input.market,
+        },
+        instruction::SelectWinningOptions {
+            winning_options: input.winning_options,
+        },
+    )
+}
+
+pub struct ExtendRevealPeriodParams {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub new_time_to_reveal: u64,
+}
+
+pub fn extend_reveal_period(input: ExtendRevealPeriodParams) -> Instruction {
+    build(
+        accounts::ExtendRevealPeriod {
+            authority: input.authority,
+            market: input.market,
+        },
+        instruction::ExtendRevealPeriod {
+            new_time_to_reveal: input.new_time_to_reveal,
+        },
+    )
+}
+
+pub struct DiscloseOptionTalliesParams {
+    pub authority: Pubkey,
+    pub market: Pubkey,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn disclose_option_tallies(input: DiscloseOptionTalliesParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("disclose_option_tallies", config);
+    build(
+        accounts::DiscloseOptionTallies {
+            authority: input.authority,
+            market: input.market,
+            option_tallies: option_tallies_address(&input.market).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::DiscloseOptionTallies {
+            computation_offset: arcium.computation_offset,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
+
+pub struct IncrementOptionTallyParams {
+    pub signer: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub option_index: u16,
+    pub share_account_id: u32,
+}
+
+pub fn increment_option_tally(input: IncrementOptionTallyParams) -> Instruction {
+    build(
+        accounts::IncrementOptionTally {
+            signer: input.signer,
+            owner: input.owner,
+            market: input.market,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::IncrementOptionTally {
+            option_index: input.option_index,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
+
+pub struct CloseShareAccountParams {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub reward_mint: Pubkey,
+    pub owner_token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub option_index: u16,
+    pub share_account_id: u32,
+}
+
+pub fn close_share_account(input: CloseShareAccountParams) -> Instruction {
+    build(
+        accounts::CloseShareAccount {
+            owner: input.owner,
+            market: input.market,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            reward_mint: input.reward_mint,
+            market_token_ata: market_token_address(&input.market, &input.reward_mint, &input.token_program),
+            owner_token_account: input.owner_token_account,
+            token_program: input.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::CloseShareAccount {
+            option_index: input.option_index,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
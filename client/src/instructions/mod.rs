@@ -0,0 +1,33 @@
+//! Typed builders for the program entrypoints. MPC callbacks (`*_callback`) are invoked by
+//! the Arcium program once a computation finalizes and have no client-side builder.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::Instruction,
+    InstructionData, ToAccountMetas,
+};
+use opportunity_market::ID;
+
+pub mod central_state;
+pub mod comp_defs;
+pub mod encrypted_token_account;
+pub mod market;
+pub mod share_account;
+pub mod stake_delegation;
+
+pub use central_state::*;
+pub use comp_defs::*;
+pub use encrypted_token_account::*;
+pub use market::*;
+pub use share_account::*;
+pub use stake_delegation::*;
+
+pub(crate) const SYSTEM_PROGRAM_ID: Pubkey = anchor_lang::system_program::ID;
+
+pub(crate) fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,256 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use opportunity_market::{accounts, instruction};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::arcium::{get_compute_accounts, ArciumConfig, ARCIUM_PROGRAM_ID};
+use crate::pda::{
+    market_token_address, opportunity_market_option_address, option_tallies_address, share_account_address,
+    token_vault_address, token_vault_token_address,
+};
+
+pub struct InitShareAccountParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub state_nonce: u128,
+    pub share_account_id: u32,
+}
+
+pub fn init_share_account(input: InitShareAccountParams) -> Instruction {
+    build(
+        accounts::InitShareAccount {
+            signer: input.signer,
+            market: input.market,
+            share_account: share_account_address(&input.signer, &input.market, input.share_account_id).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitShareAccount {
+            state_nonce: input.state_nonce,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
+
+pub struct StakeParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub user_eta: Pubkey,
+    pub share_account_id: u32,
+    pub amount_ciphertext: [u8; 32],
+    pub selected_option_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn stake(input: StakeParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("buy_opportunity_market_shares", config);
+    build(
+        accounts::Stake {
+            signer: input.signer,
+            market: input.market,
+            user_eta: input.user_eta,
+            share_account: share_account_address(&input.signer, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::Stake {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+            amount_ciphertext: input.amount_ciphertext,
+            selected_option_ciphertext: input.selected_option_ciphertext,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
+
+pub struct IncreaseStakeParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub user_eta: Pubkey,
+    pub share_account_id: u32,
+    pub amount_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn increase_stake(input: IncreaseStakeParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("increase_stake", config);
+    build(
+        accounts::IncreaseStake {
+            signer: input.signer,
+            market: input.market,
+            user_eta: input.user_eta,
+            share_account: share_account_address(&input.signer, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::IncreaseStake {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+            amount_ciphertext: input.amount_ciphertext,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
+
+pub struct RevealSharesParams {
+    pub signer: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub user_eta: Pubkey,
+    pub share_account_id: u32,
+    pub token_mint: Pubkey,
+    /// The market's reward mint, its token account is only passed when it is `token_mint`
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey,
+    /// Option the share account is expected to reveal. The callback tallies it right away
+    /// if it matches, so no separate `increment_option_tally` is needed.
+    pub tally_option_index: Option<u16>,
+}
+
+pub fn reveal_shares(input: RevealSharesParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("reveal_shares", config);
+    build(
+        accounts::RevealShares {
+            signer: input.signer,
+            owner: input.owner,
+            market: input.market,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            option: input
+                .tally_option_index
+                .map(|option_index| opportunity_market_option_address(&input.market, option_index).0),
+            user_eta: input.user_eta,
+            token_mint: input.token_mint,
+            token_vault: token_vault_address().0,
+            token_vault_ata: token_vault_token_address(&input.token_mint, &input.token_program),
+            market_token_ata: (input.reward_mint == input.token_mint)
+                .then(|| market_token_address(&input.market, &input.token_mint, &input.token_program)),
+            token_program: input.token_program,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::RevealShares {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
+
+pub struct UnstakeEarlyParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub share_account_id: u32,
+}
+
+pub fn unstake_early(input: UnstakeEarlyParams) -> Instruction {
+    build(
+        accounts::UnstakeEarly {
+            signer: input.signer,
+            market: input.market,
+            share_account: share_account_address(&input.signer, &input.market, input.share_account_id).0,
+        },
+        instruction::UnstakeEarly {
+            share_account_id: input.share_account_id,
+        },
+    )
+}
+
+pub struct DoUnstakeEarlyParams {
+    pub signer: Pubkey,
+    pub market: Pubkey,
+    pub user_eta: Pubkey,
+    pub share_account_id: u32,
+    pub share_account_owner: Pubkey,
+}
+
+pub fn do_unstake_early(input: DoUnstakeEarlyParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("unstake_early", config);
+    build(
+        accounts::DoUnstakeEarly {
+            signer: input.signer,
+            market: input.market,
+            user_eta: input.user_eta,
+            share_account: share_account_address(&input.share_account_owner, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::DoUnstakeEarly {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+            share_account_owner: input.share_account_owner,
+        },
+    )
+}
+
+pub struct SyncOptionTallyParams {
+    pub signer: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub share_account_id: u32,
+}
+
+/// Moves a share account's pending stake change into the market's option tallies.
+pub fn sync_option_tally(input: SyncOptionTallyParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("sync_option_tally", config);
+    build(
+        accounts::SyncOptionTally {
+            signer: input.signer,
+            owner: input.owner,
+            market: input.market,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            option_tallies: option_tallies_address(&input.market).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::SyncOptionTally {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
@@ -0,0 +1,176 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use opportunity_market::{accounts, instruction};
+
+use super::{build, SYSTEM_PROGRAM_ID};
+use crate::arcium::{get_compute_accounts, ArciumConfig, ARCIUM_PROGRAM_ID};
+use crate::pda::{
+    central_state_address, opportunity_market_option_address, share_account_address, stake_delegation_address,
+};
+
+pub struct CreateStakeDelegationParams {
+    pub owner: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub delegate: Pubkey,
+    pub spending_cap: u64,
+    pub expires_at: u64,
+    pub state_nonce: u128,
+}
+
+pub fn create_stake_delegation(input: CreateStakeDelegationParams) -> Instruction {
+    build(
+        accounts::CreateStakeDelegation {
+            owner: input.owner,
+            encrypted_token_account: input.encrypted_token_account,
+            delegation: stake_delegation_address(&input.encrypted_token_account, &input.delegate).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::CreateStakeDelegation {
+            delegate: input.delegate,
+            spending_cap: input.spending_cap,
+            expires_at: input.expires_at,
+            state_nonce: input.state_nonce,
+        },
+    )
+}
+
+pub struct RevokeStakeDelegationParams {
+    pub owner: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub delegate: Pubkey,
+}
+
+pub fn revoke_stake_delegation(input: RevokeStakeDelegationParams) -> Instruction {
+    build(
+        accounts::RevokeStakeDelegation {
+            owner: input.owner,
+            delegation: stake_delegation_address(&input.encrypted_token_account, &input.delegate).0,
+        },
+        instruction::RevokeStakeDelegation {},
+    )
+}
+
+pub struct InitShareAccountAsDelegateParams {
+    pub delegate: Pubkey,
+    /// Owner of the delegated ETA, owns the share account
+    pub owner: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub market: Pubkey,
+    pub state_nonce: u128,
+    pub share_account_id: u32,
+}
+
+pub fn init_share_account_as_delegate(input: InitShareAccountAsDelegateParams) -> Instruction {
+    build(
+        accounts::InitShareAccountAsDelegate {
+            delegate: input.delegate,
+            delegation: stake_delegation_address(&input.encrypted_token_account, &input.delegate).0,
+            market: input.market,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            system_program: SYSTEM_PROGRAM_ID,
+        },
+        instruction::InitShareAccountAsDelegate {
+            state_nonce: input.state_nonce,
+            share_account_id: input.share_account_id,
+        },
+    )
+}
+
+pub struct StakeAsDelegateParams {
+    pub delegate: Pubkey,
+    /// Owner of `user_eta`, owns the share account
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub user_eta: Pubkey,
+    pub share_account_id: u32,
+    /// Encrypted with the delegate's own x25519 key
+    pub amount_ciphertext: [u8; 32],
+    pub selected_option_ciphertext: [u8; 32],
+    pub delegate_pubkey: [u8; 32],
+    pub input_nonce: u128,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn stake_as_delegate(input: StakeAsDelegateParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("delegated_buy_opportunity_market_shares", config);
+    build(
+        accounts::StakeAsDelegate {
+            delegate: input.delegate,
+            market: input.market,
+            delegation: stake_delegation_address(&input.user_eta, &input.delegate).0,
+            user_eta: input.user_eta,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::StakeAsDelegate {
+            computation_offset: arcium.computation_offset,
+            share_account_id: input.share_account_id,
+            amount_ciphertext: input.amount_ciphertext,
+            selected_option_ciphertext: input.selected_option_ciphertext,
+            delegate_pubkey: input.delegate_pubkey,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
+
+pub struct AddMarketOptionAsDelegateParams {
+    pub delegate: Pubkey,
+    /// Owner of `source_eta`, owns the share account and is recorded as the option's creator
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub source_eta: Pubkey,
+    pub option_index: u16,
+    pub share_account_id: u32,
+    pub name: String,
+    /// Encrypted with the delegate's own x25519 key
+    pub amount_ciphertext: [u8; 32],
+    pub delegate_pubkey: [u8; 32],
+    pub input_nonce: u128,
+    pub authorized_reader_nonce: u128,
+}
+
+pub fn add_market_option_as_delegate(input: AddMarketOptionAsDelegateParams, config: ArciumConfig) -> Instruction {
+    let arcium = get_compute_accounts("delegated_add_option_stake", config);
+    build(
+        accounts::AddMarketOptionAsDelegate {
+            delegate: input.delegate,
+            market: input.market,
+            central_state: central_state_address().0,
+            option: opportunity_market_option_address(&input.market, input.option_index).0,
+            delegation: stake_delegation_address(&input.source_eta, &input.delegate).0,
+            source_eta: input.source_eta,
+            share_account: share_account_address(&input.owner, &input.market, input.share_account_id).0,
+            sign_pda_account: arcium.sign_pda_account,
+            mxe_account: arcium.mxe_account,
+            mempool_account: arcium.mempool_account,
+            executing_pool: arcium.executing_pool,
+            computation_account: arcium.computation_account,
+            comp_def_account: arcium.comp_def_account,
+            cluster_account: arcium.cluster_account,
+            pool_account: arcium.pool_account,
+            clock_account: arcium.clock_account,
+            system_program: SYSTEM_PROGRAM_ID,
+            arcium_program: ARCIUM_PROGRAM_ID,
+        },
+        instruction::AddMarketOptionAsDelegate {
+            computation_offset: arcium.computation_offset,
+            option_index: input.option_index,
+            share_account_id: input.share_account_id,
+            name: input.name,
+            amount_ciphertext: input.amount_ciphertext,
+            delegate_pubkey: input.delegate_pubkey,
+            input_nonce: input.input_nonce,
+            authorized_reader_nonce: input.authorized_reader_nonce,
+        },
+    )
+}
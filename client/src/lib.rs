@@ -0,0 +1,12 @@
+//! Rust client for the `opportunity_market` program: PDA derivation, instruction builders,
+//! account deserializers and x25519/Rescue helpers for the encrypted inputs and state.
+
+pub mod accounts;
+pub mod arcium;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+pub mod x25519;
+
+pub use error::ClientError;
+pub use opportunity_market::ID as OPPORTUNITY_MARKET_PROGRAM_ID;
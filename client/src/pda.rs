@@ -0,0 +1,184 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use opportunity_market::{
+    ENCRYPTED_TOKEN_ACCOUNT_SEED, ID, MARKET_SPONSOR_SEED, MARKET_TEMPLATE_SEED, OPTION_TALLIES_SEED,
+    SHARE_ACCOUNT_SEED, STAKE_DELEGATION_SEED, TOKEN_VAULT_SEED,
+};
+
+pub const CENTRAL_STATE_SEED: &[u8] = b"central_state";
+pub const OPPORTUNITY_MARKET_SEED: &[u8] = b"opportunity_market";
+pub const OPPORTUNITY_MARKET_OPTION_SEED: &[u8] = b"option";
+
+pub fn central_state_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CENTRAL_STATE_SEED], &ID)
+}
+
+pub fn opportunity_market_address(creator: &Pubkey, market_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OPPORTUNITY_MARKET_SEED, creator.as_ref(), &market_index.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Market `series_index` of a template's series
+pub fn template_market_address(template: &Pubkey, series_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OPPORTUNITY_MARKET_SEED, template.as_ref(), &series_index.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn market_template_address(creator: &Pubkey, template_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MARKET_TEMPLATE_SEED, creator.as_ref(), &template_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Contributions of `sponsor` to a market's reward pool
+pub fn market_sponsor_address(market: &Pubkey, sponsor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MARKET_SPONSOR_SEED, market.as_ref(), sponsor.as_ref()], &ID)
+}
+
+pub fn opportunity_market_option_address(market: &Pubkey, option_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OPPORTUNITY_MARKET_OPTION_SEED, market.as_ref(), &option_index.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Running per-option stake of a market, encrypted to the MXE
+pub fn option_tallies_address(market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OPTION_TALLIES_SEED, market.as_ref()], &ID)
+}
+
+pub fn share_account_address(owner: &Pubkey, market: &Pubkey, share_account_id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SHARE_ACCOUNT_SEED, owner.as_ref(), market.as_ref(), &share_account_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Delegation letting `delegate` stake from `encrypted_token_account`
+pub fn stake_delegation_address(encrypted_token_account: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STAKE_DELEGATION_SEED, encrypted_token_account.as_ref(), delegate.as_ref()],
+        &ID,
+    )
+}
+
+/// Address of the regular EncryptedTokenAccount (index = 0).
+/// Regular ETAs are created via init_encrypted_token_account.
+pub fn encrypted_token_account_address(token_mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    encrypted_token_account_address_with_index(token_mint, owner, 0)
+}
+
+/// Address of an EncryptedTokenAccount with a specific index.
+/// Index 0 is the regular ETA, non-zero indices are ephemeral ETAs.
+pub fn encrypted_token_account_address_with_index(
+    token_mint: &Pubkey,
+    owner: &Pubkey,
+    index: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ENCRYPTED_TOKEN_ACCOUNT_SEED, token_mint.as_ref(), owner.as_ref(), &index.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn token_vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOKEN_VAULT_SEED], &ID)
+}
+
+/// Market ATA for the reward mint, holding the reward pool, slashed stake and accrued protocol fees
+pub fn market_token_address(market: &Pubkey, token_mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(market, token_mint, token_program)
+}
+
+/// Token vault ATA backing every encrypted token account of `token_mint`
+pub fn token_vault_token_address(token_mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(&token_vault_address().0, token_mint, token_program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Seeds as written in the program's account constraints
+    #[test]
+    fn seeds_match_program() {
+        assert_eq!(CENTRAL_STATE_SEED, b"central_state");
+        assert_eq!(OPPORTUNITY_MARKET_SEED, b"opportunity_market");
+        assert_eq!(OPPORTUNITY_MARKET_OPTION_SEED, b"option");
+        assert_eq!(SHARE_ACCOUNT_SEED, b"share_account");
+        assert_eq!(OPTION_TALLIES_SEED, b"option_tallies");
+        assert_eq!(STAKE_DELEGATION_SEED, b"stake_delegation");
+        assert_eq!(MARKET_SPONSOR_SEED, b"market_sponsor");
+        assert_eq!(MARKET_TEMPLATE_SEED, b"market_template");
+        assert_eq!(ENCRYPTED_TOKEN_ACCOUNT_SEED, b"encrypted_token_account");
+        assert_eq!(TOKEN_VAULT_SEED, b"token_vault");
+    }
+
+    #[test]
+    fn helpers_derive_program_addresses() {
+        let creator = Pubkey::new_from_array([1; 32]);
+        let owner = Pubkey::new_from_array([2; 32]);
+        let mint = Pubkey::new_from_array([3; 32]);
+        let market = opportunity_market_address(&creator, 7).0;
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &ID);
+
+        assert_eq!(central_state_address(), pda(&[b"central_state"]));
+        assert_eq!(
+            opportunity_market_address(&creator, 7),
+            pda(&[b"opportunity_market", creator.as_ref(), &7u64.to_le_bytes()])
+        );
+        assert_eq!(
+            market_template_address(&creator, 3),
+            pda(&[b"market_template", creator.as_ref(), &3u64.to_le_bytes()])
+        );
+        let template = market_template_address(&creator, 3).0;
+        assert_eq!(
+            template_market_address(&template, 4),
+            pda(&[b"opportunity_market", template.as_ref(), &4u64.to_le_bytes()])
+        );
+        assert_eq!(
+            market_sponsor_address(&market, &owner),
+            pda(&[b"market_sponsor", market.as_ref(), owner.as_ref()])
+        );
+        assert_eq!(
+            opportunity_market_option_address(&market, 2),
+            pda(&[b"option", market.as_ref(), &2u16.to_le_bytes()])
+        );
+        assert_eq!(option_tallies_address(&market), pda(&[b"option_tallies", market.as_ref()]));
+        assert_eq!(
+            share_account_address(&owner, &market, 5),
+            pda(&[b"share_account", owner.as_ref(), market.as_ref(), &5u32.to_le_bytes()])
+        );
+
+        let eta = encrypted_token_account_address(&mint, &owner);
+        assert_eq!(
+            eta,
+            pda(&[b"encrypted_token_account", mint.as_ref(), owner.as_ref(), &0u64.to_le_bytes()])
+        );
+        assert_ne!(encrypted_token_account_address_with_index(&mint, &owner, 1), eta);
+        assert_eq!(
+            stake_delegation_address(&eta.0, &creator),
+            pda(&[b"stake_delegation", eta.0.as_ref(), creator.as_ref()])
+        );
+        assert_eq!(token_vault_address(), pda(&[b"token_vault"]));
+    }
+
+    #[test]
+    fn distinct_indices_give_distinct_addresses() {
+        let creator = Pubkey::new_from_array([1; 32]);
+        let owner = Pubkey::new_from_array([2; 32]);
+        let market = opportunity_market_address(&creator, 0).0;
+
+        assert_ne!(market, opportunity_market_address(&creator, 1).0);
+        assert_ne!(
+            opportunity_market_option_address(&market, 1).0,
+            opportunity_market_option_address(&market, 2).0
+        );
+        assert_ne!(share_account_address(&owner, &market, 0).0, share_account_address(&owner, &market, 1).0);
+    }
+}
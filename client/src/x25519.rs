@@ -0,0 +1,296 @@
+use arcis_compiler::{
+    traits::FromLeBytes,
+    utils::{
+        crypto::{
+            key::{X25519PrivateKey, X25519PublicKey},
+            rescue_cipher::RescueCipher,
+        },
+        curve_point::CurvePoint,
+        field::{BaseField, ScalarField},
+    },
+};
+use opportunity_market::{EncryptedTokenAccount, OptionTallies, ShareAccount, StakeDelegation, MAX_TALLIED_OPTIONS};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::error::ClientError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct X25519Keypair {
+    pub public_key: [u8; 32],
+    pub secret_key: [u8; 32],
+}
+
+impl X25519Keypair {
+    /// Generates a new X25519 keypair.
+    /// Used for encrypted computations with Arcium.
+    pub fn generate() -> Self {
+        let mut secret_key = [0u8; 32];
+        OsRng.fill_bytes(&mut secret_key);
+        Self::from_secret_key(secret_key)
+    }
+
+    pub fn from_secret_key(secret_key: [u8; 32]) -> Self {
+        let private_key = X25519PrivateKey::<ScalarField>::from_le_bytes(secret_key);
+        let public_key = X25519PublicKey::<CurvePoint>::new_from_private_key(private_key);
+        Self {
+            public_key: public_key.to_le_bytes(),
+            secret_key,
+        }
+    }
+
+    /// Derives an X25519 keypair deterministically from a signature
+    pub fn from_signature(signature: &[u8]) -> Self {
+        Self::from_secret_key(Sha256::digest(signature).into())
+    }
+}
+
+/// Random 16 byte nonce, as expected by the `input_nonce` and `*_nonce` instruction args.
+pub fn random_nonce() -> u128 {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    u128::from_le_bytes(bytes)
+}
+
+/// Rescue cipher keyed by the x25519 shared secret between a user and the MXE.
+/// Ciphertexts are compatible with `RescueCipher` from `@arcium-hq/client`.
+pub struct Cipher {
+    inner: RescueCipher<BaseField, BaseField>,
+}
+
+/// Ciphertexts for `stake`, sharing a single `input_nonce`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakeInput {
+    pub amount_ciphertext: [u8; 32],
+    pub selected_option_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+}
+
+/// Ciphertext for the instructions that only take an `amount_ciphertext`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AmountInput {
+    pub amount_ciphertext: [u8; 32],
+    pub input_nonce: u128,
+}
+
+/// Plaintext of a share account's encrypted state (or its disclosure)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecryptedShareState {
+    pub amount: u64,
+    pub selected_option: u16,
+    pub weighted_staked_at: u128,
+}
+
+impl Cipher {
+    /// Creates a cipher from a user's secret key and the MXE public key.
+    pub fn new(user_secret_key: &[u8; 32], mxe_public_key: &[u8; 32]) -> Result<Self, ClientError> {
+        let private_key = X25519PrivateKey::<ScalarField>::from_le_bytes(*user_secret_key);
+        let public_key = X25519PublicKey::<CurvePoint>::from_le_bytes(*mxe_public_key)
+            .ok_or(ClientError::InvalidX25519PublicKey)?;
+        Ok(Self {
+            inner: RescueCipher::new_with_client_from_keys(private_key, public_key),
+        })
+    }
+
+    /// Encrypts `plaintext` in CTR mode, one 32 byte ciphertext per value.
+    pub fn encrypt(&self, plaintext: &[u128], nonce: u128) -> Vec<[u8; 32]> {
+        let plaintext = plaintext.iter().map(|v| to_field(*v)).collect();
+        self.inner
+            .encrypt(plaintext, to_field(nonce))
+            .iter()
+            .map(BaseField::to_le_bytes)
+            .collect()
+    }
+
+    /// Decrypts `ciphertext` in CTR mode. Values are returned as their low 128 bits, which
+    /// covers every type stored encrypted by the program.
+    pub fn decrypt(&self, ciphertext: &[[u8; 32]], nonce: u128) -> Result<Vec<u128>, ClientError> {
+        let ciphertext = ciphertext
+            .iter()
+            .map(|c| BaseField::from_le_bytes_checked(*c).ok_or(ClientError::InvalidCiphertext))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self
+            .inner
+            .decrypt(ciphertext, to_field(nonce))
+            .iter()
+            .map(from_field)
+            .collect())
+    }
+
+    /// Encrypts the amount and selected option for `stake` under a fresh nonce.
+    pub fn encrypt_stake_input(&self, amount: u64, selected_option: u16) -> StakeInput {
+        let input_nonce = random_nonce();
+        let ciphertexts = self.encrypt(&[amount as u128, selected_option as u128], input_nonce);
+        StakeInput {
+            amount_ciphertext: ciphertexts[0],
+            selected_option_ciphertext: ciphertexts[1],
+            input_nonce,
+        }
+    }
+
+    /// Encrypts the amount for `add_market_option`, `increase_stake` and
+    /// `transfer_encrypted_tokens` under a fresh nonce.
+    pub fn encrypt_amount_input(&self, amount: u64) -> AmountInput {
+        let input_nonce = random_nonce();
+        let ciphertexts = self.encrypt(&[amount as u128], input_nonce);
+        AmountInput {
+            amount_ciphertext: ciphertexts[0],
+            input_nonce,
+        }
+    }
+
+    /// Decrypts the stake held in a share account. Requires the owner's cipher.
+    pub fn decrypt_share_state(&self, share_account: &ShareAccount) -> Result<DecryptedShareState, ClientError> {
+        let values = self.decrypt(&share_account.encrypted_state, share_account.state_nonce)?;
+        Ok(to_share_state(&values))
+    }
+
+    /// Decrypts the stake disclosed to the market's authorized reader.
+    /// Requires the cipher of the reader's keypair.
+    pub fn decrypt_share_state_disclosure(
+        &self,
+        share_account: &ShareAccount,
+    ) -> Result<DecryptedShareState, ClientError> {
+        let values = self.decrypt(
+            &share_account.encrypted_state_disclosure,
+            share_account.state_nonce_disclosure,
+        )?;
+        Ok(to_share_state(&values))
+    }
+
+    /// Decrypts an encrypted token account balance. Requires the owner's cipher.
+    pub fn decrypt_balance(&self, encrypted_token_account: &EncryptedTokenAccount) -> Result<u64, ClientError> {
+        let values = self.decrypt(
+            &encrypted_token_account.encrypted_state,
+            encrypted_token_account.state_nonce,
+        )?;
+        Ok(values[0] as u64)
+    }
+
+    /// Decrypts how much a delegate has staked so far out of the delegation's spending cap.
+    /// Requires the owner's cipher.
+    pub fn decrypt_delegation_spent(&self, delegation: &StakeDelegation) -> Result<u64, ClientError> {
+        if !delegation.is_initialized {
+            return Ok(0);
+        }
+        let values = self.decrypt(&delegation.encrypted_state, delegation.state_nonce)?;
+        Ok(values[0] as u64)
+    }
+
+    /// Decrypts the last running tallies disclosed to the market's authorized reader, option i
+    /// at index i - 1. Requires the cipher of the reader's keypair.
+    pub fn decrypt_option_tallies_disclosure(
+        &self,
+        option_tallies: &OptionTallies,
+    ) -> Result<[u64; MAX_TALLIED_OPTIONS], ClientError> {
+        let values = self.decrypt(
+            &option_tallies.encrypted_state_disclosure,
+            option_tallies.state_nonce_disclosure,
+        )?;
+        let mut amounts = [0u64; MAX_TALLIED_OPTIONS];
+        for (amount, value) in amounts.iter_mut().zip(values) {
+            *amount = value as u64;
+        }
+        Ok(amounts)
+    }
+}
+
+fn to_share_state(values: &[u128]) -> DecryptedShareState {
+    DecryptedShareState {
+        amount: values[0] as u64,
+        selected_option: values[1] as u16,
+        weighted_staked_at: values[2],
+    }
+}
+
+fn to_field(value: u128) -> BaseField {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    BaseField::from_le_bytes_checked(bytes).expect("u128 fits in the base field")
+}
+
+fn from_field(value: &BaseField) -> u128 {
+    let bytes = value.to_le_bytes();
+    u128::from_le_bytes(bytes[..16].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The user encrypts with the MXE public key; the MXE decrypts with the user's public key.
+    fn cipher_pair() -> (Cipher, Cipher) {
+        let user = X25519Keypair::generate();
+        let mxe = X25519Keypair::generate();
+        (
+            Cipher::new(&user.secret_key, &mxe.public_key).unwrap(),
+            Cipher::new(&mxe.secret_key, &user.public_key).unwrap(),
+        )
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let (user, mxe) = cipher_pair();
+        let plaintext = [0, 1, u16::MAX as u128, u64::MAX as u128, u128::MAX];
+        let nonce = random_nonce();
+        let ciphertext = user.encrypt(&plaintext, nonce);
+
+        assert_eq!(ciphertext.len(), plaintext.len());
+        assert_eq!(user.decrypt(&ciphertext, nonce).unwrap(), plaintext);
+        assert_eq!(mxe.decrypt(&ciphertext, nonce).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn nonce_changes_ciphertext() {
+        let (user, _) = cipher_pair();
+        let nonce = random_nonce();
+        let ciphertext = user.encrypt(&[42], nonce);
+
+        assert_ne!(user.encrypt(&[42], nonce.wrapping_add(1)), ciphertext);
+        assert_ne!(user.decrypt(&ciphertext, nonce.wrapping_add(1)).unwrap(), vec![42]);
+    }
+
+    #[test]
+    fn other_key_does_not_decrypt() {
+        let (user, _) = cipher_pair();
+        let (stranger, _) = cipher_pair();
+        let nonce = random_nonce();
+        let ciphertext = user.encrypt(&[42], nonce);
+
+        assert_ne!(stranger.decrypt(&ciphertext, nonce).unwrap(), vec![42]);
+    }
+
+    #[test]
+    fn instruction_inputs_round_trip() {
+        let (user, mxe) = cipher_pair();
+
+        let stake = user.encrypt_stake_input(u64::MAX, 3);
+        let values = mxe
+            .decrypt(&[stake.amount_ciphertext, stake.selected_option_ciphertext], stake.input_nonce)
+            .unwrap();
+        assert_eq!(values, vec![u64::MAX as u128, 3]);
+
+        let amount = user.encrypt_amount_input(1_000);
+        let values = mxe.decrypt(&[amount.amount_ciphertext], amount.input_nonce).unwrap();
+        assert_eq!(values, vec![1_000]);
+    }
+
+    #[test]
+    fn rejects_ciphertext_outside_the_field() {
+        let (user, _) = cipher_pair();
+        assert!(matches!(
+            user.decrypt(&[[0xff; 32]], random_nonce()),
+            Err(ClientError::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn keypair_from_signature_is_deterministic() {
+        let signature = [7u8; 64];
+        let keypair = X25519Keypair::from_signature(&signature);
+
+        assert_eq!(keypair, X25519Keypair::from_signature(&signature));
+        assert_eq!(keypair, X25519Keypair::from_secret_key(keypair.secret_key));
+        assert_ne!(keypair, X25519Keypair::from_signature(&[8u8; 64]));
+    }
+}
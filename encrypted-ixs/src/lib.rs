@@ -1,5 +1,7 @@
 use arcis::*;
 
+pub mod reference;
+
 #[encrypted]
 mod circuits {
     use arcis::*;
@@ -21,25 +23,69 @@ mod circuits {
     #[derive(Clone, Copy)]
     pub struct SharePurchase {
         pub amount: u64,
-        pub selected_option: u16
+        pub selected_option: u16,
+        // Sum of amount * staked_at over every stake into this account,
+        // divided by amount at reveal to get the stake-weighted entry time
+        pub weighted_staked_at: u128,
+    }
+
+    // Running stake per option, index i holds option i + 1. Markets have at most 8 options.
+    // Keep the length in sync with MAX_TALLIED_OPTIONS in the program.
+    #[derive(Clone, Copy)]
+    pub struct OptionTallies {
+        pub amounts: [u64; 8],
+    }
+
+    // Adds `added` to and removes `removed` from the tally of `option`, without revealing it
+    // Returns (overflow, new_tallies), the tallies unchanged when the addition would overflow
+    fn update_tally(tallies: OptionTallies, option: u16, added: u64, removed: u64) -> (bool, OptionTallies) {
+        let mut overflow = false;
+        for i in 0..8 {
+            overflow = overflow || (option == (i + 1) as u16 && added > u64::MAX - tallies.amounts[i]);
+        }
+
+        let mut new_tallies = tallies;
+        for i in 0..8 {
+            let amount = tallies.amounts[i];
+            new_tallies.amounts[i] = if option == (i + 1) as u16 && !overflow {
+                amount + added - removed
+            } else {
+                amount
+            };
+        }
+        (overflow, new_tallies)
+    }
+
+    // Part of a share account's stake already counted in the market's OptionTallies
+    #[derive(Clone, Copy)]
+    pub struct TalliedStake {
+        pub amount: u64,
     }
 
     // Wrap encrypted tokens: add to balance
     // If is_initialized is false (state_nonce == 0), creates fresh state instead of decrypting
-    // Returns new_encrypted_balance
+    // Returns (error, new_encrypted_balance) where error=true means the balance would overflow
     #[instruction]
     pub fn wrap_encrypted_tokens(
         balance_ctx: Enc<Shared, EncryptedTokenBalance>,
         is_initialized: bool,
         amount: u64,
-    ) -> Enc<Shared, EncryptedTokenBalance> {
+    ) -> (bool, Enc<Shared, EncryptedTokenBalance>) {
         let mut balance = if is_initialized {
             balance_ctx.to_arcis()
         } else {
             EncryptedTokenBalance { amount: 0 }
         };
-        balance.amount = balance.amount + amount;
-        balance_ctx.owner.from_arcis(balance)
+
+        // Check for overflow, keep balance unchanged on error
+        let overflow = amount > u64::MAX - balance.amount;
+        balance.amount = if overflow {
+            balance.amount
+        } else {
+            balance.amount + amount
+        };
+
+        (overflow.reveal(), balance_ctx.owner.from_arcis(balance))
     }
 
     // Unwrap encrypted tokens (sell): subtract from balance
@@ -67,13 +113,99 @@ mod circuits {
         (insufficient_balance.reveal(), sold.reveal(), balance_ctx.owner.from_arcis(balance))
     }
 
+    // User input for an ETA-to-ETA transfer (encrypted)
+    pub struct TransferInput {
+        pub amount: u64,
+    }
+
+    // Transfer encrypted tokens: debit sender ETA and credit recipient ETA
+    // If is_recipient_initialized is false (state_nonce == 0), treat recipient balance as 0
+    // Returns (error, new_sender_balance, new_recipient_balance) where error=true means insufficient
+    // balance or that the recipient balance would overflow
+    #[instruction]
+    pub fn transfer_encrypted_tokens(
+        input_ctx: Enc<Shared, TransferInput>,
+        sender_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
+        recipient_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
+        is_recipient_initialized: bool,
+    ) -> (bool, Enc<Shared, EncryptedTokenBalance>, Enc<Shared, EncryptedTokenBalance>) {
+        let input = input_ctx.to_arcis();
+        let mut sender_balance = sender_eta_ctx.to_arcis();
+        let mut recipient_balance = if is_recipient_initialized {
+            recipient_eta_ctx.to_arcis()
+        } else {
+            EncryptedTokenBalance { amount: 0 }
+        };
+
+        // Check for insufficient balance and recipient overflow
+        let insufficient_balance = input.amount > sender_balance.amount;
+        let overflow = input.amount > u64::MAX - recipient_balance.amount;
+        let error = insufficient_balance || overflow;
+
+        // Move nothing on error, both balances stay unchanged
+        let transferred = if error { 0 } else { input.amount };
+
+        sender_balance.amount = sender_balance.amount - transferred;
+        recipient_balance.amount = recipient_balance.amount + transferred;
+
+        (
+            error.reveal(),
+            sender_eta_ctx.owner.from_arcis(sender_balance),
+            recipient_eta_ctx.owner.from_arcis(recipient_balance)
+        )
+    }
+
     // Input for add_option_stake circuit (encrypted amount)
     pub struct AddOptionStakeInput {
         pub amount: u64,
     }
 
-    // Add option + stake: deduct from user's ETA, create share purchase
+    // Shares bought by a stake of `amount` on `selected_option`, and the balance left after it.
+    // Checks the stake limits, then above_cap (past a delegation's remaining spending cap, false
+    // for the owner's own stakes), then the balance. Nothing is bought on error.
+    // Returns: (error_code, new_user_balance, bought_shares)
+    // error_code: 0 = ok, 1 = insufficient balance, 2 = below min_stake, 3 = above max_stake,
+    // 4 = above the remaining spending cap
+    fn buy_shares(
+        amount: u64,
+        selected_option: u16,
+        user_balance: EncryptedTokenBalance,
+        staked_at: u64,
+        min_stake: u64,
+        max_stake: u64,
+        above_cap: bool,
+    ) -> (u8, EncryptedTokenBalance, SharePurchase) {
+        let below_min = amount < min_stake;
+        let above_max = amount > max_stake;
+        let insufficient_user_balance = amount > user_balance.amount;
+
+        let error_code: u8 = if below_min {
+            2
+        } else if above_max {
+            3
+        } else if above_cap {
+            4
+        } else if insufficient_user_balance {
+            1
+        } else {
+            0
+        };
+
+        let bought_amount = if error_code != 0 { 0 } else { amount };
+        let bought_shares = SharePurchase {
+            amount: bought_amount,
+            selected_option,
+            weighted_staked_at: (bought_amount as u128) * (staked_at as u128),
+        };
+
+        let mut new_balance = user_balance;
+        new_balance.amount = user_balance.amount - bought_amount;
+        (error_code, new_balance, bought_shares)
+    }
+
+    // Add option + stake: deduct from user's ETA and create share purchase
     // selected_option passed as plaintext u64 (no plaintext_u16 in ArgBuilder)
+    // error_code: 0 = ok, 1 = insufficient balance, 2 = below min_deposit
     #[instruction]
     pub fn add_option_stake(
         input_ctx: Enc<Shared, AddOptionStakeInput>,
@@ -82,39 +214,26 @@ mod circuits {
         share_account_ctx: Shared,
         min_deposit: u64,
         selected_option: u64,
+        staked_at: u64,
     ) -> (
-        bool,
+        u8,
         Enc<Shared, EncryptedTokenBalance>,
         Enc<Shared, SharePurchase>,
         Enc<Shared, SharePurchase>
     ) {
         let input = input_ctx.to_arcis();
-        let mut user_balance = user_eta_ctx.to_arcis();
-
-        let amount = input.amount;
-
-        // Check minimum deposit
-        let below_min = amount < min_deposit;
-
-        // Check if user has sufficient encrypted token balance
-        let insufficient_user_balance = amount > user_balance.amount;
-
-        let error = below_min || insufficient_user_balance;
-
-        let bought_amount = if error { 0 } else { amount };
-        let bought_shares = SharePurchase {
-            amount: bought_amount,
-            selected_option: selected_option as u16,
-        };
-
-        user_balance.amount = if error {
-            user_balance.amount
-        } else {
-            user_balance.amount - amount
-        };
+        let (error_code, user_balance, bought_shares) = buy_shares(
+            input.amount,
+            selected_option as u16,
+            user_eta_ctx.to_arcis(),
+            staked_at,
+            min_deposit,
+            u64::MAX,
+            false,
+        );
 
         (
-            error.reveal(),
+            error_code.reveal(),
             user_eta_ctx.owner.from_arcis(user_balance),
             share_account_ctx.from_arcis(bought_shares),
             shares_recipient_ctx.from_arcis(bought_shares),
@@ -122,61 +241,243 @@ mod circuits {
     }
 
     // Buy shares: deduct from user's encrypted token balance
-    // Returns: (error, new_user_balance, bought_shares_mxe, bought_shares_shared)
+    // The amount must lie within the market's [min_stake, max_stake] bounds
+    // Returns: (error_code, new_user_balance, bought_shares_mxe, bought_shares_shared)
+    // error_code: 0 = ok, 1 = insufficient balance, 2 = below min_stake, 3 = above max_stake
     #[instruction]
     pub fn buy_opportunity_market_shares(
         input_ctx: Enc<Shared, BuySharesInput>,
         shares_recipient_ctx: Shared,
         user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
         share_account_ctx: Shared,
+        staked_at: u64,
+        min_stake: u64,
+        max_stake: u64,
+    ) -> (
+        u8,
+        Enc<Shared, EncryptedTokenBalance>,
+        Enc<Shared, SharePurchase>,
+        Enc<Shared, SharePurchase>
+    ) {
+        let input = input_ctx.to_arcis();
+        let (error_code, user_balance, bought_shares) = buy_shares(
+            input.amount,
+            input.selected_option,
+            user_eta_ctx.to_arcis(),
+            staked_at,
+            min_stake,
+            max_stake,
+            false,
+        );
+
+        (
+            error_code.reveal(),
+            user_eta_ctx.owner.from_arcis(user_balance),
+            share_account_ctx.from_arcis(bought_shares),
+            shares_recipient_ctx.from_arcis(bought_shares)
+        )
+    }
+
+    // User input for increasing an existing stake (encrypted)
+    pub struct IncreaseStakeInput {
+        pub amount: u64,
+    }
+
+    // Increase stake: deduct from user's ETA and add to an existing share purchase.
+    // The selected option is carried over without being revealed.
+    // The increased position must stay within the market's max_stake
+    // Returns: (error_code, new_user_balance, updated_shares, updated_shares_disclosure)
+    // error_code: 0 = ok, 1 = insufficient balance, 3 = above max_stake, 5 = the staked amount would overflow
+    #[instruction]
+    pub fn increase_stake(
+        input_ctx: Enc<Shared, IncreaseStakeInput>,
+        shares_recipient_ctx: Shared,
+        user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
+        share_account_ctx: Enc<Shared, SharePurchase>,
+        staked_at: u64,
+        max_stake: u64,
     ) -> (
-        bool,
+        u8,
         Enc<Shared, EncryptedTokenBalance>,
         Enc<Shared, SharePurchase>,
         Enc<Shared, SharePurchase>
     ) {
         let input = input_ctx.to_arcis();
         let mut user_balance = user_eta_ctx.to_arcis();
+        let mut shares = share_account_ctx.to_arcis();
 
         let amount = input.amount;
 
-        // Check if user has sufficient encrypted token balance
-        let insufficient_user_balance = amount > user_balance.amount;
+        // Check for insufficient balance, stake overflow and max_stake, nothing is added on error
+        let insufficient_balance = amount > user_balance.amount;
+        let overflow = amount > u64::MAX - shares.amount;
+        let above_max = shares.amount > max_stake || amount > max_stake - shares.amount;
+
+        let error_code: u8 = if insufficient_balance {
+            1
+        } else if overflow {
+            5
+        } else if above_max {
+            3
+        } else {
+            0
+        };
 
-        let error = insufficient_user_balance;
+        let added_amount = if error_code != 0 { 0 } else { amount };
 
-        // Calculate bought shares (0 on error)
-        let bought_amount = if error { 0 } else { amount };
-        let bought_shares = SharePurchase {
-            amount: bought_amount,
-            selected_option: input.selected_option
-        };
+        user_balance.amount = user_balance.amount - added_amount;
+        shares.amount = shares.amount + added_amount;
+        shares.weighted_staked_at =
+            shares.weighted_staked_at + (added_amount as u128) * (staked_at as u128);
 
-        // Deduct from user balance (keep unchanged on error)
-        user_balance.amount = if error {
-            user_balance.amount
+        (
+            error_code.reveal(),
+            user_eta_ctx.owner.from_arcis(user_balance),
+            share_account_ctx.owner.from_arcis(shares),
+            shares_recipient_ctx.from_arcis(shares)
+        )
+    }
+
+    // Part of a stake delegation's spending cap used so far, encrypted to the ETA owner
+    #[derive(Clone, Copy)]
+    pub struct DelegationSpend {
+        pub amount: u64,
+    }
+
+    // Spend the delegated circuits start from, nothing spent when the delegation has no encrypted
+    // state yet
+    fn initial_spend(spend_ctx: Enc<Shared, DelegationSpend>, is_initialized: bool) -> DelegationSpend {
+        if is_initialized {
+            spend_ctx.to_arcis()
         } else {
-            user_balance.amount - amount
+            DelegationSpend { amount: 0 }
+        }
+    }
+
+    // Whether `amount` is past what is left of spending_cap after `spend`, checked so that
+    // neither side can wrap
+    fn above_spending_cap(spend: DelegationSpend, amount: u64, spending_cap: u64) -> bool {
+        amount > u64::MAX - spend.amount || spend.amount + amount > spending_cap
+    }
+
+    // Delegated buy: like buy_opportunity_market_shares, staked by a delegate from the owner's ETA.
+    // The input is encrypted to the delegate, everything else to the owner as for a regular stake.
+    // The amount must also fit within spending_cap minus what the delegation has spent so far.
+    // If is_spend_initialized is false, nothing has been spent yet
+    // Returns: (error_code, new_user_balance, bought_shares_mxe, bought_shares_shared, new_spend)
+    // error_code: 0 = ok, 1 = insufficient balance, 2 = below min_stake, 3 = above max_stake,
+    // 4 = above the remaining spending cap
+    #[instruction]
+    pub fn delegated_buy_opportunity_market_shares(
+        input_ctx: Enc<Shared, BuySharesInput>,
+        shares_recipient_ctx: Shared,
+        user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
+        share_account_ctx: Shared,
+        staked_at: u64,
+        min_stake: u64,
+        max_stake: u64,
+        spend_ctx: Enc<Shared, DelegationSpend>,
+        is_spend_initialized: bool,
+        spending_cap: u64,
+    ) -> (
+        u8,
+        Enc<Shared, EncryptedTokenBalance>,
+        Enc<Shared, SharePurchase>,
+        Enc<Shared, SharePurchase>,
+        Enc<Shared, DelegationSpend>
+    ) {
+        let input = input_ctx.to_arcis();
+        let spend = initial_spend(spend_ctx, is_spend_initialized);
+        let (error_code, user_balance, bought_shares) = buy_shares(
+            input.amount,
+            input.selected_option,
+            user_eta_ctx.to_arcis(),
+            staked_at,
+            min_stake,
+            max_stake,
+            above_spending_cap(spend, input.amount, spending_cap),
+        );
+        let new_spend = DelegationSpend {
+            amount: spend.amount + bought_shares.amount,
         };
 
         (
-            error.reveal(),
+            error_code.reveal(),
             user_eta_ctx.owner.from_arcis(user_balance),
             share_account_ctx.from_arcis(bought_shares),
-            shares_recipient_ctx.from_arcis(bought_shares)
+            shares_recipient_ctx.from_arcis(bought_shares),
+            spend_ctx.owner.from_arcis(new_spend)
+        )
+    }
+
+    // Delegated add option + stake: like add_option_stake, staked by a delegate from the owner's ETA
+    // within the delegation's remaining spending cap
+    // error_code: 0 = ok, 1 = insufficient balance, 2 = below min_deposit, 4 = above the remaining spending cap
+    #[instruction]
+    pub fn delegated_add_option_stake(
+        input_ctx: Enc<Shared, AddOptionStakeInput>,
+        shares_recipient_ctx: Shared,
+        user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
+        share_account_ctx: Shared,
+        min_deposit: u64,
+        selected_option: u64,
+        staked_at: u64,
+        spend_ctx: Enc<Shared, DelegationSpend>,
+        is_spend_initialized: bool,
+        spending_cap: u64,
+    ) -> (
+        u8,
+        Enc<Shared, EncryptedTokenBalance>,
+        Enc<Shared, SharePurchase>,
+        Enc<Shared, SharePurchase>,
+        Enc<Shared, DelegationSpend>
+    ) {
+        let input = input_ctx.to_arcis();
+        let spend = initial_spend(spend_ctx, is_spend_initialized);
+        let (error_code, user_balance, bought_shares) = buy_shares(
+            input.amount,
+            selected_option as u16,
+            user_eta_ctx.to_arcis(),
+            staked_at,
+            min_deposit,
+            u64::MAX,
+            above_spending_cap(spend, input.amount, spending_cap),
+        );
+        let new_spend = DelegationSpend {
+            amount: spend.amount + bought_shares.amount,
+        };
+
+        (
+            error_code.reveal(),
+            user_eta_ctx.owner.from_arcis(user_balance),
+            share_account_ctx.from_arcis(bought_shares),
+            shares_recipient_ctx.from_arcis(bought_shares),
+            spend_ctx.owner.from_arcis(new_spend)
         )
     }
 
     // Reveal shares: decrypt share account and credit ETA
+    // Stake on a non-winning option is refunded minus slash_bps, the slashed part is revealed
     // If is_eta_initialized is false (state_nonce == 0), treat existing balance as 0
+    // error=true means the refund would overflow the ETA balance, which is then left unchanged
     #[instruction]
     pub fn reveal_shares(
         share_account_ctx: Enc<Shared, SharePurchase>,
         user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
         is_eta_initialized: bool,
+        // Winning option indices, 0 for unused slots (options are 1-based)
+        winning_option_0: u64,
+        winning_option_1: u64,
+        winning_option_2: u64,
+        winning_option_3: u64,
+        // Share of stake on non-winning options that is not refunded, in basis points
+        slash_bps: u64,
     ) -> (
+        bool,                              // error (ETA balance overflow)
         u64,                               // revealed_amount
         u16,                               // revealed_option
+        u128,                              // revealed_weighted_staked_at
+        u64,                               // slashed_amount
         Enc<Shared, EncryptedTokenBalance>,     // updated ETA balance
     ) {
         let share_data = share_account_ctx.to_arcis();
@@ -186,24 +487,46 @@ mod circuits {
             EncryptedTokenBalance { amount: 0 }
         };
 
-        // Credit share amount to ETA balance
-        user_balance.amount = user_balance.amount + share_data.amount;
+        let option = share_data.selected_option as u64;
+        let is_winner = option == winning_option_0
+            || option == winning_option_1
+            || option == winning_option_2
+            || option == winning_option_3;
+
+        let slashed = if is_winner {
+            0
+        } else {
+            ((share_data.amount as u128) * (slash_bps as u128) / 10_000) as u64
+        };
+
+        // Credit share amount minus the slashed part to ETA balance
+        let refund = share_data.amount - slashed;
+        let overflow = refund > u64::MAX - user_balance.amount;
+        user_balance.amount = if overflow {
+            user_balance.amount
+        } else {
+            user_balance.amount + refund
+        };
 
         (
+            overflow.reveal(),
             share_data.amount.reveal(),
             share_data.selected_option.reveal(),
+            share_data.weighted_staked_at.reveal(),
+            slashed.reveal(),
             user_eta_ctx.owner.from_arcis(user_balance),
         )
     }
 
     // Unstake early: refund ETA
     // If is_eta_initialized is false (state_nonce == 0), treat existing balance as 0
+    // Returns (error, new_balance) where error=true means the refund would overflow the balance
     #[instruction]
     pub fn unstake_early(
         share_account_ctx: Enc<Shared, SharePurchase>,
         user_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
         is_eta_initialized: bool,
-    ) -> Enc<Shared, EncryptedTokenBalance> {
+    ) -> (bool, Enc<Shared, EncryptedTokenBalance>) {
         let share_data = share_account_ctx.to_arcis();
         let mut user_balance = if is_eta_initialized {
             user_eta_ctx.to_arcis()
@@ -211,20 +534,87 @@ mod circuits {
             EncryptedTokenBalance { amount: 0 }
         };
 
-        user_balance.amount = user_balance.amount + share_data.amount;
+        // Check for overflow, keep balance unchanged on error
+        let overflow = share_data.amount > u64::MAX - user_balance.amount;
+        user_balance.amount = if overflow {
+            user_balance.amount
+        } else {
+            user_balance.amount + share_data.amount
+        };
+
+        (overflow.reveal(), user_eta_ctx.owner.from_arcis(user_balance))
+    }
+
+    // Sync option tally: brings the share account's stake in the market's tallies up to date,
+    // moving the difference between its current stake (0 once unstaked) and what was tallied so far.
+    // Runs separately from staking so that stakes never wait on each other for the market's tallies.
+    // If is_tallied_initialized / is_tallies_initialized is false, nothing was tallied yet
+    // Returns (error, new_tallied, new_tallies) where error=true means the tally would overflow,
+    // in which case nothing changes
+    #[instruction]
+    pub fn sync_option_tally(
+        share_account_ctx: Enc<Shared, SharePurchase>,
+        tallied_ctx: Enc<Mxe, TalliedStake>,
+        is_tallied_initialized: bool,
+        is_unstaked: bool,
+        tallies_ctx: Enc<Mxe, OptionTallies>,
+        is_tallies_initialized: bool,
+    ) -> (bool, Enc<Mxe, TalliedStake>, Enc<Mxe, OptionTallies>) {
+        let share_data = share_account_ctx.to_arcis();
+        let tallied = if is_tallied_initialized {
+            tallied_ctx.to_arcis()
+        } else {
+            TalliedStake { amount: 0 }
+        };
+        let tallies = if is_tallies_initialized {
+            tallies_ctx.to_arcis()
+        } else {
+            OptionTallies { amounts: [0; 8] }
+        };
+
+        let target = if is_unstaked { 0 } else { share_data.amount };
+        let grows = target > tallied.amount;
+        let added = if grows { target - tallied.amount } else { 0 };
+        let removed = if grows { 0 } else { tallied.amount - target };
+        let (overflow, tallies) = update_tally(tallies, share_data.selected_option, added, removed);
+
+        let new_tallied = TalliedStake {
+            amount: if overflow { tallied.amount } else { target },
+        };
 
-        user_eta_ctx.owner.from_arcis(user_balance)
+        (
+            overflow.reveal(),
+            tallied_ctx.owner.from_arcis(new_tallied),
+            tallies_ctx.owner.from_arcis(tallies)
+        )
+    }
+
+    // Disclose option tallies: re-encrypt the running tallies to the market's authorized reader
+    // If is_tallies_initialized is false, nothing was staked yet and all tallies are 0
+    #[instruction]
+    pub fn disclose_option_tallies(
+        tallies_ctx: Enc<Mxe, OptionTallies>,
+        is_tallies_initialized: bool,
+        reader_ctx: Shared,
+    ) -> Enc<Shared, OptionTallies> {
+        let tallies = if is_tallies_initialized {
+            tallies_ctx.to_arcis()
+        } else {
+            OptionTallies { amounts: [0; 8] }
+        };
+
+        reader_ctx.from_arcis(tallies)
     }
 
     // Close ephemeral ETA: transfer balance to regular ETA
     // If is_regular_eta_initialized is false (state_nonce == 0), treat existing balance as 0
-    // Returns new regular ETA balance
+    // Returns (error, new_regular_balance) where error=true means the regular balance would overflow
     #[instruction]
     pub fn close_ephemeral_encrypted_token_account(
         ephemeral_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
         regular_eta_ctx: Enc<Shared, EncryptedTokenBalance>,
         is_regular_eta_initialized: bool,
-    ) -> Enc<Shared, EncryptedTokenBalance> {
+    ) -> (bool, Enc<Shared, EncryptedTokenBalance>) {
         let ephemeral_balance = ephemeral_eta_ctx.to_arcis();
         let mut regular_balance = if is_regular_eta_initialized {
             regular_eta_ctx.to_arcis()
@@ -232,9 +622,14 @@ mod circuits {
             EncryptedTokenBalance { amount: 0 }
         };
 
-        // Transfer entire balance from ephemeral to regular
-        regular_balance.amount = regular_balance.amount + ephemeral_balance.amount;
+        // Transfer entire balance from ephemeral to regular, nothing on overflow
+        let overflow = ephemeral_balance.amount > u64::MAX - regular_balance.amount;
+        regular_balance.amount = if overflow {
+            regular_balance.amount
+        } else {
+            regular_balance.amount + ephemeral_balance.amount
+        };
 
-        regular_eta_ctx.owner.from_arcis(regular_balance)
+        (overflow.reveal(), regular_eta_ctx.owner.from_arcis(regular_balance))
     }
 }
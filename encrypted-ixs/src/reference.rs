@@ -0,0 +1,1157 @@
+//! Plaintext twin of the circuits in `circuits`.
+//!
+//! Every function takes the decrypted inputs of the circuit of the same name and returns its
+//! outputs in order, with each `Enc<Shared, T>` or `Enc<Mxe, T>` output replaced by the plaintext
+//! `T`. Encryption contexts (`Shared` owners) have no plaintext counterpart and are dropped from
+//! the signatures.
+//! Keep this module in sync with the circuits: the tests below run both on random inputs and at
+//! the u64 overflow boundaries, and fail on any divergence.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EncryptedTokenBalance {
+    pub amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SharePurchase {
+    pub amount: u64,
+    pub selected_option: u16,
+    pub weighted_staked_at: u128,
+}
+
+/// Options per market, each tracked in `OptionTallies` with option `i` at `amounts[i - 1]`
+pub const MAX_TALLIED_OPTIONS: usize = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptionTallies {
+    pub amounts: [u64; MAX_TALLIED_OPTIONS],
+}
+
+impl OptionTallies {
+    /// Running stake of `option`, `None` for option indices no market can have
+    pub fn amount(&self, option: u16) -> Option<u64> {
+        (option as usize).checked_sub(1).and_then(|i| self.amounts.get(i)).copied()
+    }
+
+    fn amount_mut(&mut self, option: u16) -> Option<&mut u64> {
+        (option as usize).checked_sub(1).and_then(|i| self.amounts.get_mut(i))
+    }
+}
+
+/// Tallies the circuits start from when the market has no encrypted tallies yet
+fn initial_tallies(tallies: OptionTallies, is_initialized: bool) -> OptionTallies {
+    if is_initialized {
+        tallies
+    } else {
+        OptionTallies::default()
+    }
+}
+
+/// Adds `added` to and removes `removed` from the tally of `option`, untracked options are ignored.
+/// Returns (overflow, new_tallies), the tallies unchanged when the addition would overflow
+fn update_tally(tallies: OptionTallies, option: u16, added: u64, removed: u64) -> (bool, OptionTallies) {
+    let mut new_tallies = tallies;
+    match new_tallies.amount_mut(option) {
+        Some(amount) => match amount.checked_add(added) {
+            Some(sum) => {
+                *amount = sum - removed;
+                (false, new_tallies)
+            }
+            None => (true, tallies),
+        },
+        None => (false, tallies),
+    }
+}
+
+/// Balance the circuits start from when the ETA has no encrypted state yet
+fn initial_balance(balance: EncryptedTokenBalance, is_initialized: bool) -> EncryptedTokenBalance {
+    if is_initialized {
+        balance
+    } else {
+        EncryptedTokenBalance::default()
+    }
+}
+
+/// Credits `amount` to `balance`; returns (overflow, new_balance), the balance unchanged on overflow
+fn credit(balance: EncryptedTokenBalance, amount: u64) -> (bool, EncryptedTokenBalance) {
+    match balance.amount.checked_add(amount) {
+        Some(amount) => (false, EncryptedTokenBalance { amount }),
+        None => (true, balance),
+    }
+}
+
+/// Returns (overflow, new_balance)
+pub fn wrap_encrypted_tokens(
+    balance: EncryptedTokenBalance,
+    is_initialized: bool,
+    amount: u64,
+) -> (bool, EncryptedTokenBalance) {
+    credit(initial_balance(balance, is_initialized), amount)
+}
+
+/// Returns (insufficient_balance, amount_sold, new_balance)
+pub fn unwrap_encrypted_tokens(balance: EncryptedTokenBalance, amount: u64) -> (bool, u64, EncryptedTokenBalance) {
+    if amount > balance.amount {
+        (true, 0, balance)
+    } else {
+        (
+            false,
+            amount,
+            EncryptedTokenBalance {
+                amount: balance.amount - amount,
+            },
+        )
+    }
+}
+
+/// Returns (error, new_sender_balance, new_recipient_balance), both balances unchanged when the
+/// sender's balance is insufficient or the recipient's would overflow
+pub fn transfer_encrypted_tokens(
+    amount: u64,
+    sender_balance: EncryptedTokenBalance,
+    recipient_balance: EncryptedTokenBalance,
+    is_recipient_initialized: bool,
+) -> (bool, EncryptedTokenBalance, EncryptedTokenBalance) {
+    let recipient_balance = initial_balance(recipient_balance, is_recipient_initialized);
+    if amount > sender_balance.amount {
+        return (true, sender_balance, recipient_balance);
+    }
+    match credit(recipient_balance, amount) {
+        (false, new_recipient_balance) => (
+            false,
+            EncryptedTokenBalance {
+                amount: sender_balance.amount - amount,
+            },
+            new_recipient_balance,
+        ),
+        (true, _) => (true, sender_balance, recipient_balance),
+    }
+}
+
+/// Returns (error_code, new_user_balance, bought_shares), nothing bought on error. Checks the
+/// stake limits, then `above_cap`, then the balance: error_code is 1 for an insufficient balance,
+/// 2 below `min_stake`, 3 above `max_stake` and 4 when `above_cap` is set
+fn buy_shares(
+    amount: u64,
+    selected_option: u16,
+    user_balance: EncryptedTokenBalance,
+    staked_at: u64,
+    min_stake: u64,
+    max_stake: u64,
+    above_cap: bool,
+) -> (u8, EncryptedTokenBalance, SharePurchase) {
+    let error_code = if amount < min_stake {
+        2
+    } else if amount > max_stake {
+        3
+    } else if above_cap {
+        4
+    } else if amount > user_balance.amount {
+        1
+    } else {
+        0
+    };
+    let amount = if error_code != 0 { 0 } else { amount };
+    (
+        error_code,
+        EncryptedTokenBalance {
+            amount: user_balance.amount - amount,
+        },
+        SharePurchase {
+            amount,
+            selected_option,
+            weighted_staked_at: amount as u128 * staked_at as u128,
+        },
+    )
+}
+
+/// Returns (error_code, new_user_balance, bought_shares); the disclosure output equals
+/// `bought_shares`. error_code is 1 for an insufficient balance, 2 below `min_deposit`
+pub fn add_option_stake(
+    amount: u64,
+    user_balance: EncryptedTokenBalance,
+    min_deposit: u64,
+    selected_option: u64,
+    staked_at: u64,
+) -> (u8, EncryptedTokenBalance, SharePurchase) {
+    buy_shares(
+        amount,
+        selected_option as u16,
+        user_balance,
+        staked_at,
+        min_deposit,
+        u64::MAX,
+        false,
+    )
+}
+
+/// Returns (error_code, new_user_balance, bought_shares); the disclosure output equals
+/// `bought_shares`. error_code is 1 for an insufficient balance, 2 below `min_stake` and 3 above
+/// `max_stake`
+pub fn buy_opportunity_market_shares(
+    amount: u64,
+    selected_option: u16,
+    user_balance: EncryptedTokenBalance,
+    staked_at: u64,
+    min_stake: u64,
+    max_stake: u64,
+) -> (u8, EncryptedTokenBalance, SharePurchase) {
+    buy_shares(
+        amount,
+        selected_option,
+        user_balance,
+        staked_at,
+        min_stake,
+        max_stake,
+        false,
+    )
+}
+
+/// Returns (error_code, new_user_balance, updated_shares); the disclosure output equals
+/// `updated_shares`. Nothing changes on error: error_code is 1 for an insufficient balance, 5
+/// when the staked amount would overflow and 3 when it would exceed `max_stake`
+pub fn increase_stake(
+    amount: u64,
+    user_balance: EncryptedTokenBalance,
+    shares: SharePurchase,
+    staked_at: u64,
+    max_stake: u64,
+) -> (u8, EncryptedTokenBalance, SharePurchase) {
+    let error_code = if amount > user_balance.amount {
+        1
+    } else if shares.amount.checked_add(amount).is_none() {
+        5
+    } else if shares.amount + amount > max_stake {
+        3
+    } else {
+        0
+    };
+    if error_code != 0 {
+        return (error_code, user_balance, shares);
+    }
+    (
+        0,
+        EncryptedTokenBalance {
+            amount: user_balance.amount - amount,
+        },
+        SharePurchase {
+            amount: shares.amount + amount,
+            selected_option: shares.selected_option,
+            weighted_staked_at: shares.weighted_staked_at + amount as u128 * staked_at as u128,
+        },
+    )
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DelegationSpend {
+    pub amount: u64,
+}
+
+/// Spend the delegated circuits start from when the delegation has no encrypted state yet
+fn initial_spend(spend: DelegationSpend, is_initialized: bool) -> DelegationSpend {
+    if is_initialized {
+        spend
+    } else {
+        DelegationSpend::default()
+    }
+}
+
+/// Whether `amount` is past what is left of `spending_cap` after `spend`
+fn above_spending_cap(spend: DelegationSpend, amount: u64, spending_cap: u64) -> bool {
+    spend.amount.checked_add(amount).is_none_or(|total| total > spending_cap)
+}
+
+/// Returns (error_code, new_user_balance, bought_shares, new_spend); the disclosure output equals
+/// `bought_shares`. error_code is that of `buy_opportunity_market_shares`, or 4 above what is left
+/// of `spending_cap`
+#[allow(clippy::too_many_arguments)]
+pub fn delegated_buy_opportunity_market_shares(
+    amount: u64,
+    selected_option: u16,
+    user_balance: EncryptedTokenBalance,
+    staked_at: u64,
+    min_stake: u64,
+    max_stake: u64,
+    spend: DelegationSpend,
+    is_spend_initialized: bool,
+    spending_cap: u64,
+) -> (u8, EncryptedTokenBalance, SharePurchase, DelegationSpend) {
+    let spend = initial_spend(spend, is_spend_initialized);
+    let (error_code, new_user_balance, bought_shares) = buy_shares(
+        amount,
+        selected_option,
+        user_balance,
+        staked_at,
+        min_stake,
+        max_stake,
+        above_spending_cap(spend, amount, spending_cap),
+    );
+    (
+        error_code,
+        new_user_balance,
+        bought_shares,
+        DelegationSpend {
+            amount: spend.amount + bought_shares.amount,
+        },
+    )
+}
+
+/// Returns (error_code, new_user_balance, bought_shares, new_spend); the disclosure output equals
+/// `bought_shares`. error_code is that of `add_option_stake`, or 4 above what is left of
+/// `spending_cap`
+#[allow(clippy::too_many_arguments)]
+pub fn delegated_add_option_stake(
+    amount: u64,
+    user_balance: EncryptedTokenBalance,
+    min_deposit: u64,
+    selected_option: u64,
+    staked_at: u64,
+    spend: DelegationSpend,
+    is_spend_initialized: bool,
+    spending_cap: u64,
+) -> (u8, EncryptedTokenBalance, SharePurchase, DelegationSpend) {
+    let spend = initial_spend(spend, is_spend_initialized);
+    let (error_code, new_user_balance, bought_shares) = buy_shares(
+        amount,
+        selected_option as u16,
+        user_balance,
+        staked_at,
+        min_deposit,
+        u64::MAX,
+        above_spending_cap(spend, amount, spending_cap),
+    );
+    (
+        error_code,
+        new_user_balance,
+        bought_shares,
+        DelegationSpend {
+            amount: spend.amount + bought_shares.amount,
+        },
+    )
+}
+
+/// Returns (overflow, revealed_amount, revealed_option, revealed_weighted_staked_at, slashed_amount, new_balance)
+pub fn reveal_shares(
+    shares: SharePurchase,
+    user_balance: EncryptedTokenBalance,
+    is_eta_initialized: bool,
+    winning_options: [u64; 4],
+    slash_bps: u64,
+) -> (bool, u64, u16, u128, u64, EncryptedTokenBalance) {
+    let user_balance = initial_balance(user_balance, is_eta_initialized);
+    let is_winner = winning_options.contains(&(shares.selected_option as u64));
+    let slashed = if is_winner {
+        0
+    } else {
+        (shares.amount as u128 * slash_bps as u128 / 10_000) as u64
+    };
+    let (overflow, new_balance) = credit(user_balance, shares.amount - slashed);
+    (
+        overflow,
+        shares.amount,
+        shares.selected_option,
+        shares.weighted_staked_at,
+        slashed,
+        new_balance,
+    )
+}
+
+/// Returns (overflow, refunded_balance)
+pub fn unstake_early(
+    shares: SharePurchase,
+    user_balance: EncryptedTokenBalance,
+    is_eta_initialized: bool,
+) -> (bool, EncryptedTokenBalance) {
+    credit(initial_balance(user_balance, is_eta_initialized), shares.amount)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TalliedStake {
+    pub amount: u64,
+}
+
+/// Returns (overflow, new_tallied, new_tallies): the share's stake, or 0 once unstaked, replaces
+/// what was tallied for it so far. Nothing changes when the option's tally would overflow
+pub fn sync_option_tally(
+    shares: SharePurchase,
+    tallied: TalliedStake,
+    is_tallied_initialized: bool,
+    is_unstaked: bool,
+    tallies: OptionTallies,
+    is_tallies_initialized: bool,
+) -> (bool, TalliedStake, OptionTallies) {
+    let tallied = if is_tallied_initialized { tallied } else { TalliedStake::default() };
+    let tallies = initial_tallies(tallies, is_tallies_initialized);
+    let target = if is_unstaked { 0 } else { shares.amount };
+    let (added, removed) = if target > tallied.amount {
+        (target - tallied.amount, 0)
+    } else {
+        (0, tallied.amount - target)
+    };
+    match update_tally(tallies, shares.selected_option, added, removed) {
+        (true, tallies) => (true, tallied, tallies),
+        (false, tallies) => (false, TalliedStake { amount: target }, tallies),
+    }
+}
+
+/// Returns the tallies as re-encrypted for the authorized reader
+pub fn disclose_option_tallies(tallies: OptionTallies, is_tallies_initialized: bool) -> OptionTallies {
+    initial_tallies(tallies, is_tallies_initialized)
+}
+
+/// Returns (overflow, new_regular_balance)
+pub fn close_ephemeral_encrypted_token_account(
+    ephemeral_balance: EncryptedTokenBalance,
+    regular_balance: EncryptedTokenBalance,
+    is_regular_eta_initialized: bool,
+) -> (bool, EncryptedTokenBalance) {
+    credit(
+        initial_balance(regular_balance, is_regular_eta_initialized),
+        ephemeral_balance.amount,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits;
+    use arcis::testing::{thread_rng, Rng};
+    use arcis::{ArcisType, ArcisX25519Pubkey, Cipher, Enc, Mxe, Shared};
+
+    const ROUNDS: usize = 64;
+
+    fn owner() -> Shared {
+        Shared::new(ArcisX25519Pubkey::gen_input(&mut Vec::new()))
+    }
+
+    /// Random u64 biased towards the edges of the range
+    fn random_amount(rng: &mut impl Rng, max: u64) -> u64 {
+        match rng.gen_range(0..8) {
+            0 => 0,
+            1 => max,
+            2 => rng.gen_range(0..=max.min(1_000)),
+            _ => rng.gen_range(0..=max),
+        }
+    }
+
+    /// Amounts around the u64 overflow boundary, every pair is run through the circuits that add
+    /// two amounts. `u64::MAX / 2 + 1` is the smallest amount that overflows when doubled.
+    const EDGE_AMOUNTS: [u64; 6] = [0, 1, u64::MAX / 2, u64::MAX / 2 + 1, u64::MAX - 1, u64::MAX];
+
+    fn edge_pairs() -> impl Iterator<Item = (u64, u64)> {
+        EDGE_AMOUNTS
+            .into_iter()
+            .flat_map(|a| EDGE_AMOUNTS.into_iter().map(move |b| (a, b)))
+    }
+
+    fn overflows(a: u64, b: u64) -> bool {
+        a.checked_add(b).is_none()
+    }
+
+    fn timestamp(rng: &mut impl Rng) -> u64 {
+        rng.gen::<u32>() as u64
+    }
+
+    fn enc_balance(amount: u64) -> Enc<Shared, circuits::EncryptedTokenBalance> {
+        owner().from_arcis(circuits::EncryptedTokenBalance { amount })
+    }
+
+    fn enc_shares(shares: SharePurchase) -> Enc<Shared, circuits::SharePurchase> {
+        owner().from_arcis(circuits::SharePurchase {
+            amount: shares.amount,
+            selected_option: shares.selected_option,
+            weighted_staked_at: shares.weighted_staked_at,
+        })
+    }
+
+    fn balance_of(enc: Enc<Shared, circuits::EncryptedTokenBalance>) -> EncryptedTokenBalance {
+        EncryptedTokenBalance {
+            amount: enc.to_arcis().amount,
+        }
+    }
+
+    fn shares_of(enc: Enc<Shared, circuits::SharePurchase>) -> SharePurchase {
+        let shares = enc.to_arcis();
+        SharePurchase {
+            amount: shares.amount,
+            selected_option: shares.selected_option,
+            weighted_staked_at: shares.weighted_staked_at,
+        }
+    }
+
+    fn enc_tallies(tallies: OptionTallies) -> Enc<Mxe, circuits::OptionTallies> {
+        Mxe::get().from_arcis(circuits::OptionTallies {
+            amounts: tallies.amounts,
+        })
+    }
+
+    fn tallies_of(enc: Enc<Mxe, circuits::OptionTallies>) -> OptionTallies {
+        OptionTallies {
+            amounts: enc.to_arcis().amounts,
+        }
+    }
+
+    fn enc_tallied(amount: u64) -> Enc<Mxe, circuits::TalliedStake> {
+        Mxe::get().from_arcis(circuits::TalliedStake { amount })
+    }
+
+    fn tallied_of(enc: Enc<Mxe, circuits::TalliedStake>) -> TalliedStake {
+        TalliedStake {
+            amount: enc.to_arcis().amount,
+        }
+    }
+
+    /// Tallies where `option` holds at least `staked`
+    fn random_tallies(rng: &mut impl Rng, option: u16, staked: u64) -> OptionTallies {
+        let mut tallies = OptionTallies::default();
+        for amount in tallies.amounts.iter_mut() {
+            *amount = random_amount(rng, u64::MAX);
+        }
+        if let Some(amount) = tallies.amount_mut(option) {
+            *amount = staked + random_amount(rng, u64::MAX - staked);
+        }
+        tallies
+    }
+
+    /// Mostly options a market can have, sometimes one above `MAX_TALLIED_OPTIONS` that a stake
+    /// can still select since its option is encrypted
+    fn random_option(rng: &mut impl Rng) -> u16 {
+        rng.gen_range(1..=MAX_TALLIED_OPTIONS as u16 + 2)
+    }
+
+    fn random_shares(rng: &mut impl Rng, amount: u64) -> SharePurchase {
+        SharePurchase {
+            amount,
+            selected_option: rng.gen_range(1..=8),
+            weighted_staked_at: amount as u128 * timestamp(rng) as u128,
+        }
+    }
+
+    fn enc_spend(amount: u64) -> Enc<Shared, circuits::DelegationSpend> {
+        owner().from_arcis(circuits::DelegationSpend { amount })
+    }
+
+    fn spend_of(enc: Enc<Shared, circuits::DelegationSpend>) -> DelegationSpend {
+        DelegationSpend {
+            amount: enc.to_arcis().amount,
+        }
+    }
+
+    /// Spending cap and an amount already spent from it
+    fn random_spend(rng: &mut impl Rng) -> (u64, u64) {
+        let spending_cap = random_amount(rng, u64::MAX);
+        (spending_cap, random_amount(rng, spending_cap))
+    }
+
+    #[test]
+    fn wrap_encrypted_tokens_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let is_initialized = rng.gen();
+            let expected = wrap_encrypted_tokens(EncryptedTokenBalance { amount: balance }, is_initialized, amount);
+            let (error, new_balance) = circuits::wrap_encrypted_tokens(enc_balance(balance), is_initialized, amount);
+            assert_eq!((error, balance_of(new_balance)), expected, "balance {balance}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn unwrap_encrypted_tokens_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let expected = unwrap_encrypted_tokens(EncryptedTokenBalance { amount: balance }, amount);
+            let (error, sold, new_balance) = circuits::unwrap_encrypted_tokens(enc_balance(balance), amount);
+            assert_eq!((error, sold, balance_of(new_balance)), expected, "balance {balance}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn transfer_encrypted_tokens_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let sender = random_amount(rng, u64::MAX);
+            let recipient = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let is_recipient_initialized = rng.gen();
+            let expected = transfer_encrypted_tokens(
+                amount,
+                EncryptedTokenBalance { amount: sender },
+                EncryptedTokenBalance { amount: recipient },
+                is_recipient_initialized,
+            );
+            let (error, new_sender, new_recipient) = circuits::transfer_encrypted_tokens(
+                owner().from_arcis(circuits::TransferInput { amount }),
+                enc_balance(sender),
+                enc_balance(recipient),
+                is_recipient_initialized,
+            );
+            assert_eq!(
+                (error, balance_of(new_sender), balance_of(new_recipient)),
+                expected,
+                "sender {sender}, recipient {recipient}, amount {amount}"
+            );
+        }
+    }
+
+    #[test]
+    fn add_option_stake_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let min_deposit = random_amount(rng, u64::MAX);
+            let selected_option = random_option(rng);
+            let staked_at = timestamp(rng);
+            let expected = add_option_stake(
+                amount,
+                EncryptedTokenBalance { amount: balance },
+                min_deposit,
+                selected_option as u64,
+                staked_at,
+            );
+            let (error, new_balance, shares, disclosure) = circuits::add_option_stake(
+                owner().from_arcis(circuits::AddOptionStakeInput { amount }),
+                owner(),
+                enc_balance(balance),
+                owner(),
+                min_deposit,
+                selected_option as u64,
+                staked_at,
+            );
+            assert_eq!(shares_of(disclosure), expected.2);
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(shares)),
+                expected,
+                "balance {balance}, amount {amount}, min deposit {min_deposit}"
+            );
+        }
+    }
+
+    #[test]
+    fn buy_opportunity_market_shares_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let selected_option = if rng.gen() { random_option(rng) } else { rng.gen() };
+            let staked_at = timestamp(rng);
+            let min_stake = random_amount(rng, u64::MAX);
+            let max_stake = random_amount(rng, u64::MAX);
+            let expected = buy_opportunity_market_shares(
+                amount,
+                selected_option,
+                EncryptedTokenBalance { amount: balance },
+                staked_at,
+                min_stake,
+                max_stake,
+            );
+            let (error, new_balance, shares, disclosure) = circuits::buy_opportunity_market_shares(
+                owner().from_arcis(circuits::BuySharesInput {
+                    amount,
+                    selected_option,
+                }),
+                owner(),
+                enc_balance(balance),
+                owner(),
+                staked_at,
+                min_stake,
+                max_stake,
+            );
+            assert_eq!(shares_of(disclosure), expected.2);
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(shares)),
+                expected,
+                "balance {balance}, amount {amount}, min stake {min_stake}, max stake {max_stake}"
+            );
+        }
+    }
+
+    #[test]
+    fn increase_stake_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let staked = random_amount(rng, u64::MAX);
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let shares = random_shares(rng, staked);
+            let staked_at = timestamp(rng);
+            let max_stake = random_amount(rng, u64::MAX);
+            let expected =
+                increase_stake(amount, EncryptedTokenBalance { amount: balance }, shares, staked_at, max_stake);
+            let (error, new_balance, new_shares, disclosure) = circuits::increase_stake(
+                owner().from_arcis(circuits::IncreaseStakeInput { amount }),
+                owner(),
+                enc_balance(balance),
+                enc_shares(shares),
+                staked_at,
+                max_stake,
+            );
+            assert_eq!(shares_of(disclosure), expected.2);
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(new_shares)),
+                expected,
+                "balance {balance}, staked {staked}, amount {amount}, max stake {max_stake}"
+            );
+        }
+    }
+
+    #[test]
+    fn delegated_buy_opportunity_market_shares_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let selected_option = if rng.gen() { random_option(rng) } else { rng.gen() };
+            let staked_at = timestamp(rng);
+            let min_stake = random_amount(rng, u64::MAX);
+            let max_stake = random_amount(rng, u64::MAX);
+            let (spending_cap, spent) = random_spend(rng);
+            let is_spend_initialized = rng.gen();
+            let expected = delegated_buy_opportunity_market_shares(
+                amount,
+                selected_option,
+                EncryptedTokenBalance { amount: balance },
+                staked_at,
+                min_stake,
+                max_stake,
+                DelegationSpend { amount: spent },
+                is_spend_initialized,
+                spending_cap,
+            );
+            let (error, new_balance, shares, disclosure, new_spend) = circuits::delegated_buy_opportunity_market_shares(
+                owner().from_arcis(circuits::BuySharesInput {
+                    amount,
+                    selected_option,
+                }),
+                owner(),
+                enc_balance(balance),
+                owner(),
+                staked_at,
+                min_stake,
+                max_stake,
+                enc_spend(spent),
+                is_spend_initialized,
+                spending_cap,
+            );
+            assert_eq!(shares_of(disclosure), expected.2);
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(shares), spend_of(new_spend)),
+                expected,
+                "balance {balance}, amount {amount}, cap {spending_cap}, spent {spent}"
+            );
+        }
+    }
+
+    #[test]
+    fn delegated_add_option_stake_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let amount = random_amount(rng, u64::MAX);
+            let min_deposit = random_amount(rng, u64::MAX);
+            let selected_option = random_option(rng);
+            let staked_at = timestamp(rng);
+            let (spending_cap, spent) = random_spend(rng);
+            let is_spend_initialized = rng.gen();
+            let expected = delegated_add_option_stake(
+                amount,
+                EncryptedTokenBalance { amount: balance },
+                min_deposit,
+                selected_option as u64,
+                staked_at,
+                DelegationSpend { amount: spent },
+                is_spend_initialized,
+                spending_cap,
+            );
+            let (error, new_balance, shares, disclosure, new_spend) = circuits::delegated_add_option_stake(
+                owner().from_arcis(circuits::AddOptionStakeInput { amount }),
+                owner(),
+                enc_balance(balance),
+                owner(),
+                min_deposit,
+                selected_option as u64,
+                staked_at,
+                enc_spend(spent),
+                is_spend_initialized,
+                spending_cap,
+            );
+            assert_eq!(shares_of(disclosure), expected.2);
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(shares), spend_of(new_spend)),
+                expected,
+                "balance {balance}, amount {amount}, min deposit {min_deposit}, cap {spending_cap}, spent {spent}"
+            );
+        }
+    }
+
+    #[test]
+    fn reveal_shares_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let shares = random_shares(rng, random_amount(rng, u64::MAX));
+            let is_eta_initialized = rng.gen();
+            let winning_options = [0; 4].map(|_| rng.gen_range(0..=8));
+            let slash_bps = rng.gen_range(0..=10_000);
+            let expected = reveal_shares(
+                shares,
+                EncryptedTokenBalance { amount: balance },
+                is_eta_initialized,
+                winning_options,
+                slash_bps,
+            );
+            let [w0, w1, w2, w3] = winning_options;
+            let (error, amount, option, weighted_staked_at, slashed, new_balance) = circuits::reveal_shares(
+                enc_shares(shares),
+                enc_balance(balance),
+                is_eta_initialized,
+                w0,
+                w1,
+                w2,
+                w3,
+                slash_bps,
+            );
+            assert_eq!(
+                (error, amount, option, weighted_staked_at, slashed, balance_of(new_balance)),
+                expected,
+                "shares {shares:?}, balance {balance}, winning options {winning_options:?}, slash {slash_bps}"
+            );
+        }
+    }
+
+    #[test]
+    fn unstake_early_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let balance = random_amount(rng, u64::MAX);
+            let shares = random_shares(rng, random_amount(rng, u64::MAX));
+            let is_eta_initialized = rng.gen();
+            let expected = unstake_early(shares, EncryptedTokenBalance { amount: balance }, is_eta_initialized);
+            let (error, new_balance) = circuits::unstake_early(enc_shares(shares), enc_balance(balance), is_eta_initialized);
+            assert_eq!((error, balance_of(new_balance)), expected, "shares {shares:?}, balance {balance}");
+        }
+    }
+
+    #[test]
+    fn sync_option_tally_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let shares = SharePurchase {
+                selected_option: random_option(rng),
+                ..random_shares(rng, random_amount(rng, u64::MAX))
+            };
+            // Most syncs catch up with a stake that grew since it was last tallied
+            let tallied = random_amount(rng, shares.amount);
+            let is_tallied_initialized = rng.gen();
+            let is_unstaked = rng.gen();
+            let tallies = random_tallies(rng, shares.selected_option, tallied);
+            // Both are first written by the same sync
+            let is_tallies_initialized = is_tallied_initialized || rng.gen();
+            let expected = sync_option_tally(
+                shares,
+                TalliedStake { amount: tallied },
+                is_tallied_initialized,
+                is_unstaked,
+                tallies,
+                is_tallies_initialized,
+            );
+            let (error, new_tallied, new_tallies) = circuits::sync_option_tally(
+                enc_shares(shares),
+                enc_tallied(tallied),
+                is_tallied_initialized,
+                is_unstaked,
+                enc_tallies(tallies),
+                is_tallies_initialized,
+            );
+            assert_eq!(
+                (error, tallied_of(new_tallied), tallies_of(new_tallies)),
+                expected,
+                "shares {shares:?}, tallied {tallied}, unstaked {is_unstaked}, tallies {tallies:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn disclose_option_tallies_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let tallies = random_tallies(rng, 1, 0);
+            let is_tallies_initialized = rng.gen();
+            let expected = disclose_option_tallies(tallies, is_tallies_initialized);
+            let disclosed =
+                circuits::disclose_option_tallies(enc_tallies(tallies), is_tallies_initialized, owner()).to_arcis();
+            assert_eq!(OptionTallies { amounts: disclosed.amounts }, expected, "tallies {tallies:?}");
+        }
+    }
+
+    #[test]
+    fn close_ephemeral_encrypted_token_account_matches_circuit() {
+        let rng = &mut thread_rng();
+        for _ in 0..ROUNDS {
+            let regular = random_amount(rng, u64::MAX);
+            let ephemeral = random_amount(rng, u64::MAX);
+            let is_regular_eta_initialized = rng.gen();
+            let expected = close_ephemeral_encrypted_token_account(
+                EncryptedTokenBalance { amount: ephemeral },
+                EncryptedTokenBalance { amount: regular },
+                is_regular_eta_initialized,
+            );
+            let (error, new_balance) = circuits::close_ephemeral_encrypted_token_account(
+                enc_balance(ephemeral),
+                enc_balance(regular),
+                is_regular_eta_initialized,
+            );
+            assert_eq!((error, balance_of(new_balance)), expected, "ephemeral {ephemeral}, regular {regular}");
+        }
+    }
+
+    #[test]
+    fn wrap_encrypted_tokens_at_u64_boundaries() {
+        for (balance, amount) in edge_pairs() {
+            let expected = wrap_encrypted_tokens(EncryptedTokenBalance { amount: balance }, true, amount);
+            let (error, new_balance) = circuits::wrap_encrypted_tokens(enc_balance(balance), true, amount);
+            assert_eq!((error, balance_of(new_balance)), expected, "balance {balance}, amount {amount}");
+            assert_eq!(error, overflows(balance, amount), "balance {balance}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn transfer_encrypted_tokens_at_u64_boundaries() {
+        // The sender always holds enough, so only the recipient credit can fail
+        for (recipient, amount) in edge_pairs() {
+            let expected = transfer_encrypted_tokens(
+                amount,
+                EncryptedTokenBalance { amount: u64::MAX },
+                EncryptedTokenBalance { amount: recipient },
+                true,
+            );
+            let (error, new_sender, new_recipient) = circuits::transfer_encrypted_tokens(
+                owner().from_arcis(circuits::TransferInput { amount }),
+                enc_balance(u64::MAX),
+                enc_balance(recipient),
+                true,
+            );
+            assert_eq!(
+                (error, balance_of(new_sender), balance_of(new_recipient)),
+                expected,
+                "recipient {recipient}, amount {amount}"
+            );
+            assert_eq!(error, overflows(recipient, amount), "recipient {recipient}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn increase_stake_at_u64_boundaries() {
+        // The balance always covers the amount, so only the staked amount can overflow
+        for (staked, amount) in edge_pairs() {
+            let shares = SharePurchase {
+                amount: staked,
+                selected_option: 1,
+                weighted_staked_at: staked as u128,
+            };
+            let expected = increase_stake(amount, EncryptedTokenBalance { amount }, shares, 1, u64::MAX);
+            let (error, new_balance, new_shares, _) = circuits::increase_stake(
+                owner().from_arcis(circuits::IncreaseStakeInput { amount }),
+                owner(),
+                enc_balance(amount),
+                enc_shares(shares),
+                1,
+                u64::MAX,
+            );
+            assert_eq!(
+                (error, balance_of(new_balance), shares_of(new_shares)),
+                expected,
+                "staked {staked}, amount {amount}"
+            );
+            assert_eq!(error == 5, overflows(staked, amount), "staked {staked}, amount {amount}");
+
+            // A max_stake right at or just below the increased position
+            if !overflows(staked, amount) {
+                let total = staked + amount;
+                for max_stake in [total, total.saturating_sub(1)] {
+                    let (error, _, new_shares, _) = circuits::increase_stake(
+                        owner().from_arcis(circuits::IncreaseStakeInput { amount }),
+                        owner(),
+                        enc_balance(amount),
+                        enc_shares(shares),
+                        1,
+                        max_stake,
+                    );
+                    let expected = increase_stake(amount, EncryptedTokenBalance { amount }, shares, 1, max_stake);
+                    assert_eq!((error, shares_of(new_shares)), (expected.0, expected.2));
+                    assert_eq!(error == 3, total > max_stake, "staked {staked}, amount {amount}, max {max_stake}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn delegated_buy_opportunity_market_shares_at_u64_boundaries() {
+        // Stake limits and balance always pass, so only the spending cap can reject the stake
+        for (spent, amount) in edge_pairs() {
+            for spending_cap in [u64::MAX, spent, spent.saturating_add(amount), amount] {
+                let expected = delegated_buy_opportunity_market_shares(
+                    amount,
+                    1,
+                    EncryptedTokenBalance { amount },
+                    1,
+                    0,
+                    u64::MAX,
+                    DelegationSpend { amount: spent },
+                    true,
+                    spending_cap,
+                );
+                let (error, new_balance, shares, _, new_spend) = circuits::delegated_buy_opportunity_market_shares(
+                    owner().from_arcis(circuits::BuySharesInput {
+                        amount,
+                        selected_option: 1,
+                    }),
+                    owner(),
+                    enc_balance(amount),
+                    owner(),
+                    1,
+                    0,
+                    u64::MAX,
+                    enc_spend(spent),
+                    true,
+                    spending_cap,
+                );
+                assert_eq!(
+                    (error, balance_of(new_balance), shares_of(shares), spend_of(new_spend)),
+                    expected,
+                    "spent {spent}, amount {amount}, cap {spending_cap}"
+                );
+                let above_cap = overflows(spent, amount) || spent + amount > spending_cap;
+                assert_eq!(error == 4, above_cap, "spent {spent}, amount {amount}, cap {spending_cap}");
+            }
+        }
+    }
+
+    #[test]
+    fn delegated_add_option_stake_at_u64_boundaries() {
+        for (spent, amount) in edge_pairs() {
+            for spending_cap in [u64::MAX, spent, spent.saturating_add(amount), amount] {
+                let expected = delegated_add_option_stake(
+                    amount,
+                    EncryptedTokenBalance { amount },
+                    0,
+                    1,
+                    1,
+                    DelegationSpend { amount: spent },
+                    true,
+                    spending_cap,
+                );
+                let (error, new_balance, shares, _, new_spend) = circuits::delegated_add_option_stake(
+                    owner().from_arcis(circuits::AddOptionStakeInput { amount }),
+                    owner(),
+                    enc_balance(amount),
+                    owner(),
+                    0,
+                    1,
+                    1,
+                    enc_spend(spent),
+                    true,
+                    spending_cap,
+                );
+                assert_eq!(
+                    (error, balance_of(new_balance), shares_of(shares), spend_of(new_spend)),
+                    expected,
+                    "spent {spent}, amount {amount}, cap {spending_cap}"
+                );
+                let above_cap = overflows(spent, amount) || spent + amount > spending_cap;
+                assert_eq!(error == 4, above_cap, "spent {spent}, amount {amount}, cap {spending_cap}");
+            }
+        }
+    }
+
+    #[test]
+    fn reveal_shares_at_u64_boundaries() {
+        // Winning stake, refunded in full
+        for (balance, amount) in edge_pairs() {
+            let shares = SharePurchase {
+                amount,
+                selected_option: 1,
+                weighted_staked_at: amount as u128,
+            };
+            let expected = reveal_shares(shares, EncryptedTokenBalance { amount: balance }, true, [1, 0, 0, 0], 10_000);
+            let (error, revealed, option, weighted_staked_at, slashed, new_balance) = circuits::reveal_shares(
+                enc_shares(shares),
+                enc_balance(balance),
+                true,
+                1,
+                0,
+                0,
+                0,
+                10_000,
+            );
+            assert_eq!(
+                (error, revealed, option, weighted_staked_at, slashed, balance_of(new_balance)),
+                expected,
+                "balance {balance}, amount {amount}"
+            );
+            assert_eq!(error, overflows(balance, amount), "balance {balance}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn unstake_early_at_u64_boundaries() {
+        for (balance, amount) in edge_pairs() {
+            let shares = SharePurchase {
+                amount,
+                selected_option: 1,
+                weighted_staked_at: amount as u128,
+            };
+            let expected = unstake_early(shares, EncryptedTokenBalance { amount: balance }, true);
+            let (error, new_balance) = circuits::unstake_early(enc_shares(shares), enc_balance(balance), true);
+            assert_eq!((error, balance_of(new_balance)), expected, "balance {balance}, amount {amount}");
+            assert_eq!(error, overflows(balance, amount), "balance {balance}, amount {amount}");
+        }
+    }
+
+    #[test]
+    fn close_ephemeral_encrypted_token_account_at_u64_boundaries() {
+        for (regular, ephemeral) in edge_pairs() {
+            let expected = close_ephemeral_encrypted_token_account(
+                EncryptedTokenBalance { amount: ephemeral },
+                EncryptedTokenBalance { amount: regular },
+                true,
+            );
+            let (error, new_balance) =
+                circuits::close_ephemeral_encrypted_token_account(enc_balance(ephemeral), enc_balance(regular), true);
+            assert_eq!((error, balance_of(new_balance)), expected, "ephemeral {ephemeral}, regular {regular}");
+            assert_eq!(error, overflows(regular, ephemeral), "ephemeral {ephemeral}, regular {regular}");
+        }
+    }
+
+    #[test]
+    fn sync_option_tally_at_u64_boundaries() {
+        // A fresh share account whose whole stake is added to its option's tally
+        for (tallied, amount) in edge_pairs() {
+            let shares = SharePurchase {
+                amount,
+                selected_option: 1,
+                weighted_staked_at: amount as u128,
+            };
+            let mut tallies = OptionTallies::default();
+            tallies.amounts[0] = tallied;
+            let expected = sync_option_tally(shares, TalliedStake::default(), false, false, tallies, true);
+            let (error, new_tallied, new_tallies) = circuits::sync_option_tally(
+                enc_shares(shares),
+                enc_tallied(0),
+                false,
+                false,
+                enc_tallies(tallies),
+                true,
+            );
+            assert_eq!(
+                (error, tallied_of(new_tallied), tallies_of(new_tallies)),
+                expected,
+                "tallied {tallied}, amount {amount}"
+            );
+            assert_eq!(error, overflows(tallied, amount), "tallied {tallied}, amount {amount}");
+        }
+    }
+}
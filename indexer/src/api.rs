@@ -0,0 +1,73 @@
+//! Read-only JSON HTTP API over the indexed data:
+//!
+//! - `GET /markets`: every market
+//! - `GET /markets/{market}`: a market with its options
+//! - `GET /markets/{market}/timeline`: every event of a market, in order
+//! - `GET /users/{owner}/positions`: share accounts and encrypted token accounts of a wallet
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::error::IndexerError;
+use crate::store::Store;
+
+/// Status code and JSON body of an API response
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl ApiResponse {
+    fn ok(body: impl Serialize) -> Self {
+        Self {
+            status: 200,
+            body: serde_json::to_value(body).unwrap_or(Value::Null),
+        }
+    }
+
+    fn error(status: u16, message: impl ToString) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+}
+
+/// Routes a request to the store, independent of the HTTP server so it can be tested directly.
+pub fn handle(store: &Store, method: &Method, url: &str) -> ApiResponse {
+    if *method != Method::Get {
+        return ApiResponse::error(405, "Method not allowed");
+    }
+
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let result = match segments.as_slice() {
+        ["markets"] => store.markets().map(ApiResponse::ok),
+        ["markets", market] => store.market(market).and_then(|row| match row {
+            Some(row) => Ok(ApiResponse::ok(json!({ "market": row, "options": store.options(market)? }))),
+            None => Ok(ApiResponse::error(404, "Market not found")),
+        }),
+        ["markets", market, "timeline"] => store.timeline(market).map(ApiResponse::ok),
+        ["users", owner, "positions"] => store.positions(owner).map(ApiResponse::ok),
+        _ => Ok(ApiResponse::error(404, "Not found")),
+    };
+    result.unwrap_or_else(|err| ApiResponse::error(500, err))
+}
+
+/// Serves the API on `addr` until the process is stopped
+pub fn serve(store: &Store, addr: &str) -> Result<(), IndexerError> {
+    let server = Server::http(addr).map_err(|err| IndexerError::Server(err.to_string()))?;
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+
+    for request in server.incoming_requests() {
+        let response = handle(store, request.method(), request.url());
+        let body = Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        // The client may have hung up, nothing to do about it
+        let _ = request.respond(body);
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! Decoding of the Anchor events emitted by the program (`emit!` logs
+//! `discriminator || borsh(event)` as `Program data:`).
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use opportunity_market::events::*;
+use opportunity_market::{MarketParams, ScoringConfig, WinningOption};
+use serde_json::{json, Map, Value};
+
+/// Event field as it appears in the JSON API. Pubkeys are base58, ciphertexts hex and
+/// u128 nonces decimal strings, since JSON numbers can't hold them exactly.
+trait JsonField {
+    fn to_json(&self) -> Value;
+}
+
+macro_rules! json_field_as_is {
+    ($($ty:ty),*) => {
+        $(impl JsonField for $ty {
+            fn to_json(&self) -> Value {
+                json!(self)
+            }
+        })*
+    };
+}
+
+json_field_as_is!(bool, u16, u64, i64, String);
+
+impl JsonField for u128 {
+    fn to_json(&self) -> Value {
+        json!(self.to_string())
+    }
+}
+
+impl JsonField for Pubkey {
+    fn to_json(&self) -> Value {
+        json!(self.to_string())
+    }
+}
+
+impl JsonField for [u8; 32] {
+    fn to_json(&self) -> Value {
+        json!(hex::encode(self))
+    }
+}
+
+impl<const N: usize> JsonField for [[u8; 32]; N] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(JsonField::to_json).collect())
+    }
+}
+
+impl<T: JsonField> JsonField for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, JsonField::to_json)
+    }
+}
+
+impl JsonField for Vec<WinningOption> {
+    fn to_json(&self) -> Value {
+        self.iter()
+            .map(|winner| json!({ "option_index": winner.option_index, "weight": winner.weight }))
+            .collect()
+    }
+}
+
+impl JsonField for StakeErrorReason {
+    fn to_json(&self) -> Value {
+        json!(match self {
+            StakeErrorReason::ComputationFailed => "computation_failed",
+            StakeErrorReason::InsufficientBalance => "insufficient_balance",
+            StakeErrorReason::BelowMinimum => "below_minimum",
+            StakeErrorReason::AboveMaximum => "above_maximum",
+            StakeErrorReason::AboveDelegationCap => "above_delegation_cap",
+            StakeErrorReason::Overflow => "overflow",
+        })
+    }
+}
+
+impl JsonField for ScoringConfig {
+    fn to_json(&self) -> Value {
+        match self {
+            ScoringConfig::Linear => json!({ "kind": "linear" }),
+            ScoringConfig::ExponentialDecay { half_life_seconds } => {
+                json!({ "kind": "exponential_decay", "half_life_seconds": half_life_seconds })
+            }
+            ScoringConfig::StepTiers { tiers } => json!({
+                "kind": "step_tiers",
+                "tiers": tiers
+                    .iter()
+                    .map(|tier| json!({ "until_seconds": tier.until_seconds, "factor": tier.factor }))
+                    .collect::<Vec<_>>(),
+            }),
+            ScoringConfig::Flat => json!({ "kind": "flat" }),
+        }
+    }
+}
+
+impl JsonField for MarketParams {
+    fn to_json(&self) -> Value {
+        json!({
+            "reward_amount": self.reward_amount,
+            "time_to_stake": self.time_to_stake,
+            "time_to_reveal": self.time_to_reveal,
+            "market_authority": self.market_authority.to_json(),
+            "unstake_delay_seconds": self.unstake_delay_seconds,
+            "authorized_reader_pubkey": self.authorized_reader_pubkey.to_json(),
+            "allow_closing_early": self.allow_closing_early,
+            "earliness_cutoff_seconds": self.earliness_cutoff_seconds,
+            "scoring_config": self.scoring_config.to_json(),
+            "losing_stake_slash_bps": self.losing_stake_slash_bps,
+            "min_stake": self.min_stake,
+            "max_stake": self.max_stake,
+        })
+    }
+}
+
+macro_rules! program_events {
+    ($($event:ident { $($field:ident),* $(,)? }),* $(,)?) => {
+        /// Every event emitted by the program
+        pub enum ProgramEvent {
+            $($event($event),)*
+        }
+
+        impl ProgramEvent {
+            /// Decodes `Program data:` bytes, `None` if they are not one of the program's events
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(mut payload) = data.strip_prefix($event::DISCRIMINATOR) {
+                        return $event::deserialize(&mut payload).ok().map(ProgramEvent::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ProgramEvent::$event(_) => stringify!($event),)*
+                }
+            }
+
+            pub fn timestamp(&self) -> i64 {
+                match self {
+                    $(ProgramEvent::$event(event) => event.timestamp,)*
+                }
+            }
+
+            /// Event fields without the timestamp
+            pub fn to_json(&self) -> Value {
+                match self {
+                    $(ProgramEvent::$event(event) => {
+                        let mut fields = Map::new();
+                        $(fields.insert(stringify!($field).to_string(), event.$field.to_json());)*
+                        Value::Object(fields)
+                    })*
+                }
+            }
+        }
+    };
+}
+
+program_events! {
+    MarketCreatedEvent {
+        market, creator, index, time_to_stake, time_to_reveal, market_authority, authorized_reader_pubkey,
+        unstake_delay_seconds, allow_closing_early, earliness_cutoff_seconds, scoring_config,
+        losing_stake_slash_bps, min_stake, max_stake, planned_open_timestamp,
+    },
+    MarketTemplateCreatedEvent { template, creator, template_id, mint, reward_mint, params },
+    MarketCreatedFromTemplateEvent { market, template, series_index },
+    MarketOptionCreatedEvent { option, market, creator, by_market_creator, index, name },
+    MarketOpenedEvent { market, creator, open_timestamp },
+    MarketClosedEvent { market, creator, remaining_amount },
+    MarketSponsoredEvent { market, sponsor, amount, sponsor_total, reward_amount },
+    SponsorRefundedEvent { market, sponsor, amount, reward_amount },
+    MarketSponsorClosedEvent { market, sponsor, amount },
+    MarketOptionClosedEvent { market, option, index },
+    MarketCancelledEvent { market, authority, refunded_amount },
+    OptionSelectedEvent { market, authority, selected_option, winning_options },
+    RevealPeriodExtendedEvent { market, authority, new_time_to_reveal },
+    MarketRewardsReclaimedEvent { market, creator, amount },
+    ProtocolFeeCollectedEvent { market, share_account, amount },
+    ProtocolFeesClaimedEvent { market, treasury, amount },
+    EncryptedTokenAccountInitializedEvent { encrypted_token_account, owner, token_mint },
+    EphemeralEncryptedTokenAccountInitializedEvent { encrypted_token_account, owner, token_mint, index, rent_payer },
+    EncryptedTokensWrappedEvent { encrypted_token_account, user, deposit_amount, encrypted_new_balance, nonce },
+    EncryptedTokensWrappedError { user, encrypted_token_account, pending_deposit },
+    EncryptedTokensUnwrappedEvent { user, encrypted_token_account, amount_withdrawn, encrypted_new_balance, nonce },
+    EncryptedTokensUnwrappedError { user },
+    EncryptedTokensTransferredEvent {
+        sender, recipient, sender_encrypted_token_account, recipient_encrypted_token_account,
+        sender_encrypted_new_balance, sender_nonce, recipient_encrypted_new_balance, recipient_nonce,
+    },
+    EncryptedTokensTransferredError { sender, recipient },
+    PendingDepositClaimedEvent { user, encrypted_token_account, amount },
+    EphemeralAccountClosedEvent { user, encrypted_token_account, encrypted_new_balance, nonce },
+    EphemeralAccountClosedError { user },
+    ShareAccountInitializedEvent { share_account, owner, market },
+    StakedEvent {
+        user, market, encrypted_token_account, share_account, share_encrypted_state, share_state_nonce,
+        share_encrypted_state_disclosure, share_state_disclosure_nonce, encrypted_eta_balance, eta_balance_nonce,
+    },
+    StakedError { user, reason },
+    StakeDelegationCreatedEvent { delegation, encrypted_token_account, owner, delegate, spending_cap, expires_at },
+    StakeDelegationRevokedEvent { delegation, owner, delegate },
+    DelegatedStakeEvent { delegation, owner, delegate, share_account, encrypted_spent, spent_nonce },
+    StakeIncreasedEvent {
+        user, market, encrypted_token_account, share_account, share_encrypted_state, share_state_nonce,
+        share_encrypted_state_disclosure, share_state_disclosure_nonce, encrypted_eta_balance, eta_balance_nonce,
+    },
+    UnstakeInitiatedEvent { user, market, share_account, unstakeable_at_timestamp },
+    UnstakedEvent { user, market, encrypted_token_account, share_account },
+    UnstakedError { user },
+    StakeRevealedEvent {
+        user, market, encrypted_token_account, share_account, shares_amount, selected_option, entry_timestamp,
+        slashed_amount,
+    },
+    StakeRevealedError { user },
+    TallyIncrementedEvent { owner, market, share_account, option, revealed_amount, user_score },
+    TallyIncrementedError { owner, market, share_account, option },
+    RewardClaimedEvent { owner, market, share_account, option, reward_amount },
+    ShareAccountClosedEvent { owner, market, share_account },
+    OptionTalliesDisclosedEvent { market, encrypted_tallies, nonce },
+    OptionTalliesDisclosedError { market },
+    OptionTallySyncedEvent { market, share_account },
+    OptionTallySyncedError { market, share_account },
+}
+
+impl ProgramEvent {
+    /// Market the event belongs to, if any
+    pub fn market(&self) -> Option<String> {
+        self.to_json().get("market").and_then(Value::as_str).map(str::to_string)
+    }
+
+    /// Wallet that acted or was acted upon, if any
+    pub fn wallet(&self) -> Option<String> {
+        let fields = self.to_json();
+        ["user", "owner", "sender", "creator", "sponsor"]
+            .iter()
+            .find_map(|key| fields.get(*key).and_then(Value::as_str))
+            .map(str::to_string)
+    }
+}
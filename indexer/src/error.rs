@@ -0,0 +1,41 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum IndexerError {
+    // SQLite query or schema error
+    Database(rusqlite::Error),
+
+    // Reading a log file or stdin failed
+    Io(std::io::Error),
+
+    // A JSON transaction record could not be parsed, with its line number
+    InvalidRecord(usize, String),
+
+    // The HTTP server could not bind its address
+    Server(String),
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::Database(err) => write!(f, "Database error: {err}"),
+            IndexerError::Io(err) => write!(f, "I/O error: {err}"),
+            IndexerError::InvalidRecord(line, err) => write!(f, "Invalid transaction record on line {line}: {err}"),
+            IndexerError::Server(err) => write!(f, "Server error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(err: rusqlite::Error) -> Self {
+        IndexerError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for IndexerError {
+    fn from(err: std::io::Error) -> Self {
+        IndexerError::Io(err)
+    }
+}
@@ -0,0 +1,26 @@
+//! Off-chain indexer for the `opportunity_market` program: decodes the program's Anchor events
+//! from transaction logs, keeps markets, options, share accounts and encrypted token accounts in
+//! SQLite and serves per-market timelines and per-user positions over a JSON HTTP API.
+
+pub mod api;
+pub mod decode;
+pub mod error;
+pub mod logs;
+pub mod store;
+
+pub use error::IndexerError;
+pub use opportunity_market::ID as OPPORTUNITY_MARKET_PROGRAM_ID;
+
+use std::io::BufRead;
+
+use logs::LogReader;
+use store::Store;
+
+/// Indexes every transaction read from `reader`, returning how many new events were stored
+pub fn ingest(store: &mut Store, reader: impl BufRead, program_id: &str) -> Result<usize, IndexerError> {
+    let mut inserted = 0;
+    for tx in LogReader::new(reader) {
+        inserted += store.ingest(&tx?, program_id)?;
+    }
+    Ok(inserted)
+}
@@ -0,0 +1,179 @@
+//! Transaction logs as read from a ledger replay or a live `solana logs` stream.
+//!
+//! Two input formats are accepted and detected from the first non-empty line:
+//! - JSON lines, one `getTransaction` RPC result per line (`slot`, `blockTime`,
+//!   `transaction.signatures`, `meta.err`, `meta.logMessages`)
+//! - The plain text printed by `solana logs`, one `Transaction executed in slot` block per
+//!   transaction. A block is only complete once the next one starts or the input ends.
+
+use std::io::{BufRead, Lines};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+
+use crate::error::IndexerError;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+const TEXT_TRANSACTION_PREFIX: &str = "Transaction executed in slot ";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoggedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    // Failed transactions roll back every event they logged
+    pub succeeded: bool,
+    pub logs: Vec<String>,
+}
+
+impl LoggedTransaction {
+    /// Payloads of `Program data:` lines logged by `program_id` itself, in log order. Data
+    /// logged by programs it invokes, or by programs invoking it, is skipped.
+    pub fn program_data(&self, program_id: &str) -> Vec<Vec<u8>> {
+        let mut invoked: Vec<&str> = Vec::new();
+        let mut data = Vec::new();
+        for line in &self.logs {
+            if let Some(payload) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+                if invoked.last() != Some(&program_id) {
+                    continue;
+                }
+                // `sol_log_data` joins several slices with spaces, Anchor events are a single one
+                let encoded = payload.split(' ').next().unwrap_or_default();
+                if let Ok(bytes) = STANDARD.decode(encoded) {
+                    data.push(bytes);
+                }
+            } else if let Some(rest) = line.strip_prefix("Program ") {
+                let mut words = rest.split(' ');
+                let program = words.next().unwrap_or_default();
+                match words.next() {
+                    Some("invoke") => invoked.push(program),
+                    Some("success") | Some("failed:") => {
+                        invoked.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        data
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionRecord {
+    slot: u64,
+    block_time: Option<i64>,
+    transaction: TransactionSignatures,
+    meta: TransactionMeta,
+}
+
+#[derive(Deserialize)]
+struct TransactionSignatures {
+    signatures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionMeta {
+    err: Option<serde_json::Value>,
+    #[serde(default)]
+    log_messages: Vec<String>,
+}
+
+impl From<TransactionRecord> for LoggedTransaction {
+    fn from(record: TransactionRecord) -> Self {
+        LoggedTransaction {
+            signature: record.transaction.signatures.into_iter().next().unwrap_or_default(),
+            slot: record.slot,
+            block_time: record.block_time,
+            succeeded: record.meta.err.is_none(),
+            logs: record.meta.log_messages,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    JsonLines,
+    Text,
+}
+
+/// Iterates over the transactions of a log file or stream, in input order.
+pub struct LogReader<R> {
+    lines: Lines<R>,
+    line_number: usize,
+    format: Option<Format>,
+    pending: Option<LoggedTransaction>,
+}
+
+impl<R: BufRead> LogReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_number: 0,
+            format: None,
+            pending: None,
+        }
+    }
+
+    fn parse_json(&self, line: &str) -> Result<LoggedTransaction, IndexerError> {
+        serde_json::from_str::<TransactionRecord>(line)
+            .map(LoggedTransaction::from)
+            .map_err(|err| IndexerError::InvalidRecord(self.line_number, err.to_string()))
+    }
+
+    /// Applies a line of `solana logs` output, returning the previous transaction once a new one starts
+    fn parse_text(&mut self, line: &str) -> Option<LoggedTransaction> {
+        if let Some(slot) = line.strip_prefix(TEXT_TRANSACTION_PREFIX) {
+            let next = LoggedTransaction {
+                slot: slot.trim_end_matches(':').parse().unwrap_or_default(),
+                ..Default::default()
+            };
+            return self.pending.replace(next);
+        }
+
+        let tx = self.pending.as_mut()?;
+        if let Some(signature) = line.strip_prefix("Signature: ") {
+            tx.signature = signature.to_string();
+        } else if let Some(status) = line.strip_prefix("Status: ") {
+            tx.succeeded = status == "Ok";
+        } else if line != "Log Messages:" {
+            tx.logs.push(line.to_string());
+        }
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for LogReader<R> {
+    type Item = Result<LoggedTransaction, IndexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Some(Err(err.into())),
+                None => return self.pending.take().map(Ok),
+            };
+            self.line_number += 1;
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let format = *self.format.get_or_insert(if line.starts_with('{') {
+                Format::JsonLines
+            } else {
+                Format::Text
+            });
+            match format {
+                Format::JsonLines => return Some(self.parse_json(line)),
+                Format::Text => {
+                    if let Some(tx) = self.parse_text(line) {
+                        return Some(Ok(tx));
+                    }
+                }
+            }
+        }
+    }
+}
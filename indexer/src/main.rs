@@ -0,0 +1,66 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use opportunity_market_indexer::{api, ingest, store::Store, IndexerError, OPPORTUNITY_MARKET_PROGRAM_ID};
+
+#[derive(Parser)]
+#[command(about = "Indexes opportunity_market events into SQLite and serves them over HTTP")]
+struct Cli {
+    /// SQLite database file, created if missing
+    #[arg(long, default_value = "opportunity-market-indexer.sqlite")]
+    db: PathBuf,
+
+    /// Program whose events are indexed
+    #[arg(long, default_value_t = OPPORTUNITY_MARKET_PROGRAM_ID.to_string())]
+    program_id: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index transaction logs: JSON lines of `getTransaction` results or `solana logs` output.
+    /// Reads stdin when no file is given, so `solana logs <program> | ... ingest` indexes live.
+    Ingest { files: Vec<PathBuf> },
+
+    /// Serve the JSON API
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+    },
+}
+
+fn run(cli: Cli) -> Result<(), IndexerError> {
+    let mut store = Store::open(&cli.db)?;
+    match cli.command {
+        Command::Ingest { files } if files.is_empty() => {
+            let inserted = ingest(&mut store, io::stdin().lock(), &cli.program_id)?;
+            eprintln!("Indexed {inserted} events from stdin");
+        }
+        Command::Ingest { files } => {
+            for path in files {
+                let inserted = ingest(&mut store, BufReader::new(File::open(&path)?), &cli.program_id)?;
+                eprintln!("Indexed {inserted} events from {}", path.display());
+            }
+        }
+        Command::Serve { bind } => {
+            eprintln!("Serving on http://{bind}");
+            api::serve(&store, &bind)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
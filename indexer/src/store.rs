@@ -0,0 +1,669 @@
+//! SQLite storage. Every decoded event is kept in `events` (the per-market timeline), and
+//! folded into one row per market, option, share account and encrypted token account.
+//!
+//! Events are keyed by transaction signature and position, so replaying a ledger file that
+//! overlaps with what is already indexed is a no-op for the overlapping part.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::decode::ProgramEvent;
+use crate::error::IndexerError;
+use crate::logs::LoggedTransaction;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    market TEXT,
+    wallet TEXT,
+    timestamp INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_by_market ON events (market, slot, timestamp);
+CREATE INDEX IF NOT EXISTS events_by_wallet ON events (wallet, slot, timestamp);
+
+CREATE TABLE IF NOT EXISTS markets (
+    address TEXT PRIMARY KEY,
+    creator TEXT NOT NULL,
+    market_index INTEGER NOT NULL,
+    time_to_stake INTEGER NOT NULL,
+    time_to_reveal INTEGER NOT NULL,
+    market_authority TEXT,
+    unstake_delay_seconds INTEGER NOT NULL,
+    allow_closing_early INTEGER NOT NULL,
+    earliness_cutoff_seconds INTEGER NOT NULL,
+    scoring_config TEXT NOT NULL,
+    losing_stake_slash_bps INTEGER NOT NULL,
+    min_stake INTEGER NOT NULL,
+    max_stake INTEGER,
+    template TEXT,
+    series_index INTEGER,
+    planned_open_timestamp INTEGER,
+    open_timestamp INTEGER,
+    selected_option INTEGER,
+    winning_options TEXT NOT NULL DEFAULT '[]',
+    total_rewards_claimed INTEGER NOT NULL DEFAULT 0,
+    protocol_fees_collected INTEGER NOT NULL DEFAULT 0,
+    total_slashed INTEGER NOT NULL DEFAULT 0,
+    total_sponsored INTEGER NOT NULL DEFAULT 0,
+    cancelled INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS options (
+    market TEXT NOT NULL,
+    option_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    creator TEXT NOT NULL,
+    by_market_creator INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    total_shares INTEGER NOT NULL DEFAULT 0,
+    total_score INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (market, option_index)
+);
+
+CREATE TABLE IF NOT EXISTS share_accounts (
+    address TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    market TEXT NOT NULL,
+    staked_at INTEGER,
+    unstakeable_at INTEGER,
+    unstaked_at INTEGER,
+    revealed_amount INTEGER,
+    revealed_option INTEGER,
+    entry_timestamp INTEGER,
+    slashed_amount INTEGER,
+    user_score INTEGER,
+    reward_amount INTEGER,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS share_accounts_by_owner ON share_accounts (owner);
+
+CREATE TABLE IF NOT EXISTS encrypted_token_accounts (
+    address TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    token_mint TEXT NOT NULL,
+    eta_index INTEGER NOT NULL,
+    rent_payer TEXT,
+    encrypted_balance TEXT,
+    state_nonce TEXT,
+    total_wrapped INTEGER NOT NULL DEFAULT 0,
+    total_unwrapped INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS encrypted_token_accounts_by_owner ON encrypted_token_accounts (owner);
+";
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MarketRow {
+    pub address: String,
+    pub creator: String,
+    pub market_index: u64,
+    pub time_to_stake: u64,
+    pub time_to_reveal: u64,
+    pub market_authority: Option<String>,
+    pub unstake_delay_seconds: u64,
+    pub allow_closing_early: bool,
+    pub earliness_cutoff_seconds: u64,
+    pub scoring_config: Value,
+    pub losing_stake_slash_bps: u16,
+    pub min_stake: u64,
+    pub max_stake: Option<u64>,
+    pub template: Option<String>,
+    pub series_index: Option<u64>,
+    pub planned_open_timestamp: Option<u64>,
+    pub open_timestamp: Option<u64>,
+    pub selected_option: Option<u16>,
+    pub winning_options: Value,
+    pub total_rewards_claimed: u64,
+    pub protocol_fees_collected: u64,
+    pub total_slashed: u64,
+    /// Sponsor contributions to the reward pool that have not been refunded
+    pub total_sponsored: u64,
+    pub cancelled: bool,
+    pub closed: bool,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OptionRow {
+    pub market: String,
+    pub option_index: u16,
+    pub address: String,
+    pub creator: String,
+    pub by_market_creator: bool,
+    pub name: String,
+    pub total_shares: u64,
+    pub total_score: u64,
+    pub closed: bool,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ShareAccountRow {
+    pub address: String,
+    pub owner: String,
+    pub market: String,
+    pub staked_at: Option<i64>,
+    pub unstakeable_at: Option<u64>,
+    pub unstaked_at: Option<i64>,
+    pub revealed_amount: Option<u64>,
+    pub revealed_option: Option<u16>,
+    pub entry_timestamp: Option<u64>,
+    pub slashed_amount: Option<u64>,
+    pub user_score: Option<u64>,
+    pub reward_amount: Option<u64>,
+    pub closed: bool,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EncryptedTokenAccountRow {
+    pub address: String,
+    pub owner: String,
+    pub token_mint: String,
+    pub eta_index: u64,
+    pub rent_payer: Option<String>,
+    pub encrypted_balance: Option<String>,
+    pub state_nonce: Option<String>,
+    pub total_wrapped: u64,
+    pub total_unwrapped: u64,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub name: String,
+    pub wallet: Option<String>,
+    pub data: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Positions {
+    pub owner: String,
+    pub share_accounts: Vec<ShareAccountRow>,
+    pub encrypted_token_accounts: Vec<EncryptedTokenAccountRow>,
+}
+
+// SQLite integers are signed, u64 amounts are stored bit for bit
+fn int(value: u64) -> i64 {
+    value as i64
+}
+
+fn uint(row: &Row, column: &str) -> rusqlite::Result<u64> {
+    row.get::<_, i64>(column).map(|value| value as u64)
+}
+
+fn opt_uint(row: &Row, column: &str) -> rusqlite::Result<Option<u64>> {
+    row.get::<_, Option<i64>>(column).map(|value| value.map(|value| value as u64))
+}
+
+fn json_column(row: &Row, column: &str) -> rusqlite::Result<Value> {
+    let text: String = row.get(column)?;
+    Ok(serde_json::from_str(&text).unwrap_or(Value::Null))
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, IndexerError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Indexes the program events of a transaction, returning how many were new. Failed
+    /// transactions are skipped, their events were rolled back.
+    pub fn ingest(&mut self, tx: &LoggedTransaction, program_id: &str) -> Result<usize, IndexerError> {
+        if !tx.succeeded {
+            return Ok(0);
+        }
+
+        let db = self.conn.transaction()?;
+        let mut inserted = 0;
+        for (event_index, data) in tx.program_data(program_id).iter().enumerate() {
+            let Some(event) = ProgramEvent::decode(data) else {
+                continue;
+            };
+            let is_new = db.execute(
+                "INSERT OR IGNORE INTO events (signature, event_index, slot, name, market, wallet, timestamp, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    tx.signature,
+                    event_index as i64,
+                    int(tx.slot),
+                    event.name(),
+                    event.market(),
+                    event.wallet(),
+                    event.timestamp(),
+                    event.to_json().to_string(),
+                ],
+            )? > 0;
+            if is_new {
+                apply(&db, &event)?;
+                inserted += 1;
+            }
+        }
+        db.commit()?;
+        Ok(inserted)
+    }
+
+    pub fn markets(&self) -> Result<Vec<MarketRow>, IndexerError> {
+        let mut stmt = self.conn.prepare("SELECT * FROM markets ORDER BY created_at, address")?;
+        let rows = stmt.query_map([], market_row)?.collect::<Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn market(&self, address: &str) -> Result<Option<MarketRow>, IndexerError> {
+        let row = self
+            .conn
+            .query_row("SELECT * FROM markets WHERE address = ?1", [address], market_row)
+            .optional()?;
+        Ok(row)
+    }
+
+    pub fn options(&self, market: &str) -> Result<Vec<OptionRow>, IndexerError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM options WHERE market = ?1 ORDER BY option_index")?;
+        let rows = stmt.query_map([market], option_row)?.collect::<Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    /// Every event of a market in the order it was indexed
+    pub fn timeline(&self, market: &str) -> Result<Vec<TimelineEntry>, IndexerError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT signature, slot, timestamp, name, wallet, data FROM events
+             WHERE market = ?1 ORDER BY rowid",
+        )?;
+        let rows = stmt
+            .query_map([market], |row| {
+                Ok(TimelineEntry {
+                    signature: row.get("signature")?,
+                    slot: uint(row, "slot")?,
+                    timestamp: row.get("timestamp")?,
+                    name: row.get("name")?,
+                    wallet: row.get("wallet")?,
+                    data: json_column(row, "data")?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn positions(&self, owner: &str) -> Result<Positions, IndexerError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM share_accounts WHERE owner = ?1 ORDER BY created_at, address")?;
+        let share_accounts = stmt.query_map([owner], share_account_row)?.collect::<Result<_, _>>()?;
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM encrypted_token_accounts WHERE owner = ?1 ORDER BY eta_index, address")?;
+        let encrypted_token_accounts = stmt
+            .query_map([owner], encrypted_token_account_row)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(Positions {
+            owner: owner.to_string(),
+            share_accounts,
+            encrypted_token_accounts,
+        })
+    }
+}
+
+/// Folds an event into the market, option, share account and ETA rows
+fn apply(db: &Connection, event: &ProgramEvent) -> rusqlite::Result<()> {
+    let json = event.to_json();
+    match event {
+        ProgramEvent::MarketCreatedEvent(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO markets (address, creator, market_index, time_to_stake, time_to_reveal,
+                 market_authority, unstake_delay_seconds, allow_closing_early, earliness_cutoff_seconds,
+                 scoring_config, losing_stake_slash_bps, min_stake, max_stake, planned_open_timestamp, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                params![
+                    e.market.to_string(),
+                    e.creator.to_string(),
+                    int(e.index),
+                    int(e.time_to_stake),
+                    int(e.time_to_reveal),
+                    e.market_authority.map(|authority| authority.to_string()),
+                    int(e.unstake_delay_seconds),
+                    e.allow_closing_early,
+                    int(e.earliness_cutoff_seconds),
+                    json["scoring_config"].to_string(),
+                    e.losing_stake_slash_bps,
+                    int(e.min_stake),
+                    e.max_stake.map(int),
+                    e.planned_open_timestamp.map(int),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MarketCreatedFromTemplateEvent(e) => {
+            db.execute(
+                "UPDATE markets SET template = ?2, series_index = ?3 WHERE address = ?1",
+                params![e.market.to_string(), e.template.to_string(), int(e.series_index)],
+            )?;
+        }
+        ProgramEvent::MarketOpenedEvent(e) => {
+            db.execute(
+                "UPDATE markets SET open_timestamp = ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.open_timestamp)],
+            )?;
+        }
+        ProgramEvent::OptionSelectedEvent(e) => {
+            db.execute(
+                "UPDATE markets SET selected_option = ?2, winning_options = ?3 WHERE address = ?1",
+                params![e.market.to_string(), e.selected_option, json["winning_options"].to_string()],
+            )?;
+        }
+        ProgramEvent::RevealPeriodExtendedEvent(e) => {
+            db.execute(
+                "UPDATE markets SET time_to_reveal = ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.new_time_to_reveal)],
+            )?;
+        }
+        ProgramEvent::MarketCancelledEvent(e) => {
+            db.execute("UPDATE markets SET cancelled = 1 WHERE address = ?1", [e.market.to_string()])?;
+        }
+        ProgramEvent::MarketClosedEvent(e) => {
+            db.execute("UPDATE markets SET closed = 1 WHERE address = ?1", [e.market.to_string()])?;
+        }
+        ProgramEvent::ProtocolFeeCollectedEvent(e) => {
+            db.execute(
+                "UPDATE markets SET protocol_fees_collected = protocol_fees_collected + ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.amount)],
+            )?;
+        }
+        ProgramEvent::MarketSponsoredEvent(e) => {
+            db.execute(
+                "UPDATE markets SET total_sponsored = total_sponsored + ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.amount)],
+            )?;
+        }
+        ProgramEvent::SponsorRefundedEvent(e) => {
+            db.execute(
+                "UPDATE markets SET total_sponsored = total_sponsored - ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.amount)],
+            )?;
+        }
+        ProgramEvent::MarketOptionCreatedEvent(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO options
+                 (market, option_index, address, creator, by_market_creator, name, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    e.market.to_string(),
+                    e.index,
+                    e.option.to_string(),
+                    e.creator.to_string(),
+                    e.by_market_creator,
+                    e.name,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MarketOptionClosedEvent(e) => {
+            db.execute(
+                "UPDATE options SET closed = 1 WHERE market = ?1 AND option_index = ?2",
+                params![e.market.to_string(), e.index],
+            )?;
+        }
+        ProgramEvent::ShareAccountInitializedEvent(e) => {
+            db.execute(
+                "INSERT OR IGNORE INTO share_accounts (address, owner, market, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![e.share_account.to_string(), e.owner.to_string(), e.market.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::StakedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET staked_at = ?2 WHERE address = ?1",
+                params![e.share_account.to_string(), e.timestamp],
+            )?;
+            update_eta_balance(db, &e.encrypted_token_account, &json, "encrypted_eta_balance", "eta_balance_nonce")?;
+        }
+        ProgramEvent::StakeIncreasedEvent(e) => {
+            update_eta_balance(db, &e.encrypted_token_account, &json, "encrypted_eta_balance", "eta_balance_nonce")?;
+        }
+        ProgramEvent::UnstakeInitiatedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET unstakeable_at = ?2 WHERE address = ?1",
+                params![e.share_account.to_string(), int(e.unstakeable_at_timestamp)],
+            )?;
+        }
+        ProgramEvent::UnstakedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET unstaked_at = ?2 WHERE address = ?1",
+                params![e.share_account.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::StakeRevealedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET revealed_amount = ?2, revealed_option = ?3, entry_timestamp = ?4,
+                 slashed_amount = ?5 WHERE address = ?1",
+                params![
+                    e.share_account.to_string(),
+                    int(e.shares_amount),
+                    e.selected_option,
+                    int(e.entry_timestamp),
+                    int(e.slashed_amount),
+                ],
+            )?;
+            db.execute(
+                "UPDATE markets SET total_slashed = total_slashed + ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.slashed_amount)],
+            )?;
+        }
+        ProgramEvent::TallyIncrementedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET user_score = ?2 WHERE address = ?1",
+                params![e.share_account.to_string(), int(e.user_score)],
+            )?;
+            db.execute(
+                "UPDATE options SET total_shares = total_shares + ?3, total_score = total_score + ?4
+                 WHERE market = ?1 AND option_index = ?2",
+                params![e.market.to_string(), e.option, int(e.revealed_amount), int(e.user_score)],
+            )?;
+        }
+        ProgramEvent::RewardClaimedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET reward_amount = ?2, closed = 1 WHERE address = ?1",
+                params![e.share_account.to_string(), int(e.reward_amount)],
+            )?;
+            db.execute(
+                "UPDATE markets SET total_rewards_claimed = total_rewards_claimed + ?2 WHERE address = ?1",
+                params![e.market.to_string(), int(e.reward_amount)],
+            )?;
+        }
+        ProgramEvent::ShareAccountClosedEvent(e) => {
+            db.execute(
+                "UPDATE share_accounts SET closed = 1 WHERE address = ?1",
+                [e.share_account.to_string()],
+            )?;
+        }
+        ProgramEvent::EncryptedTokenAccountInitializedEvent(e) => {
+            db.execute(
+                "INSERT OR IGNORE INTO encrypted_token_accounts (address, owner, token_mint, eta_index, created_at)
+                 VALUES (?1, ?2, ?3, 0, ?4)",
+                params![
+                    e.encrypted_token_account.to_string(),
+                    e.owner.to_string(),
+                    e.token_mint.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::EphemeralEncryptedTokenAccountInitializedEvent(e) => {
+            db.execute(
+                "INSERT OR IGNORE INTO encrypted_token_accounts
+                 (address, owner, token_mint, eta_index, rent_payer, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    e.encrypted_token_account.to_string(),
+                    e.owner.to_string(),
+                    e.token_mint.to_string(),
+                    int(e.index),
+                    e.rent_payer.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::EncryptedTokensWrappedEvent(e) => {
+            db.execute(
+                "UPDATE encrypted_token_accounts SET total_wrapped = total_wrapped + ?2 WHERE address = ?1",
+                params![e.encrypted_token_account.to_string(), int(e.deposit_amount)],
+            )?;
+            update_eta_balance(db, &e.encrypted_token_account, &json, "encrypted_new_balance", "nonce")?;
+        }
+        ProgramEvent::EncryptedTokensUnwrappedEvent(e) => {
+            db.execute(
+                "UPDATE encrypted_token_accounts SET total_unwrapped = total_unwrapped + ?2 WHERE address = ?1",
+                params![e.encrypted_token_account.to_string(), int(e.amount_withdrawn)],
+            )?;
+            update_eta_balance(db, &e.encrypted_token_account, &json, "encrypted_new_balance", "nonce")?;
+        }
+        ProgramEvent::EncryptedTokensTransferredEvent(e) => {
+            update_eta_balance(
+                db,
+                &e.sender_encrypted_token_account,
+                &json,
+                "sender_encrypted_new_balance",
+                "sender_nonce",
+            )?;
+            update_eta_balance(
+                db,
+                &e.recipient_encrypted_token_account,
+                &json,
+                "recipient_encrypted_new_balance",
+                "recipient_nonce",
+            )?;
+        }
+        ProgramEvent::EphemeralAccountClosedEvent(e) => {
+            // Only the regular ETA the balance moved into is named, the ephemeral one is gone
+            update_eta_balance(db, &e.encrypted_token_account, &json, "encrypted_new_balance", "nonce")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Stores the latest ciphertext of an ETA, read from the event's `balance_field` and `nonce_field`
+fn update_eta_balance(
+    db: &Connection,
+    address: &Pubkey,
+    json: &Value,
+    balance_field: &str,
+    nonce_field: &str,
+) -> rusqlite::Result<()> {
+    db.execute(
+        "UPDATE encrypted_token_accounts SET encrypted_balance = ?2, state_nonce = ?3 WHERE address = ?1",
+        params![address.to_string(), json[balance_field].as_str(), json[nonce_field].as_str()],
+    )?;
+    Ok(())
+}
+
+fn market_row(row: &Row) -> rusqlite::Result<MarketRow> {
+    Ok(MarketRow {
+        address: row.get("address")?,
+        creator: row.get("creator")?,
+        market_index: uint(row, "market_index")?,
+        time_to_stake: uint(row, "time_to_stake")?,
+        time_to_reveal: uint(row, "time_to_reveal")?,
+        market_authority: row.get("market_authority")?,
+        unstake_delay_seconds: uint(row, "unstake_delay_seconds")?,
+        allow_closing_early: row.get("allow_closing_early")?,
+        earliness_cutoff_seconds: uint(row, "earliness_cutoff_seconds")?,
+        scoring_config: json_column(row, "scoring_config")?,
+        losing_stake_slash_bps: row.get("losing_stake_slash_bps")?,
+        min_stake: uint(row, "min_stake")?,
+        max_stake: opt_uint(row, "max_stake")?,
+        template: row.get("template")?,
+        series_index: opt_uint(row, "series_index")?,
+        planned_open_timestamp: opt_uint(row, "planned_open_timestamp")?,
+        open_timestamp: opt_uint(row, "open_timestamp")?,
+        selected_option: row.get("selected_option")?,
+        winning_options: json_column(row, "winning_options")?,
+        total_rewards_claimed: uint(row, "total_rewards_claimed")?,
+        protocol_fees_collected: uint(row, "protocol_fees_collected")?,
+        total_slashed: uint(row, "total_slashed")?,
+        total_sponsored: uint(row, "total_sponsored")?,
+        cancelled: row.get("cancelled")?,
+        closed: row.get("closed")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+fn option_row(row: &Row) -> rusqlite::Result<OptionRow> {
+    Ok(OptionRow {
+        market: row.get("market")?,
+        option_index: row.get("option_index")?,
+        address: row.get("address")?,
+        creator: row.get("creator")?,
+        by_market_creator: row.get("by_market_creator")?,
+        name: row.get("name")?,
+        total_shares: uint(row, "total_shares")?,
+        total_score: uint(row, "total_score")?,
+        closed: row.get("closed")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+fn share_account_row(row: &Row) -> rusqlite::Result<ShareAccountRow> {
+    Ok(ShareAccountRow {
+        address: row.get("address")?,
+        owner: row.get("owner")?,
+        market: row.get("market")?,
+        staked_at: row.get("staked_at")?,
+        unstakeable_at: opt_uint(row, "unstakeable_at")?,
+        unstaked_at: row.get("unstaked_at")?,
+        revealed_amount: opt_uint(row, "revealed_amount")?,
+        revealed_option: row.get("revealed_option")?,
+        entry_timestamp: opt_uint(row, "entry_timestamp")?,
+        slashed_amount: opt_uint(row, "slashed_amount")?,
+        user_score: opt_uint(row, "user_score")?,
+        reward_amount: opt_uint(row, "reward_amount")?,
+        closed: row.get("closed")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+fn encrypted_token_account_row(row: &Row) -> rusqlite::Result<EncryptedTokenAccountRow> {
+    Ok(EncryptedTokenAccountRow {
+        address: row.get("address")?,
+        owner: row.get("owner")?,
+        token_mint: row.get("token_mint")?,
+        eta_index: uint(row, "eta_index")?,
+        rent_payer: row.get("rent_payer")?,
+        encrypted_balance: row.get("encrypted_balance")?,
+        state_nonce: row.get("state_nonce")?,
+        total_wrapped: uint(row, "total_wrapped")?,
+        total_unwrapped: uint(row, "total_unwrapped")?,
+        created_at: row.get("created_at")?,
+    })
+}
@@ -0,0 +1,171 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use opportunity_market_indexer::{
+    api::handle, ingest, logs::LogReader, store::Store, OPPORTUNITY_MARKET_PROGRAM_ID,
+};
+use tiny_http::Method;
+
+const MARKET: &str = "9NGEriSgssSsyytz15y3YP28LjU6HnW2H27BopY6V9KH";
+const CREATOR: &str = "3TzddA8xcMD2N2QwWWtXQSY9RE62ffz7RAzX16Lsbyqx";
+const ALICE: &str = "955dG3DUKvbRxC7exyd81t8KdKkU4MCMkiCZEEXLApxc";
+const BOB: &str = "DFK4931fqk7pnrqdcvDWozDABepb2BeTwE5DYfiRPi4j";
+const ALICE_SHARE_ACCOUNT: &str = "DfvrRRJshh12HtCP5fGehbjhUMBCHtujthWB98KNX1nv";
+const ALICE_ETA: &str = "FRRsYytQg4kakBFrMQ54p1rftfGhTwCUBkT3JJv358Z";
+const FAILED_SIGNATURE: &str =
+    "41Zxb1ejbKEAzbGRMn3T9xYhmvF6Trx2qYkrGuZdxVNT68qSij5wRymLMV2koaZUpBYhhbR6jPJBwdAncWvoJevq";
+
+fn fixture(name: &str) -> BufReader<File> {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    BufReader::new(File::open(path).expect("fixture exists"))
+}
+
+fn indexed(name: &str) -> Store {
+    let mut store = Store::open_in_memory().unwrap();
+    ingest(&mut store, fixture(name), &OPPORTUNITY_MARKET_PROGRAM_ID.to_string()).unwrap();
+    store
+}
+
+#[test]
+fn text_logs_are_split_into_transactions() {
+    let txs: Vec<_> = LogReader::new(fixture("lifecycle.log")).collect::<Result<_, _>>().unwrap();
+    assert_eq!(txs.len(), 20);
+    assert_eq!(txs[0].slot, 100);
+    assert!(txs[0].logs[0].starts_with("Program ComputeBudget"));
+
+    let failed = txs.iter().find(|tx| tx.signature == FAILED_SIGNATURE).unwrap();
+    assert!(!failed.succeeded);
+}
+
+#[test]
+fn only_events_of_the_program_itself_are_decoded() {
+    let txs: Vec<_> = LogReader::new(fixture("lifecycle.log")).collect::<Result<_, _>>().unwrap();
+    // Callback transaction: Arcium logs its own data before invoking the program
+    let callback = &txs[6];
+    assert_eq!(callback.logs.iter().filter(|line| line.starts_with("Program data: ")).count(), 2);
+    assert_eq!(callback.program_data(&OPPORTUNITY_MARKET_PROGRAM_ID.to_string()).len(), 1);
+}
+
+#[test]
+fn market_lifecycle_is_indexed() {
+    let store = indexed("lifecycle.log");
+
+    let market = store.market(MARKET).unwrap().unwrap();
+    assert_eq!(market.creator, CREATOR);
+    assert_eq!(market.time_to_stake, 3600);
+    assert_eq!(market.open_timestamp, Some(1_700_000_100));
+    assert_eq!(market.selected_option, Some(1));
+    assert_eq!(market.total_rewards_claimed, 5000);
+    assert_eq!(market.scoring_config["kind"], "linear");
+    assert_eq!((market.min_stake, market.max_stake), (10, Some(10_000)));
+    assert_eq!((market.template.as_deref(), market.series_index), (None, None));
+    assert_eq!(market.planned_open_timestamp, None);
+    assert_eq!(market.total_sponsored, 0);
+    assert!(!market.cancelled);
+
+    let options = store.options(MARKET).unwrap();
+    assert_eq!(options.len(), 2);
+    assert_eq!(options[0].name, "Ship it");
+    assert_eq!((options[0].total_shares, options[0].total_score), (400, 1234));
+    assert_eq!((options[1].total_shares, options[1].total_score), (0, 0));
+
+    let timeline = store.timeline(MARKET).unwrap();
+    let names: Vec<_> = timeline.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "MarketCreatedEvent",
+            "MarketOptionCreatedEvent",
+            "MarketOptionCreatedEvent",
+            "MarketOpenedEvent",
+            "ShareAccountInitializedEvent",
+            "ShareAccountInitializedEvent",
+            "StakedEvent",
+            "StakedEvent",
+            "UnstakeInitiatedEvent",
+            "UnstakedEvent",
+            "OptionSelectedEvent",
+            "StakeRevealedEvent",
+            "TallyIncrementedEvent",
+            "RewardClaimedEvent",
+            "RewardClaimedEvent",
+        ]
+    );
+    assert!(timeline.iter().all(|entry| entry.signature != FAILED_SIGNATURE));
+    assert_eq!(timeline[10].data["winning_options"][0]["weight"], 1);
+}
+
+#[test]
+fn user_positions_are_indexed() {
+    let store = indexed("lifecycle.log");
+
+    let alice = store.positions(ALICE).unwrap();
+    assert_eq!(alice.share_accounts.len(), 1);
+    let share = &alice.share_accounts[0];
+    assert_eq!(share.address, ALICE_SHARE_ACCOUNT);
+    assert_eq!(share.staked_at, Some(1_700_000_120));
+    assert_eq!(share.revealed_amount, Some(400));
+    assert_eq!(share.revealed_option, Some(1));
+    assert_eq!(share.user_score, Some(1234));
+    assert_eq!(share.reward_amount, Some(5000));
+    assert!(share.closed);
+
+    assert_eq!(alice.encrypted_token_accounts.len(), 1);
+    let eta = &alice.encrypted_token_accounts[0];
+    assert_eq!(eta.address, ALICE_ETA);
+    assert_eq!(eta.total_wrapped, 1000);
+    // Latest ciphertext comes from the stake, not the earlier wrap
+    assert_eq!(eta.state_nonce.as_deref(), Some("13"));
+
+    let bob = store.positions(BOB).unwrap();
+    let share = &bob.share_accounts[0];
+    assert_eq!(share.unstakeable_at, Some(1_700_000_260));
+    assert_eq!(share.unstaked_at, Some(1_700_000_270));
+    assert_eq!(share.revealed_amount, None);
+    assert_eq!(share.reward_amount, Some(0));
+}
+
+#[test]
+fn json_lines_and_text_logs_index_the_same_events() {
+    let from_text = indexed("lifecycle.log");
+    let from_json = indexed("lifecycle.jsonl");
+    assert_eq!(from_text.timeline(MARKET).unwrap(), from_json.timeline(MARKET).unwrap());
+    assert_eq!(from_text.markets().unwrap(), from_json.markets().unwrap());
+    assert_eq!(from_text.positions(ALICE).unwrap(), from_json.positions(ALICE).unwrap());
+}
+
+#[test]
+fn replaying_a_ledger_twice_is_a_no_op() {
+    let mut store = indexed("lifecycle.jsonl");
+    let program_id = OPPORTUNITY_MARKET_PROGRAM_ID.to_string();
+    assert_eq!(ingest(&mut store, fixture("lifecycle.jsonl"), &program_id).unwrap(), 0);
+    assert_eq!(ingest(&mut store, fixture("lifecycle.log"), &program_id).unwrap(), 0);
+
+    assert_eq!(store.timeline(MARKET).unwrap().len(), 15);
+    assert_eq!(store.options(MARKET).unwrap()[0].total_shares, 400);
+    assert_eq!(store.market(MARKET).unwrap().unwrap().total_rewards_claimed, 5000);
+}
+
+#[test]
+fn api_serves_markets_timelines_and_positions() {
+    let store = indexed("lifecycle.log");
+
+    let markets = handle(&store, &Method::Get, "/markets");
+    assert_eq!(markets.status, 200);
+    assert_eq!(markets.body[0]["address"], MARKET);
+
+    let market = handle(&store, &Method::Get, &format!("/markets/{MARKET}"));
+    assert_eq!(market.status, 200);
+    assert_eq!(market.body["options"][1]["name"], "Wait");
+
+    let timeline = handle(&store, &Method::Get, &format!("/markets/{MARKET}/timeline"));
+    assert_eq!(timeline.body.as_array().unwrap().len(), 15);
+    assert_eq!(timeline.body[0]["data"]["creator"], CREATOR);
+
+    let positions = handle(&store, &Method::Get, &format!("/users/{ALICE}/positions?pretty"));
+    assert_eq!(positions.body["share_accounts"][0]["reward_amount"], 5000);
+
+    assert_eq!(handle(&store, &Method::Get, &format!("/markets/{ALICE}")).status, 404);
+    assert_eq!(handle(&store, &Method::Get, "/nope").status, 404);
+    assert_eq!(handle(&store, &Method::Post, "/markets").status, 405);
+}
@@ -3,3 +3,7 @@ export * from "./encryptedTokenAccount";
 export * from "./shareAccount";
 export * from "./opportunityMarketOption";
 export * from "./tokenVault";
+export * from "./optionTallies";
+export * from "./stakeDelegation";
+export * from "./marketTemplate";
+export * from "./marketSponsor";
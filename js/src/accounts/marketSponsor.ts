@@ -0,0 +1,25 @@
+import {
+  type Address,
+  getAddressEncoder,
+  getProgramDerivedAddress,
+  type ProgramDerivedAddress,
+} from "@solana/kit";
+import { OPPORTUNITY_MARKET_PROGRAM_ADDRESS } from "../generated";
+
+export const MARKET_SPONSOR_SEED = "market_sponsor";
+
+export async function getMarketSponsorAddress(
+  market: Address,
+  sponsor: Address,
+  programId: Address = OPPORTUNITY_MARKET_PROGRAM_ADDRESS
+): Promise<ProgramDerivedAddress> {
+  const addressEncoder = getAddressEncoder();
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      MARKET_SPONSOR_SEED,
+      addressEncoder.encode(market),
+      addressEncoder.encode(sponsor),
+    ],
+  });
+}
@@ -0,0 +1,43 @@
+import {
+  type Address,
+  getAddressEncoder,
+  getU64Encoder,
+  getProgramDerivedAddress,
+  type ProgramDerivedAddress,
+} from "@solana/kit";
+import { OPPORTUNITY_MARKET_PROGRAM_ADDRESS } from "../generated";
+
+export const MARKET_TEMPLATE_SEED = "market_template";
+
+export async function getMarketTemplateAddress(
+  creator: Address,
+  templateId: bigint,
+  programId: Address = OPPORTUNITY_MARKET_PROGRAM_ADDRESS
+): Promise<ProgramDerivedAddress> {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      MARKET_TEMPLATE_SEED,
+      getAddressEncoder().encode(creator),
+      getU64Encoder().encode(templateId),
+    ],
+  });
+}
+
+export const OPPORTUNITY_MARKET_SEED = "opportunity_market";
+
+/** Market `seriesIndex` of a template's series */
+export async function getTemplateMarketAddress(
+  template: Address,
+  seriesIndex: bigint,
+  programId: Address = OPPORTUNITY_MARKET_PROGRAM_ADDRESS
+): Promise<ProgramDerivedAddress> {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      OPPORTUNITY_MARKET_SEED,
+      getAddressEncoder().encode(template),
+      getU64Encoder().encode(seriesIndex),
+    ],
+  });
+}
@@ -0,0 +1,19 @@
+import {
+  type Address,
+  getAddressEncoder,
+  getProgramDerivedAddress,
+  type ProgramDerivedAddress,
+} from "@solana/kit";
+import { OPPORTUNITY_MARKET_PROGRAM_ADDRESS } from "../generated";
+
+export const OPTION_TALLIES_SEED = "option_tallies";
+
+export async function getOptionTalliesAddress(
+  market: Address,
+  programId: Address = OPPORTUNITY_MARKET_PROGRAM_ADDRESS
+): Promise<ProgramDerivedAddress> {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [OPTION_TALLIES_SEED, getAddressEncoder().encode(market)],
+  });
+}
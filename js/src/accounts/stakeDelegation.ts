@@ -0,0 +1,25 @@
+import {
+  type Address,
+  getAddressEncoder,
+  getProgramDerivedAddress,
+  type ProgramDerivedAddress,
+} from "@solana/kit";
+import { OPPORTUNITY_MARKET_PROGRAM_ADDRESS } from "../generated";
+
+export const STAKE_DELEGATION_SEED = "stake_delegation";
+
+export async function getStakeDelegationAddress(
+  encryptedTokenAccount: Address,
+  delegate: Address,
+  programId: Address = OPPORTUNITY_MARKET_PROGRAM_ADDRESS
+): Promise<ProgramDerivedAddress> {
+  const addressEncoder = getAddressEncoder();
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      STAKE_DELEGATION_SEED,
+      addressEncoder.encode(encryptedTokenAccount),
+      addressEncoder.encode(delegate),
+    ],
+  });
+}
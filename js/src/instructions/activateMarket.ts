@@ -0,0 +1,25 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getActivateMarketInstruction,
+  type ActivateMarketInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface ActivateMarketParams extends BaseInstructionParams {
+  /** Any signer, the market must be funded and have a planned open time */
+  signer: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  marketTokenAta: Address;
+  tokenProgram: Address;
+}
+
+export function activateMarket(
+  input: ActivateMarketParams
+): ActivateMarketInstruction<string> {
+  const { programAddress, ...params } = input;
+  return getActivateMarketInstruction(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
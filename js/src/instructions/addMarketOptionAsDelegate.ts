@@ -0,0 +1,62 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getAddMarketOptionAsDelegateInstructionAsync,
+  type AddMarketOptionAsDelegateInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type ByteArray, toNumberArray } from "../utils";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface AddMarketOptionAsDelegateParams extends BaseInstructionParams {
+  delegate: TransactionSigner;
+  market: Address;
+  sourceEta: Address;
+  /** Share account of the ETA owner */
+  shareAccount: Address;
+  optionIndex: number;
+  shareAccountId: number;
+  name: string;
+  /** Encrypted with the delegate's own x25519 key */
+  amountCiphertext: ByteArray;
+  delegatePubkey: ByteArray;
+  inputNonce: bigint;
+  authorizedReaderNonce: bigint;
+}
+
+export async function addMarketOptionAsDelegate(
+  input: AddMarketOptionAsDelegateParams,
+  config: ArciumConfig,
+): Promise<AddMarketOptionAsDelegateInstruction<string>> {
+  const {
+    programAddress,
+    delegate,
+    market,
+    sourceEta,
+    shareAccount,
+    optionIndex,
+    shareAccountId,
+    name,
+    amountCiphertext,
+    delegatePubkey,
+    inputNonce,
+    authorizedReaderNonce,
+  } = input;
+
+  return getAddMarketOptionAsDelegateInstructionAsync(
+    {
+      ...getComputeAccounts("delegated_add_option_stake", config),
+      delegate,
+      market,
+      sourceEta,
+      shareAccount,
+      optionIndex,
+      shareAccountId,
+      name,
+      amountCiphertext: toNumberArray(amountCiphertext),
+      delegatePubkey: toNumberArray(delegatePubkey),
+      inputNonce,
+      authorizedReaderNonce,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
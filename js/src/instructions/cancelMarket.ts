@@ -0,0 +1,24 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCancelMarketInstructionAsync,
+  type CancelMarketInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CancelMarketParams extends BaseInstructionParams {
+  authority: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  creatorTokenAccount: Address;
+  tokenProgram: Address;
+}
+
+export async function cancelMarket(
+  input: CancelMarketParams
+): Promise<CancelMarketInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getCancelMarketInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
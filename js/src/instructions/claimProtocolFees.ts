@@ -0,0 +1,25 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getClaimProtocolFeesInstructionAsync,
+  type ClaimProtocolFeesInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface ClaimProtocolFeesParams extends BaseInstructionParams {
+  signer: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  /** Token account owned by the central state's treasury */
+  treasuryTokenAccount: Address;
+  tokenProgram: Address;
+}
+
+export async function claimProtocolFees(
+  input: ClaimProtocolFeesParams
+): Promise<ClaimProtocolFeesInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getClaimProtocolFeesInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
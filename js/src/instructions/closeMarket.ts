@@ -0,0 +1,24 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCloseMarketInstructionAsync,
+  type CloseMarketInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CloseMarketParams extends BaseInstructionParams {
+  creator: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  creatorTokenAccount: Address;
+  tokenProgram: Address;
+}
+
+export async function closeMarket(
+  input: CloseMarketParams
+): Promise<CloseMarketInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getCloseMarketInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
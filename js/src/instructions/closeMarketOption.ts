@@ -0,0 +1,24 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCloseMarketOptionInstructionAsync,
+  type CloseMarketOptionInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CloseMarketOptionParams extends BaseInstructionParams {
+  creator: TransactionSigner;
+  market: Address;
+  /** Account that paid for the option, receives its rent */
+  optionCreator: Address;
+  optionIndex: number;
+}
+
+export async function closeMarketOption(
+  input: CloseMarketOptionParams
+): Promise<CloseMarketOptionInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getCloseMarketOptionInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
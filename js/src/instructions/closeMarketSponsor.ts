@@ -0,0 +1,23 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCloseMarketSponsorInstructionAsync,
+  type CloseMarketSponsorInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CloseMarketSponsorParams extends BaseInstructionParams {
+  /** Any signer, the rent always goes to the sponsor */
+  signer: TransactionSigner;
+  sponsor: Address;
+  market: Address;
+}
+
+export async function closeMarketSponsor(
+  input: CloseMarketSponsorParams
+): Promise<CloseMarketSponsorInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getCloseMarketSponsorInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -8,7 +8,7 @@ import { type BaseInstructionParams } from "./instructionParams";
 export interface CloseShareAccountParams extends BaseInstructionParams {
   owner: TransactionSigner;
   market: Address;
-  tokenMint: Address;
+  rewardMint: Address;
   ownerTokenAccount: Address;
   tokenProgram: Address;
   optionIndex: number;
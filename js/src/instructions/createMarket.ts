@@ -2,6 +2,7 @@ import { type TransactionSigner, type Address } from "@solana/kit";
 import {
   getCreateMarketInstructionAsync,
   type CreateMarketInstruction,
+  type ScoringConfigArgs,
 } from "../generated";
 import { type ByteArray, toNumberArray } from "../utils";
 import { type BaseInstructionParams } from "./instructionParams";
@@ -9,6 +10,9 @@ import { type BaseInstructionParams } from "./instructionParams";
 export interface CreateMarketParams extends BaseInstructionParams {
   creator: TransactionSigner;
   tokenMint: Address;
+  /** Mint the reward is paid in, defaults to `tokenMint` */
+  rewardMint?: Address;
+  /** Token program of the reward mint */
   tokenProgram: Address;
   marketIndex: bigint;
   rewardAmount: bigint;
@@ -18,6 +22,18 @@ export interface CreateMarketParams extends BaseInstructionParams {
   unstakeDelaySeconds: bigint;
   authorizedReaderPubkey: ByteArray;
   allowClosingEarly: boolean;
+  /** Overrides the central state earliness cutoff for this market */
+  earlinessCutoffSeconds?: bigint | null;
+  /** Earliness curve used for scoring, defaults to linear */
+  scoringConfig?: ScoringConfigArgs;
+  /** Basis points of stake on non-winning options moved to the reward pool, defaults to 0 */
+  losingStakeSlashBps?: number;
+  /** Smallest amount a single stake may carry, defaults to 0 */
+  minStake?: bigint;
+  /** Largest amount a single stake may carry, unbounded by default */
+  maxStake?: bigint | null;
+  /** Lets anyone open the funded market at this time with `activateMarket` */
+  plannedOpenTimestamp?: bigint | null;
 }
 
 export async function createMarket(
@@ -26,6 +42,7 @@ export async function createMarket(
   const {
     creator,
     tokenMint,
+    rewardMint,
     tokenProgram,
     marketIndex,
     rewardAmount,
@@ -35,6 +52,12 @@ export async function createMarket(
     unstakeDelaySeconds,
     authorizedReaderPubkey,
     allowClosingEarly,
+    earlinessCutoffSeconds,
+    scoringConfig,
+    losingStakeSlashBps,
+    minStake,
+    maxStake,
+    plannedOpenTimestamp,
     programAddress,
   } = input;
 
@@ -42,6 +65,7 @@ export async function createMarket(
     {
       creator,
       tokenMint,
+      rewardMint: rewardMint ?? tokenMint,
       tokenProgram,
       marketIndex,
       rewardAmount,
@@ -51,6 +75,12 @@ export async function createMarket(
       unstakeDelaySeconds,
       authorizedReaderPubkey: toNumberArray(authorizedReaderPubkey),
       allowClosingEarly,
+      earlinessCutoffSeconds: earlinessCutoffSeconds ?? null,
+      scoringConfig: scoringConfig ?? { __kind: "Linear" },
+      losingStakeSlashBps: losingStakeSlashBps ?? 0,
+      minStake: minStake ?? 0n,
+      maxStake: maxStake ?? null,
+      plannedOpenTimestamp: plannedOpenTimestamp ?? null,
     },
     programAddress ? { programAddress } : undefined
   );
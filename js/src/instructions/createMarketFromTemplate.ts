@@ -0,0 +1,36 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCreateMarketFromTemplateInstructionAsync,
+  type CreateMarketFromTemplateInstruction,
+} from "../generated";
+import { getTemplateMarketAddress } from "../accounts";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CreateMarketFromTemplateParams extends BaseInstructionParams {
+  creator: TransactionSigner;
+  template: Address;
+  /** Must be the template's mints */
+  tokenMint: Address;
+  rewardMint: Address;
+  /** Token program of the reward mint */
+  tokenProgram: Address;
+  /** The template's `nextSeriesIndex`, the market address is derived from it */
+  seriesIndex: bigint;
+  /** Lets anyone open the funded market at this time with `activateMarket` */
+  plannedOpenTimestamp?: bigint | null;
+}
+
+export async function createMarketFromTemplate(
+  input: CreateMarketFromTemplateParams
+): Promise<CreateMarketFromTemplateInstruction<string>> {
+  const { programAddress, plannedOpenTimestamp, seriesIndex, ...params } = input;
+  const [market] = await getTemplateMarketAddress(
+    params.template,
+    seriesIndex,
+    programAddress
+  );
+  return getCreateMarketFromTemplateInstructionAsync(
+    { ...params, market, plannedOpenTimestamp: plannedOpenTimestamp ?? null },
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,27 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCreateMarketTemplateInstructionAsync,
+  type CreateMarketTemplateInstruction,
+  type MarketParamsArgs,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CreateMarketTemplateParams extends BaseInstructionParams {
+  creator: TransactionSigner;
+  tokenMint: Address;
+  /** Mint the series' rewards are paid in, defaults to `tokenMint` */
+  rewardMint?: Address;
+  templateId: bigint;
+  /** Applied to every market created from the template */
+  params: MarketParamsArgs;
+}
+
+export async function createMarketTemplate(
+  input: CreateMarketTemplateParams
+): Promise<CreateMarketTemplateInstruction<string>> {
+  const { programAddress, rewardMint, ...params } = input;
+  return getCreateMarketTemplateInstructionAsync(
+    { ...params, rewardMint: rewardMint ?? params.tokenMint },
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,27 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getCreateStakeDelegationInstructionAsync,
+  type CreateStakeDelegationInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface CreateStakeDelegationParams extends BaseInstructionParams {
+  owner: TransactionSigner;
+  encryptedTokenAccount: Address;
+  delegate: Address;
+  /** Most the delegate can stake from the ETA in total */
+  spendingCap: bigint;
+  /** Unix timestamp after which the delegate can no longer stake */
+  expiresAt: bigint;
+  stateNonce: bigint;
+}
+
+export async function createStakeDelegation(
+  input: CreateStakeDelegationParams
+): Promise<CreateStakeDelegationInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getCreateStakeDelegationInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
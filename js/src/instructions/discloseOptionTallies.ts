@@ -0,0 +1,31 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getDiscloseOptionTalliesInstructionAsync,
+  type DiscloseOptionTalliesInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface DiscloseOptionTalliesParams extends BaseInstructionParams {
+  /** Market creator or market authority */
+  authority: TransactionSigner;
+  market: Address;
+  authorizedReaderNonce: bigint;
+}
+
+export async function discloseOptionTallies(
+  input: DiscloseOptionTalliesParams,
+  config: ArciumConfig
+): Promise<DiscloseOptionTalliesInstruction<string>> {
+  const { programAddress, authority, market, authorizedReaderNonce } = input;
+
+  return getDiscloseOptionTalliesInstructionAsync(
+    {
+      ...getComputeAccounts("disclose_option_tallies", config),
+      authority,
+      market,
+      authorizedReaderNonce,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
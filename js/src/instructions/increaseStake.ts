@@ -0,0 +1,48 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getIncreaseStakeInstructionAsync,
+  type IncreaseStakeInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type ByteArray, toNumberArray } from "../utils";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface IncreaseStakeParams extends BaseInstructionParams {
+  signer: TransactionSigner;
+  market: Address;
+  userEta: Address;
+  shareAccountId: number;
+  amountCiphertext: ByteArray;
+  inputNonce: bigint;
+  authorizedReaderNonce: bigint;
+}
+
+export async function increaseStake(
+  input: IncreaseStakeParams,
+  config: ArciumConfig
+): Promise<IncreaseStakeInstruction<string>> {
+  const {
+    programAddress,
+    signer,
+    market,
+    userEta,
+    shareAccountId,
+    amountCiphertext,
+    inputNonce,
+    authorizedReaderNonce,
+  } = input;
+
+  return getIncreaseStakeInstructionAsync(
+    {
+      ...getComputeAccounts("increase_stake", config),
+      signer,
+      market,
+      userEta,
+      shareAccountId,
+      amountCiphertext: toNumberArray(amountCiphertext),
+      inputNonce,
+      authorizedReaderNonce,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
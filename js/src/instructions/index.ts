@@ -4,18 +4,39 @@ export * from "./initEphemeralEncryptedTokenAccount";
 export * from "./initTokenVault";
 export * from "./closeEphemeralEncryptedTokenAccount";
 export * from "./createMarket";
+export * from "./createMarketTemplate";
+export * from "./createMarketFromTemplate";
 export * from "./initCompDef";
 export * from "./stake";
+export * from "./increaseStake";
 export * from "./wrapEncryptedTokens";
 export * from "./unwrapEncryptedTokens";
+export * from "./transferEncryptedTokens";
 export * from "./revealShares";
 export * from "./addMarketOption";
 export * from "./addMarketOptionAsCreator";
 export * from "./openMarket";
+export * from "./activateMarket";
+export * from "./sponsorMarket";
+export * from "./refundSponsor";
+export * from "./closeMarketSponsor";
 export * from "./selectOption";
+export * from "./selectWinningOptions";
+export * from "./cancelMarket";
 export * from "./extendRevealPeriod";
 export * from "./initShareAccount";
 export * from "./closeShareAccount";
+export * from "./reclaimMarketRewards";
+export * from "./claimProtocolFees";
+export * from "./closeMarketOption";
+export * from "./closeMarket";
 export * from "./incrementOptionTally";
 export * from "./unstakeEarly";
 export * from "./doUnstakeEarly";
+export * from "./discloseOptionTallies";
+export * from "./syncOptionTally";
+export * from "./createStakeDelegation";
+export * from "./revokeStakeDelegation";
+export * from "./initShareAccountAsDelegate";
+export * from "./stakeAsDelegate";
+export * from "./addMarketOptionAsDelegate";
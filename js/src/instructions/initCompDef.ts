@@ -16,11 +16,17 @@ import { PublicKey } from "@solana/web3.js";
 import {
   getWrapEncryptedTokensCompDefInstruction,
   getUnwrapEncryptedTokensCompDefInstruction,
+  getTransferEncryptedTokensCompDefInstruction,
   getBuyOpportunityMarketSharesCompDefInstruction,
   getRevealSharesCompDefInstruction,
   getUnstakeEarlyCompDefInstruction,
   getAddOptionStakeCompDefInstruction,
   getCloseEphemeralEncryptedTokenAccountCompDefInstruction,
+  getIncreaseStakeCompDefInstruction,
+  getDiscloseOptionTalliesCompDefInstruction,
+  getSyncOptionTallyCompDefInstruction,
+  getDelegatedBuyOpportunityMarketSharesCompDefInstruction,
+  getDelegatedAddOptionStakeCompDefInstruction,
   OPPORTUNITY_MARKET_PROGRAM_ADDRESS,
   fetchMXEAccount,
 } from "../generated";
@@ -30,20 +36,32 @@ import { type BaseInstructionParams } from "./instructionParams";
 export type CompDefCircuitName =
   | "wrap_encrypted_tokens"
   | "unwrap_encrypted_tokens"
+  | "transfer_encrypted_tokens"
   | "buy_opportunity_market_shares"
   | "reveal_shares"
   | "unstake_early"
   | "add_option_stake"
-  | "close_ephemeral_encrypted_token_account";
+  | "close_ephemeral_encrypted_token_account"
+  | "increase_stake"
+  | "disclose_option_tallies"
+  | "sync_option_tally"
+  | "delegated_buy_opportunity_market_shares"
+  | "delegated_add_option_stake";
 
 export const ALL_COMP_DEF_CIRCUITS: CompDefCircuitName[] = [
   "wrap_encrypted_tokens",
   "unwrap_encrypted_tokens",
+  "transfer_encrypted_tokens",
   "buy_opportunity_market_shares",
   "reveal_shares",
   "unstake_early",
   "add_option_stake",
   "close_ephemeral_encrypted_token_account",
+  "increase_stake",
+  "disclose_option_tallies",
+  "sync_option_tally",
+  "delegated_buy_opportunity_market_shares",
+  "delegated_add_option_stake",
 ];
 
 
@@ -106,6 +124,9 @@ export async function getInitCompDefInstruction(
     case "unwrap_encrypted_tokens":
       return getUnwrapEncryptedTokensCompDefInstruction(baseInput, { programAddress: programId });
 
+    case "transfer_encrypted_tokens":
+      return getTransferEncryptedTokensCompDefInstruction(baseInput, { programAddress: programId });
+
     case "buy_opportunity_market_shares":
       return getBuyOpportunityMarketSharesCompDefInstruction(baseInput, { programAddress: programId });
 
@@ -121,6 +142,21 @@ export async function getInitCompDefInstruction(
     case "close_ephemeral_encrypted_token_account":
       return getCloseEphemeralEncryptedTokenAccountCompDefInstruction(baseInput, { programAddress: programId });
 
+    case "increase_stake":
+      return getIncreaseStakeCompDefInstruction(baseInput, { programAddress: programId });
+
+    case "disclose_option_tallies":
+      return getDiscloseOptionTalliesCompDefInstruction(baseInput, { programAddress: programId });
+
+    case "sync_option_tally":
+      return getSyncOptionTallyCompDefInstruction(baseInput, { programAddress: programId });
+
+    case "delegated_buy_opportunity_market_shares":
+      return getDelegatedBuyOpportunityMarketSharesCompDefInstruction(baseInput, { programAddress: programId });
+
+    case "delegated_add_option_stake":
+      return getDelegatedAddOptionStakeCompDefInstruction(baseInput, { programAddress: programId });
+
     default:
       throw new Error(`Unknown circuit: ${circuitName}`);
   }
@@ -0,0 +1,26 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getInitShareAccountAsDelegateInstruction,
+  type InitShareAccountAsDelegateInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface InitShareAccountAsDelegateParams extends BaseInstructionParams {
+  delegate: TransactionSigner;
+  delegation: Address;
+  market: Address;
+  /** Share account of the delegation's owner */
+  shareAccount: Address;
+  stateNonce: bigint;
+  shareAccountId: number;
+}
+
+export function initShareAccountAsDelegate(
+  input: InitShareAccountAsDelegateParams
+): InitShareAccountAsDelegateInstruction<string> {
+  const { programAddress, ...params } = input;
+  return getInitShareAccountAsDelegateInstruction(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
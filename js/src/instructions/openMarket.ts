@@ -8,7 +8,7 @@ import { type BaseInstructionParams } from "./instructionParams";
 export interface OpenMarketParams extends BaseInstructionParams {
   creator: TransactionSigner;
   market: Address;
-  tokenMint: Address;
+  rewardMint: Address;
   marketTokenAta: Address;
   tokenProgram: Address;
   openTimestamp: bigint;
@@ -0,0 +1,33 @@
+import { type TransactionSigner, type Address, type Instruction, AccountRole } from "@solana/kit";
+import { getReclaimMarketRewardsInstructionAsync } from "../generated";
+import { getOpportunityMarketOptionAddress } from "../accounts/opportunityMarketOption";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface ReclaimMarketRewardsParams extends BaseInstructionParams {
+  creator: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  creatorTokenAccount: Address;
+  tokenProgram: Address;
+  /** Option indices of the market's winning options, in the same order */
+  winningOptions: number[];
+}
+
+export async function reclaimMarketRewards(
+  input: ReclaimMarketRewardsParams
+): Promise<Instruction> {
+  const { programAddress, winningOptions, ...params } = input;
+  const instruction = await getReclaimMarketRewardsInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+
+  // The winning options' accounts are passed as remaining accounts
+  const optionAccounts = await Promise.all(
+    winningOptions.map(async (optionIndex) => ({
+      address: (await getOpportunityMarketOptionAddress(params.market, optionIndex, programAddress))[0],
+      role: AccountRole.READONLY,
+    }))
+  );
+  return { ...instruction, accounts: [...instruction.accounts, ...optionAccounts] };
+}
@@ -0,0 +1,25 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getRefundSponsorInstructionAsync,
+  type RefundSponsorInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface RefundSponsorParams extends BaseInstructionParams {
+  /** Any signer, the refund always goes to the sponsor's ATA */
+  signer: TransactionSigner;
+  sponsor: Address;
+  market: Address;
+  rewardMint: Address;
+  tokenProgram: Address;
+}
+
+export async function refundSponsor(
+  input: RefundSponsorParams
+): Promise<RefundSponsorInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getRefundSponsorInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
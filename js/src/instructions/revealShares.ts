@@ -12,13 +12,30 @@ export interface RevealSharesParams extends BaseInstructionParams {
   market: Address;
   userEta: Address;
   shareAccountId: number;
+  tokenMint: Address;
+  tokenProgram: Address;
+  /** Market's token account, only passed for markets that slash losing stake */
+  marketTokenAta?: Address;
+  /** Option account to tally the share account into if it reveals that option */
+  tallyOption?: Address;
 }
 
 export async function revealShares(
   input: RevealSharesParams,
   config: ArciumConfig
 ): Promise<RevealSharesInstruction<string>> {
-  const { programAddress, signer, owner, market, userEta, shareAccountId } = input;
+  const {
+    programAddress,
+    signer,
+    owner,
+    market,
+    userEta,
+    shareAccountId,
+    tokenMint,
+    tokenProgram,
+    marketTokenAta,
+    tallyOption,
+  } = input;
 
   return getRevealSharesInstructionAsync(
     {
@@ -28,6 +45,10 @@ export async function revealShares(
       market,
       userEta,
       shareAccountId,
+      tokenMint,
+      tokenProgram,
+      marketTokenAta,
+      option: tallyOption,
     },
     programAddress ? { programAddress } : undefined
   );
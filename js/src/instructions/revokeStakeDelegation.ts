@@ -0,0 +1,21 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getRevokeStakeDelegationInstruction,
+  type RevokeStakeDelegationInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface RevokeStakeDelegationParams extends BaseInstructionParams {
+  owner: TransactionSigner;
+  delegation: Address;
+}
+
+export function revokeStakeDelegation(
+  input: RevokeStakeDelegationParams
+): RevokeStakeDelegationInstruction<string> {
+  const { programAddress, ...params } = input;
+  return getRevokeStakeDelegationInstruction(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,24 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getSelectWinningOptionsInstruction,
+  type SelectWinningOptionsInstruction,
+  type WinningOptionArgs,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface SelectWinningOptionsParams extends BaseInstructionParams {
+  authority: TransactionSigner;
+  market: Address;
+  /** Winners in rank order, each taking `weight / totalWeight` of the reward */
+  winningOptions: WinningOptionArgs[];
+}
+
+export function selectWinningOptions(
+  input: SelectWinningOptionsParams
+): SelectWinningOptionsInstruction<string> {
+  const { programAddress, ...params } = input;
+  return getSelectWinningOptionsInstruction(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
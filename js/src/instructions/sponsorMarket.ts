@@ -0,0 +1,25 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getSponsorMarketInstructionAsync,
+  type SponsorMarketInstruction,
+} from "../generated";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface SponsorMarketParams extends BaseInstructionParams {
+  sponsor: TransactionSigner;
+  market: Address;
+  rewardMint: Address;
+  sponsorTokenAccount: Address;
+  tokenProgram: Address;
+  amount: bigint;
+}
+
+export async function sponsorMarket(
+  input: SponsorMarketParams
+): Promise<SponsorMarketInstruction<string>> {
+  const { programAddress, ...params } = input;
+  return getSponsorMarketInstructionAsync(
+    params,
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,59 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getStakeAsDelegateInstructionAsync,
+  type StakeAsDelegateInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type ByteArray, toNumberArray } from "../utils";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface StakeAsDelegateParams extends BaseInstructionParams {
+  delegate: TransactionSigner;
+  market: Address;
+  userEta: Address;
+  /** Share account of the ETA owner */
+  shareAccount: Address;
+  shareAccountId: number;
+  /** Encrypted with the delegate's own x25519 key */
+  amountCiphertext: ByteArray;
+  selectedOptionCiphertext: ByteArray;
+  delegatePubkey: ByteArray;
+  inputNonce: bigint;
+  authorizedReaderNonce: bigint;
+}
+
+export async function stakeAsDelegate(
+  input: StakeAsDelegateParams,
+  config: ArciumConfig
+): Promise<StakeAsDelegateInstruction<string>> {
+  const {
+    programAddress,
+    delegate,
+    market,
+    userEta,
+    shareAccount,
+    shareAccountId,
+    amountCiphertext,
+    selectedOptionCiphertext,
+    delegatePubkey,
+    inputNonce,
+    authorizedReaderNonce,
+  } = input;
+
+  return getStakeAsDelegateInstructionAsync(
+    {
+      ...getComputeAccounts("delegated_buy_opportunity_market_shares", config),
+      delegate,
+      market,
+      userEta,
+      shareAccount,
+      shareAccountId,
+      amountCiphertext: toNumberArray(amountCiphertext),
+      selectedOptionCiphertext: toNumberArray(selectedOptionCiphertext),
+      delegatePubkey: toNumberArray(delegatePubkey),
+      inputNonce,
+      authorizedReaderNonce,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,33 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getSyncOptionTallyInstructionAsync,
+  type SyncOptionTallyInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface SyncOptionTallyParams extends BaseInstructionParams {
+  /** Anyone, this operation is permissionless */
+  signer: TransactionSigner;
+  owner: Address;
+  market: Address;
+  shareAccountId: number;
+}
+
+export async function syncOptionTally(
+  input: SyncOptionTallyParams,
+  config: ArciumConfig
+): Promise<SyncOptionTallyInstruction<string>> {
+  const { programAddress, signer, owner, market, shareAccountId } = input;
+
+  return getSyncOptionTallyInstructionAsync(
+    {
+      ...getComputeAccounts("sync_option_tally", config),
+      signer,
+      owner,
+      market,
+      shareAccountId,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,48 @@
+import { type TransactionSigner, type Address } from "@solana/kit";
+import {
+  getTransferEncryptedTokensInstructionAsync,
+  type TransferEncryptedTokensInstruction,
+} from "../generated";
+import { type ArciumConfig, getComputeAccounts } from "../arcium/computeAccounts";
+import { type ByteArray, toNumberArray } from "../utils";
+import { type BaseInstructionParams } from "./instructionParams";
+
+export interface TransferEncryptedTokensParams extends BaseInstructionParams {
+  signer: TransactionSigner;
+  tokenMint: Address;
+  /** The signer's EncryptedTokenAccount to debit */
+  senderEncryptedTokenAccount: Address;
+  /** The EncryptedTokenAccount to credit */
+  recipientEncryptedTokenAccount: Address;
+  /** Transfer amount encrypted with the sender's x25519 key */
+  amountCiphertext: ByteArray;
+  inputNonce: bigint;
+}
+
+export async function transferEncryptedTokens(
+  input: TransferEncryptedTokensParams,
+  config: ArciumConfig
+): Promise<TransferEncryptedTokensInstruction<string>> {
+  const {
+    programAddress,
+    signer,
+    tokenMint,
+    senderEncryptedTokenAccount,
+    recipientEncryptedTokenAccount,
+    amountCiphertext,
+    inputNonce,
+  } = input;
+
+  return getTransferEncryptedTokensInstructionAsync(
+    {
+      ...getComputeAccounts("transfer_encrypted_tokens", config),
+      signer,
+      tokenMint,
+      senderEncryptedTokenAccount,
+      recipientEncryptedTokenAccount,
+      amountCiphertext: toNumberArray(amountCiphertext),
+      inputNonce,
+    },
+    programAddress ? { programAddress } : undefined
+  );
+}
@@ -0,0 +1,140 @@
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::entrypoint::ProgramResult,
+    AccountSerialize, Discriminator,
+};
+use arcium_client::idl::arcium::{
+    accounts::{ClockAccount, Cluster, ComputationAccount, ComputationDefinitionAccount, FeePool, MXEAccount},
+    types::{
+        Activation, BN254G2BLSPublicKey, CircuitSource, ComputationDefinitionMeta, ComputationSignature, Epoch,
+        MxeStatus, OffChainCircuitSource, SetUnset, Timestamp, UtilityPubkeys,
+    },
+};
+use arcium_client::pda::{clock_acc, cluster_acc, computation_acc, fee_pool_acc};
+use opportunity_market_client::arcium::{comp_def_address, mxe_address};
+use opportunity_market_client::instructions::comp_defs::ALL_COMP_DEF_CIRCUITS;
+
+pub use opportunity_market_client::arcium::ARCIUM_PROGRAM_ID;
+
+/// Cluster every fake computation is queued on
+pub const CLUSTER_OFFSET: u32 = 0;
+
+/// Discriminator of Arcium's `callback_computation`, required right before any program callback
+pub const CALLBACK_COMPUTATION_DISCRIMINATOR: [u8; 8] = [11, 224, 42, 236, 0, 154, 74, 163];
+
+/// Offsets of `slot` and `slot_counter` in `ComputationAccount` data, read by `verify_output`
+pub const SLOT_OFFSET: usize = 100;
+pub const SLOT_COUNTER_OFFSET: usize = 108;
+
+/// Stub for the Arcium program. Accepts `queue_computation` CPIs and the `callback_computation`
+/// instruction preceding each callback without doing anything; the harness plays the cluster.
+pub fn process_instruction(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).expect("account serialization");
+    data
+}
+
+pub fn mxe_account_data(x25519_pubkey: [u8; 32]) -> Vec<u8> {
+    serialize(&MXEAccount {
+        cluster: Some(CLUSTER_OFFSET),
+        keygen_offset: 0,
+        key_recovery_init_offset: 0,
+        mxe_program_id: opportunity_market::ID,
+        authority: None,
+        utility_pubkeys: SetUnset::Set(UtilityPubkeys {
+            x25519_pubkey,
+            ed25519_verifying_key: [0; 32],
+            elgamal_pubkey: [0; 32],
+            pubkey_validity_proof: [0; 64],
+        }),
+        lut_offset_slot: 0,
+        computation_definitions: Vec::new(),
+        status: MxeStatus::Active,
+        bump: 0,
+    })
+}
+
+pub fn cluster_account_data(bls_public_key: [u8; 64]) -> Vec<u8> {
+    serialize(&Cluster {
+        td_info: None,
+        authority: None,
+        cluster_size: 1,
+        activation: Activation {
+            activation_epoch: Epoch(0),
+            deactivation_epoch: Epoch(u64::MAX),
+        },
+        max_capacity: u64::MAX,
+        cu_price: 0,
+        cu_price_proposals: [0; 32],
+        last_updated_epoch: Epoch(0),
+        nodes: Vec::new(),
+        pending_nodes: Vec::new(),
+        bls_public_key: SetUnset::Set(BN254G2BLSPublicKey(bls_public_key)),
+        bump: 0,
+    })
+}
+
+pub fn comp_def_account_data() -> Vec<u8> {
+    serialize(&ComputationDefinitionAccount {
+        finalization_authority: None,
+        cu_amount: 0,
+        definition: ComputationDefinitionMeta {
+            circuit_len: 0,
+            signature: ComputationSignature {
+                parameters: Vec::new(),
+                outputs: Vec::new(),
+            },
+        },
+        circuit_source: CircuitSource::OffChain(OffChainCircuitSource {
+            source: String::new(),
+            hash: [0; 32],
+        }),
+        bump: 0,
+    })
+}
+
+pub fn fee_pool_data() -> Vec<u8> {
+    serialize(&FeePool { bump: 0 })
+}
+
+pub fn clock_account_data() -> Vec<u8> {
+    serialize(&ClockAccount {
+        start_epoch: Epoch(0),
+        current_epoch: Epoch(0),
+        start_epoch_timestamp: Timestamp { timestamp: 0 },
+        bump: 0,
+    })
+}
+
+/// Only the discriminator and the slot fields covered by the output signature are filled in.
+pub fn computation_account_data(slot: u64, slot_counter: u16) -> Vec<u8> {
+    let mut data = vec![0u8; SLOT_COUNTER_OFFSET + 2];
+    data[..8].copy_from_slice(ComputationAccount::DISCRIMINATOR);
+    data[SLOT_OFFSET..SLOT_OFFSET + 8].copy_from_slice(&slot.to_le_bytes());
+    data[SLOT_COUNTER_OFFSET..SLOT_COUNTER_OFFSET + 2].copy_from_slice(&slot_counter.to_le_bytes());
+    data
+}
+
+pub fn computation_account_address(computation_offset: u64) -> Pubkey {
+    computation_acc(CLUSTER_OFFSET, computation_offset)
+}
+
+/// Arcium-owned accounts the program reads when queueing and verifying computations
+pub fn genesis_accounts(x25519_pubkey: [u8; 32], bls_public_key: [u8; 64]) -> Vec<(Pubkey, Vec<u8>)> {
+    let mut accounts = vec![
+        (mxe_address(), mxe_account_data(x25519_pubkey)),
+        (cluster_acc(CLUSTER_OFFSET), cluster_account_data(bls_public_key)),
+        (fee_pool_acc(), fee_pool_data()),
+        (clock_acc(), clock_account_data()),
+    ];
+    accounts.extend(
+        ALL_COMP_DEF_CIRCUITS
+            .iter()
+            .map(|circuit| (comp_def_address(circuit.name()), comp_def_account_data())),
+    );
+    accounts
+}
@@ -0,0 +1,651 @@
+use anchor_lang::{
+    prelude::{AccountInfo, AccountMeta, Clock, Pubkey, Rent},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction, sysvar},
+    AccountDeserialize, AnchorDeserialize, AnchorSerialize, InstructionData,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::{
+        spl_token::{self, solana_program::program_pack::Pack},
+        TokenAccount,
+    },
+};
+use arcium_anchor::prelude::{HasSize, SignedComputationOutputs};
+use arcium_client::pda::cluster_acc;
+use opportunity_market::{
+    instruction, EncryptedTokenAccount, OpportunityMarket, OptionTallies, ShareAccount, StakeDelegation,
+};
+use opportunity_market_client::{
+    arcium::{comp_def_address, mxe_address, ArciumConfig},
+    instructions::{
+        encrypted_token_account::{
+            unwrap_encrypted_tokens, wrap_encrypted_tokens, UnwrapEncryptedTokensParams, WrapEncryptedTokensParams,
+        },
+        market::{disclose_option_tallies, DiscloseOptionTalliesParams},
+        share_account::{
+            do_unstake_early, reveal_shares, stake, sync_option_tally, DoUnstakeEarlyParams, RevealSharesParams,
+            StakeParams, SyncOptionTallyParams,
+        },
+        stake_delegation::{stake_as_delegate, StakeAsDelegateParams},
+    },
+    pda::{
+        encrypted_token_account_address, market_token_address, opportunity_market_option_address,
+        option_tallies_address, share_account_address, stake_delegation_address, token_vault_address,
+        token_vault_token_address,
+    },
+    x25519::{random_nonce, Cipher, X25519Keypair},
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::arcium::{
+    self, computation_account_address, computation_account_data, ARCIUM_PROGRAM_ID,
+    CALLBACK_COMPUTATION_DISCRIMINATOR, CLUSTER_OFFSET,
+};
+use crate::mxe::FakeMxe;
+
+/// Lamports given to every user created by the harness
+pub const USER_LAMPORTS: u64 = 100_000_000_000;
+
+fn process_opportunity_market(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // Anchor's entry ties the slice lifetime to the account lifetime, which a native
+    // processor can't express. The accounts outlive the call either way.
+    let accounts = unsafe { std::mem::transmute::<&[AccountInfo], &[AccountInfo]>(accounts) };
+    opportunity_market::entry(program_id, accounts, data)
+}
+
+/// A wallet with the x25519 keypair it uses for encrypted inputs and state
+pub struct User {
+    pub keypair: Keypair,
+    pub x25519: X25519Keypair,
+    pub cipher: Cipher,
+}
+
+impl User {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+pub struct TestContext {
+    pub context: ProgramTestContext,
+    pub mxe: FakeMxe,
+    next_computation_offset: u64,
+    slot_counter: u16,
+}
+
+impl TestContext {
+    /// Starts a bank with the program loaded natively, a stub Arcium program and the Arcium
+    /// accounts (MXE, cluster, comp defs, fee pool, clock) the program reads.
+    pub async fn new() -> Self {
+        let mxe = FakeMxe::new();
+
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "opportunity_market",
+            opportunity_market::ID,
+            processor!(process_opportunity_market),
+        );
+        program_test.add_program("arcium", ARCIUM_PROGRAM_ID, processor!(arcium::process_instruction));
+
+        let rent = Rent::default();
+        for (address, data) in arcium::genesis_accounts(mxe.x25519_public_key(), mxe.bls_public_key()) {
+            program_test.add_account(
+                address,
+                Account {
+                    lamports: rent.minimum_balance(data.len()),
+                    data,
+                    owner: ARCIUM_PROGRAM_ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+
+        Self {
+            context: program_test.start_with_context().await,
+            mxe,
+            next_computation_offset: 1,
+            slot_counter: 0,
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Sends `instructions` in one transaction paid by the harness payer.
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&self.context.payer.pubkey()), &all_signers, blockhash);
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    pub async fn account_exists(&mut self, address: Pubkey) -> bool {
+        self.context.banks_client.get_account(address).await.unwrap().is_some()
+    }
+
+    pub async fn get_account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} not found"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        self.get_account::<TokenAccount>(address).await.amount
+    }
+
+    pub async fn now(&mut self) -> u64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+    }
+
+    /// Moves the bank clock to `timestamp`.
+    pub async fn warp_to_timestamp(&mut self, timestamp: u64) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = timestamp as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Funds a new wallet and derives its x25519 keypair and cipher against the fake MXE.
+    pub fn create_user(&mut self) -> User {
+        let keypair = Keypair::new();
+        self.context.set_account(
+            &keypair.pubkey(),
+            &Account {
+                lamports: USER_LAMPORTS,
+                data: Vec::new(),
+                owner: anchor_lang::system_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+        let x25519 = X25519Keypair::generate();
+        let cipher = Cipher::new(&x25519.secret_key, &self.mxe.x25519_public_key()).unwrap();
+        User { keypair, x25519, cipher }
+    }
+
+    /// Creates an SPL token mint with the harness payer as mint authority.
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new().pubkey();
+        let rent = Rent::default();
+        self.context.set_account(
+            &mint,
+            &Account {
+                lamports: rent.minimum_balance(spl_token::state::Mint::LEN),
+                data: vec![0; spl_token::state::Mint::LEN],
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+        let payer = self.payer();
+        let ix = spl_token::instruction::initialize_mint2(&spl_token::ID, &mint, &payer, None, decimals).unwrap();
+        self.process(&[ix], &[]).await.unwrap();
+        mint
+    }
+
+    /// Returns the ATA of `owner` for `mint`, creating it if needed.
+    pub async fn create_token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let payer = self.payer();
+        let ix = create_associated_token_account_idempotent(&payer, owner, mint, &spl_token::ID);
+        self.process(&[ix], &[]).await.unwrap();
+        get_associated_token_address_with_program_id(owner, mint, &spl_token::ID)
+    }
+
+    pub async fn mint_to(&mut self, mint: &Pubkey, destination: &Pubkey, amount: u64) {
+        let payer = self.payer();
+        let ix = spl_token::instruction::mint_to(&spl_token::ID, mint, destination, &payer, &[], amount).unwrap();
+        self.process(&[ix], &[]).await.unwrap();
+    }
+
+    /// Fresh computation offset on the fake cluster
+    pub fn next_arcium_config(&mut self) -> ArciumConfig {
+        let computation_offset = self.next_computation_offset;
+        self.next_computation_offset += 1;
+        ArciumConfig {
+            cluster_offset: CLUSTER_OFFSET,
+            computation_offset,
+        }
+    }
+
+    /// Plays the cluster finishing a queued computation: writes the computation account,
+    /// signs `output` and sends `callback_computation` followed by the program callback.
+    pub async fn finalize_computation<O, D>(
+        &mut self,
+        computation_offset: u64,
+        circuit_name: &str,
+        output: O,
+        callback: impl FnOnce(SignedComputationOutputs<O>) -> D,
+        callback_accounts: Vec<AccountMeta>,
+    ) -> Result<(), BanksClientError>
+    where
+        O: HasSize + AnchorSerialize + AnchorDeserialize,
+        D: InstructionData,
+    {
+        let computation_account = computation_account_address(computation_offset);
+        let slot = self.context.banks_client.get_root_slot().await?;
+        self.slot_counter += 1;
+        let data = computation_account_data(slot, self.slot_counter);
+        let (bytes, signature) = self.mxe.sign_output(&output, &data);
+        self.context.set_account(
+            &computation_account,
+            &Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: ARCIUM_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        );
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(ARCIUM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(comp_def_address(circuit_name), false),
+            AccountMeta::new_readonly(mxe_address(), false),
+            AccountMeta::new_readonly(computation_account, false),
+            AccountMeta::new_readonly(cluster_acc(CLUSTER_OFFSET), false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        ];
+        accounts.extend(callback_accounts);
+
+        let callback_computation = Instruction {
+            program_id: ARCIUM_PROGRAM_ID,
+            accounts: Vec::new(),
+            data: CALLBACK_COMPUTATION_DISCRIMINATOR.to_vec(),
+        };
+        let callback_ix = Instruction {
+            program_id: opportunity_market::ID,
+            accounts,
+            data: callback(SignedComputationOutputs::Success(bytes, signature)).data(),
+        };
+        self.process(&[callback_computation, callback_ix], &[]).await
+    }
+
+    pub async fn wrap_encrypted_tokens(&mut self, user: &User, mint: Pubkey, amount: u64) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let eta = encrypted_token_account_address(&mint, &user.pubkey()).0;
+        let ix = wrap_encrypted_tokens(
+            WrapEncryptedTokensParams {
+                signer: user.pubkey(),
+                token_mint: mint,
+                encrypted_token_account: eta,
+                signer_token_account: get_associated_token_address_with_program_id(
+                    &user.pubkey(),
+                    &mint,
+                    &spl_token::ID,
+                ),
+                token_program: spl_token::ID,
+                amount,
+            },
+            config,
+        );
+        self.process(&[ix], &[&user.keypair]).await?;
+
+        let eta_state = self.get_account::<EncryptedTokenAccount>(eta).await;
+        let output = self.mxe.wrap_encrypted_tokens(&eta_state, amount);
+        self.finalize_computation(
+            config.computation_offset,
+            "wrap_encrypted_tokens",
+            output,
+            |output| instruction::WrapEncryptedTokensCallback { output },
+            vec![AccountMeta::new(eta, false)],
+        )
+        .await
+    }
+
+    pub async fn unwrap_encrypted_tokens(&mut self, user: &User, mint: Pubkey, amount: u64) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let eta = encrypted_token_account_address(&mint, &user.pubkey()).0;
+        let user_token_account = get_associated_token_address_with_program_id(&user.pubkey(), &mint, &spl_token::ID);
+        let ix = unwrap_encrypted_tokens(
+            UnwrapEncryptedTokensParams {
+                signer: user.pubkey(),
+                token_mint: mint,
+                encrypted_token_account: eta,
+                user_token_account,
+                token_program: spl_token::ID,
+                amount,
+            },
+            config,
+        );
+        self.process(&[ix], &[&user.keypair]).await?;
+
+        let eta_state = self.get_account::<EncryptedTokenAccount>(eta).await;
+        let output = self.mxe.unwrap_encrypted_tokens(&eta_state, amount);
+        self.finalize_computation(
+            config.computation_offset,
+            "unwrap_encrypted_tokens",
+            output,
+            |output| instruction::UnwrapEncryptedTokensCallback { output },
+            vec![
+                AccountMeta::new(eta, false),
+                AccountMeta::new(user_token_account, false),
+                AccountMeta::new_readonly(token_vault_address().0, false),
+                AccountMeta::new(token_vault_token_address(&mint, &spl_token::ID), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+        )
+        .await
+    }
+
+    /// Stakes `amount` on `selected_option` from the user's ETA into an initialized share account.
+    pub async fn stake(
+        &mut self,
+        user: &User,
+        market: Pubkey,
+        share_account_id: u32,
+        amount: u64,
+        selected_option: u16,
+    ) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let market_state = self.get_account::<OpportunityMarket>(market).await;
+        let user_eta = encrypted_token_account_address(&market_state.mint, &user.pubkey()).0;
+        let share_account = share_account_address(&user.pubkey(), &market, share_account_id).0;
+        let input = user.cipher.encrypt_stake_input(amount, selected_option);
+        let authorized_reader_nonce = random_nonce();
+        let ix = stake(
+            StakeParams {
+                signer: user.pubkey(),
+                market,
+                user_eta,
+                share_account_id,
+                amount_ciphertext: input.amount_ciphertext,
+                selected_option_ciphertext: input.selected_option_ciphertext,
+                input_nonce: input.input_nonce,
+                authorized_reader_nonce,
+            },
+            config,
+        );
+        self.process(&[ix], &[&user.keypair]).await?;
+
+        let eta_state = self.get_account::<EncryptedTokenAccount>(user_eta).await;
+        let share_state = self.get_account::<ShareAccount>(share_account).await;
+        let output = self.mxe.buy_opportunity_market_shares(
+            &input,
+            market_state.authorized_reader_pubkey,
+            authorized_reader_nonce,
+            &eta_state,
+            &share_state,
+            share_state.staked_at_timestamp.unwrap(),
+            market_state.min_stake,
+            market_state.max_stake.unwrap_or(u64::MAX),
+        );
+        self.finalize_computation(
+            config.computation_offset,
+            "buy_opportunity_market_shares",
+            output,
+            |output| instruction::BuyOpportunityMarketSharesCallback { output },
+            vec![
+                AccountMeta::new(user_eta, false),
+                AccountMeta::new(share_account, false),
+            ],
+        )
+        .await
+    }
+
+    /// Stakes `amount` on `selected_option` from `owner`'s ETA through the delegate's stake
+    /// delegation, into a share account of `owner`.
+    pub async fn stake_as_delegate(
+        &mut self,
+        delegate: &User,
+        owner: Pubkey,
+        market: Pubkey,
+        share_account_id: u32,
+        amount: u64,
+        selected_option: u16,
+    ) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let market_state = self.get_account::<OpportunityMarket>(market).await;
+        let user_eta = encrypted_token_account_address(&market_state.mint, &owner).0;
+        let share_account = share_account_address(&owner, &market, share_account_id).0;
+        let delegation = stake_delegation_address(&user_eta, &delegate.pubkey()).0;
+        let input = delegate.cipher.encrypt_stake_input(amount, selected_option);
+        let authorized_reader_nonce = random_nonce();
+        let ix = stake_as_delegate(
+            StakeAsDelegateParams {
+                delegate: delegate.pubkey(),
+                owner,
+                market,
+                user_eta,
+                share_account_id,
+                amount_ciphertext: input.amount_ciphertext,
+                selected_option_ciphertext: input.selected_option_ciphertext,
+                delegate_pubkey: delegate.x25519.public_key,
+                input_nonce: input.input_nonce,
+                authorized_reader_nonce,
+            },
+            config,
+        );
+        self.process(&[ix], &[&delegate.keypair]).await?;
+
+        let eta_state = self.get_account::<EncryptedTokenAccount>(user_eta).await;
+        let share_state = self.get_account::<ShareAccount>(share_account).await;
+        let delegation_state = self.get_account::<StakeDelegation>(delegation).await;
+        let output = self.mxe.delegated_buy_opportunity_market_shares(
+            &input,
+            delegate.x25519.public_key,
+            market_state.authorized_reader_pubkey,
+            authorized_reader_nonce,
+            &eta_state,
+            &share_state,
+            share_state.staked_at_timestamp.unwrap(),
+            market_state.min_stake,
+            market_state.max_stake.unwrap_or(u64::MAX),
+            &delegation_state,
+        );
+        self.finalize_computation(
+            config.computation_offset,
+            "delegated_buy_opportunity_market_shares",
+            output,
+            |output| instruction::DelegatedBuyOpportunityMarketSharesCallback { output },
+            vec![
+                AccountMeta::new(user_eta, false),
+                AccountMeta::new(share_account, false),
+                AccountMeta::new(delegation, false),
+            ],
+        )
+        .await
+    }
+
+    /// Refunds an unstaked share account to the owner's ETA, after `unstake_early` and its delay.
+    pub async fn do_unstake_early(
+        &mut self,
+        owner: &User,
+        market: Pubkey,
+        share_account_id: u32,
+    ) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let market_state = self.get_account::<OpportunityMarket>(market).await;
+        let user_eta = encrypted_token_account_address(&market_state.mint, &owner.pubkey()).0;
+        let share_account = share_account_address(&owner.pubkey(), &market, share_account_id).0;
+        let payer = self.payer();
+        let ix = do_unstake_early(
+            DoUnstakeEarlyParams {
+                signer: payer,
+                market,
+                user_eta,
+                share_account_id,
+                share_account_owner: owner.pubkey(),
+            },
+            config,
+        );
+        self.process(&[ix], &[]).await?;
+
+        let eta_state = self.get_account::<EncryptedTokenAccount>(user_eta).await;
+        let share_state = self.get_account::<ShareAccount>(share_account).await;
+        let output = self.mxe.unstake_early(&share_state, &eta_state);
+        self.finalize_computation(
+            config.computation_offset,
+            "unstake_early",
+            output,
+            |output| instruction::UnstakeEarlyCallback { output },
+            vec![
+                AccountMeta::new(user_eta, false),
+                AccountMeta::new(share_account, false),
+            ],
+        )
+        .await
+    }
+
+    /// Moves a share account's pending stake change into the market's option tallies.
+    pub async fn sync_option_tally(&mut self, owner: Pubkey, market: Pubkey, share_account_id: u32) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let share_account = share_account_address(&owner, &market, share_account_id).0;
+        let option_tallies = option_tallies_address(&market).0;
+        let payer = self.payer();
+        let ix = sync_option_tally(
+            SyncOptionTallyParams {
+                signer: payer,
+                owner,
+                market,
+                share_account_id,
+            },
+            config,
+        );
+        self.process(&[ix], &[]).await?;
+
+        let share_state = self.get_account::<ShareAccount>(share_account).await;
+        let tallies_state = self.get_account::<OptionTallies>(option_tallies).await;
+        let output = self.mxe.sync_option_tally(&share_state, &tallies_state);
+        self.finalize_computation(
+            config.computation_offset,
+            "sync_option_tally",
+            output,
+            |output| instruction::SyncOptionTallyCallback { output },
+            vec![
+                AccountMeta::new(share_account, false),
+                AccountMeta::new(option_tallies, false),
+            ],
+        )
+        .await
+    }
+
+    /// Discloses the market's running option tallies to its authorized reader.
+    pub async fn disclose_option_tallies(&mut self, authority: &User, market: Pubkey) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let market_state = self.get_account::<OpportunityMarket>(market).await;
+        let option_tallies = option_tallies_address(&market).0;
+        let authorized_reader_nonce = random_nonce();
+        let ix = disclose_option_tallies(
+            DiscloseOptionTalliesParams {
+                authority: authority.pubkey(),
+                market,
+                authorized_reader_nonce,
+            },
+            config,
+        );
+        self.process(&[ix], &[&authority.keypair]).await?;
+
+        let tallies_state = self.get_account::<OptionTallies>(option_tallies).await;
+        let output = self.mxe.disclose_option_tallies(
+            &tallies_state,
+            market_state.authorized_reader_pubkey,
+            authorized_reader_nonce,
+        );
+        self.finalize_computation(
+            config.computation_offset,
+            "disclose_option_tallies",
+            output,
+            |output| instruction::DiscloseOptionTalliesCallback { output },
+            vec![AccountMeta::new(option_tallies, false)],
+        )
+        .await
+    }
+
+    /// Reveals a share account and credits its stake, minus any slash, back to the owner's ETA.
+    pub async fn reveal_shares(&mut self, owner: &User, market: Pubkey, share_account_id: u32) -> Result<(), BanksClientError> {
+        self.reveal_and_tally_shares(owner, market, share_account_id, None).await
+    }
+
+    /// Like `reveal_shares`, passing `tally_option_index` so the callback also tallies the
+    /// share account if it revealed that option.
+    pub async fn reveal_and_tally_shares(
+        &mut self,
+        owner: &User,
+        market: Pubkey,
+        share_account_id: u32,
+        tally_option_index: Option<u16>,
+    ) -> Result<(), BanksClientError> {
+        let config = self.next_arcium_config();
+        let market_state = self.get_account::<OpportunityMarket>(market).await;
+        let mint = market_state.mint;
+        let user_eta = encrypted_token_account_address(&mint, &owner.pubkey()).0;
+        let share_account = share_account_address(&owner.pubkey(), &market, share_account_id).0;
+        let payer = self.payer();
+        let ix = reveal_shares(
+            RevealSharesParams {
+                signer: payer,
+                owner: owner.pubkey(),
+                market,
+                user_eta,
+                share_account_id,
+                token_mint: mint,
+                reward_mint: market_state.reward_mint,
+                token_program: spl_token::ID,
+                tally_option_index,
+            },
+            config,
+        );
+        self.process(&[ix], &[]).await?;
+
+        let mut winning_options = [0u64; 4];
+        for (slot, winner) in winning_options.iter_mut().zip(market_state.winning_options.iter()) {
+            *slot = winner.option_index as u64;
+        }
+        let eta_state = self.get_account::<EncryptedTokenAccount>(user_eta).await;
+        let share_state = self.get_account::<ShareAccount>(share_account).await;
+        let output = self.mxe.reveal_shares(
+            &share_state,
+            &eta_state,
+            winning_options,
+            market_state.losing_stake_slash_bps as u64,
+        );
+        self.finalize_computation(
+            config.computation_offset,
+            "reveal_shares",
+            output,
+            |output| instruction::RevealSharesCallback { output },
+            vec![
+                AccountMeta::new(share_account, false),
+                AccountMeta::new(user_eta, false),
+                AccountMeta::new(market, false),
+                AccountMeta::new_readonly(token_vault_address().0, false),
+                AccountMeta::new(token_vault_token_address(&mint, &spl_token::ID), false),
+                // Only markets paying rewards in the staked token pass their token account
+                if market_state.reward_mint == mint {
+                    AccountMeta::new(market_token_address(&market, &mint, &spl_token::ID), false)
+                } else {
+                    AccountMeta::new_readonly(opportunity_market::ID, false)
+                },
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                // The program id stands in for a missing option account
+                match tally_option_index {
+                    Some(option_index) => AccountMeta::new(opportunity_market_option_address(&market, option_index).0, false),
+                    None => AccountMeta::new_readonly(opportunity_market::ID, false),
+                },
+            ],
+        )
+        .await
+    }
+}
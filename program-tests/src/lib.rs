@@ -0,0 +1,7 @@
+//! Test harness for the `opportunity_market` program. Runs the program natively under
+//! `solana-program-test` next to a stub Arcium program, with [`mxe::FakeMxe`] evaluating the
+//! circuits in plaintext and signing the callback outputs with the cluster BLS key.
+
+pub mod arcium;
+pub mod harness;
+pub mod mxe;
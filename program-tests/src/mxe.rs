@@ -0,0 +1,388 @@
+use anchor_lang::AnchorSerialize;
+use arcium_anchor::prelude::{MXEEncryptedStruct, SharedEncryptedStruct};
+use encrypted_ixs::reference::{self, DelegationSpend, EncryptedTokenBalance, SharePurchase, TalliedStake};
+use opportunity_market::{
+    BuyOpportunityMarketSharesOutput, BuyOpportunityMarketSharesOutputStruct0,
+    DelegatedBuyOpportunityMarketSharesOutput, DelegatedBuyOpportunityMarketSharesOutputStruct0,
+    DiscloseOptionTalliesOutput, EncryptedTokenAccount, OptionTallies, RevealSharesOutput, RevealSharesOutputStruct0,
+    ShareAccount, StakeDelegation, SyncOptionTallyOutput, SyncOptionTallyOutputStruct0, UnstakeEarlyOutput, UnstakeEarlyOutputStruct0, UnwrapEncryptedTokensOutput, UnwrapEncryptedTokensOutputStruct0,
+    WrapEncryptedTokensOutput, WrapEncryptedTokensOutputStruct0, MAX_TALLIED_OPTIONS,
+};
+use opportunity_market_client::x25519::{Cipher, DecryptedShareState, StakeInput, X25519Keypair};
+use solana_alt_bn128_bls::{G2CompressedPoint, PrivKey, Sha256Normalized};
+
+use crate::arcium::{SLOT_COUNTER_OFFSET, SLOT_OFFSET};
+
+/// Stands in for the MXE cluster: holds the MXE x25519 key and the cluster BLS key, evaluates
+/// the circuits through `encrypted_ixs::reference` in plaintext and signs their outputs.
+pub struct FakeMxe {
+    keypair: X25519Keypair,
+    bls_key: PrivKey,
+}
+
+impl Default for FakeMxe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeMxe {
+    pub fn new() -> Self {
+        Self {
+            keypair: X25519Keypair::generate(),
+            bls_key: PrivKey::from_random(),
+        }
+    }
+
+    pub fn x25519_public_key(&self) -> [u8; 32] {
+        self.keypair.public_key
+    }
+
+    pub fn bls_public_key(&self) -> [u8; 64] {
+        G2CompressedPoint::try_from(&self.bls_key)
+            .expect("valid BLS private key")
+            .0
+    }
+
+    /// Signs `output || slot || slot_counter`, the message checked by `verify_output`.
+    pub fn sign(&self, output: &[u8], computation_account: &[u8]) -> [u8; 64] {
+        let message = [
+            output,
+            &computation_account[SLOT_OFFSET..SLOT_OFFSET + 8],
+            &computation_account[SLOT_COUNTER_OFFSET..SLOT_COUNTER_OFFSET + 2],
+        ]
+        .concat();
+        self.bls_key
+            .sign::<Sha256Normalized, _>(message)
+            .expect("BLS signing")
+            .0
+    }
+
+    /// Serializes and signs an output struct, returning the bytes expected by
+    /// `SignedComputationOutputs::Success`.
+    pub fn sign_output<O: AnchorSerialize>(&self, output: &O, computation_account: &[u8]) -> (Vec<u8>, [u8; 64]) {
+        let bytes = output.try_to_vec().expect("output serialization");
+        let signature = self.sign(&bytes, computation_account);
+        (bytes, signature)
+    }
+
+    fn cipher(&self, pubkey: &[u8; 32]) -> Cipher {
+        Cipher::new(&self.keypair.secret_key, pubkey).expect("valid x25519 public key")
+    }
+
+    fn encrypt<const N: usize>(&self, pubkey: [u8; 32], values: &[u128], input_nonce: u128) -> SharedEncryptedStruct<N> {
+        // Outputs are encrypted under the input nonce incremented by one, like the MXE does
+        let nonce = input_nonce.wrapping_add(1);
+        let ciphertexts = self.cipher(&pubkey).encrypt(values, nonce);
+        SharedEncryptedStruct {
+            encryption_key: pubkey,
+            nonce,
+            ciphertexts: ciphertexts.try_into().expect("ciphertext count"),
+        }
+    }
+
+    /// `Enc<Mxe, T>` state is encrypted to the MXE's own key, so only the MXE can read it back
+    fn mxe_cipher(&self) -> Cipher {
+        self.cipher(&self.keypair.public_key)
+    }
+
+    fn encrypt_tallies(
+        &self,
+        option_tallies: &OptionTallies,
+        tallies: &reference::OptionTallies,
+    ) -> MXEEncryptedStruct<MAX_TALLIED_OPTIONS> {
+        let nonce = option_tallies.state_nonce.wrapping_add(1);
+        let values = tallies.amounts.map(|amount| amount as u128);
+        let ciphertexts = self.mxe_cipher().encrypt(&values, nonce);
+        MXEEncryptedStruct {
+            nonce,
+            ciphertexts: ciphertexts.try_into().expect("ciphertext count"),
+        }
+    }
+
+    fn encrypt_balance(&self, eta: &EncryptedTokenAccount, amount: u64) -> SharedEncryptedStruct<1> {
+        self.encrypt(eta.user_pubkey, &[amount as u128], eta.state_nonce)
+    }
+
+    fn encrypt_share(&self, pubkey: [u8; 32], nonce: u128, share: &DecryptedShareState) -> SharedEncryptedStruct<3> {
+        self.encrypt(
+            pubkey,
+            &[share.amount as u128, share.selected_option as u128, share.weighted_staked_at],
+            nonce,
+        )
+    }
+
+    /// Plaintext ETA balance, 0 while the account has never been written by a callback.
+    pub fn balance(&self, eta: &EncryptedTokenAccount) -> u64 {
+        if !eta.is_initialized {
+            return 0;
+        }
+        self.cipher(&eta.user_pubkey)
+            .decrypt_balance(eta)
+            .expect("valid ETA ciphertext")
+    }
+
+    pub fn share_state(&self, share_account: &ShareAccount, owner_pubkey: &[u8; 32]) -> DecryptedShareState {
+        self.cipher(owner_pubkey)
+            .decrypt_share_state(share_account)
+            .expect("valid share account ciphertext")
+    }
+
+    /// Plaintext running tallies, all zero while the account has never been written by a callback.
+    pub fn option_tallies(&self, option_tallies: &OptionTallies) -> reference::OptionTallies {
+        let mut tallies = reference::OptionTallies::default();
+        if !option_tallies.is_initialized {
+            return tallies;
+        }
+        let values = self
+            .mxe_cipher()
+            .decrypt(&option_tallies.encrypted_state, option_tallies.state_nonce)
+            .expect("valid option tallies ciphertext");
+        for (amount, value) in tallies.amounts.iter_mut().zip(values) {
+            *amount = value as u64;
+        }
+        tallies
+    }
+
+    /// Plaintext stake already counted in the option tallies, 0 while the share account has never
+    /// been synced.
+    pub fn tallied_stake(&self, share_account: &ShareAccount) -> TalliedStake {
+        if !share_account.is_tallied_state_initialized {
+            return TalliedStake::default();
+        }
+        let values = self
+            .mxe_cipher()
+            .decrypt(&share_account.encrypted_tallied_state, share_account.tallied_state_nonce)
+            .expect("valid tallied stake ciphertext");
+        TalliedStake {
+            amount: values[0] as u64,
+        }
+    }
+
+    /// Plaintext amount a delegate has staked so far, 0 while the delegation has never been
+    /// written by a callback.
+    pub fn delegation_spent(&self, delegation: &StakeDelegation, owner_pubkey: &[u8; 32]) -> u64 {
+        self.cipher(owner_pubkey)
+            .decrypt_delegation_spent(delegation)
+            .expect("valid delegation ciphertext")
+    }
+
+    fn token_balance(&self, eta: &EncryptedTokenAccount) -> EncryptedTokenBalance {
+        EncryptedTokenBalance {
+            amount: self.balance(eta),
+        }
+    }
+
+    fn share_purchase(&self, share_account: &ShareAccount, owner_pubkey: &[u8; 32]) -> SharePurchase {
+        let shares = self.share_state(share_account, owner_pubkey);
+        SharePurchase {
+            amount: shares.amount,
+            selected_option: shares.selected_option,
+            weighted_staked_at: shares.weighted_staked_at,
+        }
+    }
+
+    pub fn wrap_encrypted_tokens(&self, eta: &EncryptedTokenAccount, amount: u64) -> WrapEncryptedTokensOutput {
+        let (overflow, balance) = reference::wrap_encrypted_tokens(self.token_balance(eta), eta.is_initialized, amount);
+        WrapEncryptedTokensOutput {
+            field_0: WrapEncryptedTokensOutputStruct0 {
+                field_0: overflow,
+                field_1: self.encrypt_balance(eta, balance.amount),
+            },
+        }
+    }
+
+    pub fn unwrap_encrypted_tokens(&self, eta: &EncryptedTokenAccount, amount: u64) -> UnwrapEncryptedTokensOutput {
+        let (insufficient_balance, sold, new_balance) =
+            reference::unwrap_encrypted_tokens(self.token_balance(eta), amount);
+        UnwrapEncryptedTokensOutput {
+            field_0: UnwrapEncryptedTokensOutputStruct0 {
+                field_0: insufficient_balance,
+                field_1: sold,
+                field_2: self.encrypt_balance(eta, new_balance.amount),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn buy_opportunity_market_shares(
+        &self,
+        input: &StakeInput,
+        authorized_reader_pubkey: [u8; 32],
+        authorized_reader_nonce: u128,
+        user_eta: &EncryptedTokenAccount,
+        share_account: &ShareAccount,
+        staked_at: u64,
+        min_stake: u64,
+        max_stake: u64,
+    ) -> BuyOpportunityMarketSharesOutput {
+        let values = self
+            .cipher(&user_eta.user_pubkey)
+            .decrypt(
+                &[input.amount_ciphertext, input.selected_option_ciphertext],
+                input.input_nonce,
+            )
+            .expect("valid stake input ciphertext");
+        let (amount, selected_option) = (values[0] as u64, values[1] as u16);
+
+        let (error, new_balance, bought) = reference::buy_opportunity_market_shares(
+            amount,
+            selected_option,
+            self.token_balance(user_eta),
+            staked_at,
+            min_stake,
+            max_stake,
+        );
+        let bought_shares = DecryptedShareState {
+            amount: bought.amount,
+            selected_option: bought.selected_option,
+            weighted_staked_at: bought.weighted_staked_at,
+        };
+
+        BuyOpportunityMarketSharesOutput {
+            field_0: BuyOpportunityMarketSharesOutputStruct0 {
+                field_0: error,
+                field_1: self.encrypt_balance(user_eta, new_balance.amount),
+                field_2: self.encrypt_share(user_eta.user_pubkey, share_account.state_nonce, &bought_shares),
+                field_3: self.encrypt_share(authorized_reader_pubkey, authorized_reader_nonce, &bought_shares),
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn delegated_buy_opportunity_market_shares(
+        &self,
+        input: &StakeInput,
+        delegate_pubkey: [u8; 32],
+        authorized_reader_pubkey: [u8; 32],
+        authorized_reader_nonce: u128,
+        user_eta: &EncryptedTokenAccount,
+        share_account: &ShareAccount,
+        staked_at: u64,
+        min_stake: u64,
+        max_stake: u64,
+        delegation: &StakeDelegation,
+    ) -> DelegatedBuyOpportunityMarketSharesOutput {
+        let values = self
+            .cipher(&delegate_pubkey)
+            .decrypt(
+                &[input.amount_ciphertext, input.selected_option_ciphertext],
+                input.input_nonce,
+            )
+            .expect("valid stake input ciphertext");
+        let (amount, selected_option) = (values[0] as u64, values[1] as u16);
+
+        let (error, new_balance, bought, spend) = reference::delegated_buy_opportunity_market_shares(
+            amount,
+            selected_option,
+            self.token_balance(user_eta),
+            staked_at,
+            min_stake,
+            max_stake,
+            DelegationSpend {
+                amount: self.delegation_spent(delegation, &user_eta.user_pubkey),
+            },
+            delegation.is_initialized,
+            delegation.spending_cap,
+        );
+        let bought_shares = DecryptedShareState {
+            amount: bought.amount,
+            selected_option: bought.selected_option,
+            weighted_staked_at: bought.weighted_staked_at,
+        };
+
+        DelegatedBuyOpportunityMarketSharesOutput {
+            field_0: DelegatedBuyOpportunityMarketSharesOutputStruct0 {
+                field_0: error,
+                field_1: self.encrypt_balance(user_eta, new_balance.amount),
+                field_2: self.encrypt_share(user_eta.user_pubkey, share_account.state_nonce, &bought_shares),
+                field_3: self.encrypt_share(authorized_reader_pubkey, authorized_reader_nonce, &bought_shares),
+                field_4: self.encrypt(user_eta.user_pubkey, &[spend.amount as u128], delegation.state_nonce),
+            },
+        }
+    }
+
+    pub fn unstake_early(
+        &self,
+        share_account: &ShareAccount,
+        user_eta: &EncryptedTokenAccount,
+    ) -> UnstakeEarlyOutput {
+        let (overflow, balance) = reference::unstake_early(
+            self.share_purchase(share_account, &user_eta.user_pubkey),
+            self.token_balance(user_eta),
+            user_eta.is_initialized,
+        );
+        UnstakeEarlyOutput {
+            field_0: UnstakeEarlyOutputStruct0 {
+                field_0: overflow,
+                field_1: self.encrypt_balance(user_eta, balance.amount),
+            },
+        }
+    }
+
+    pub fn sync_option_tally(&self, share_account: &ShareAccount, option_tallies: &OptionTallies) -> SyncOptionTallyOutput {
+        let (overflow, tallied, tallies) = reference::sync_option_tally(
+            self.share_purchase(share_account, &share_account.user_pubkey),
+            self.tallied_stake(share_account),
+            share_account.is_tallied_state_initialized,
+            share_account.unstaked_at_timestamp.is_some(),
+            self.option_tallies(option_tallies),
+            option_tallies.is_initialized,
+        );
+        let tallied_nonce = share_account.tallied_state_nonce.wrapping_add(1);
+        let tallied_ciphertexts = self.mxe_cipher().encrypt(&[tallied.amount as u128], tallied_nonce);
+        SyncOptionTallyOutput {
+            field_0: SyncOptionTallyOutputStruct0 {
+                field_0: overflow,
+                field_1: MXEEncryptedStruct {
+                    nonce: tallied_nonce,
+                    ciphertexts: tallied_ciphertexts.try_into().expect("ciphertext count"),
+                },
+                field_2: self.encrypt_tallies(option_tallies, &tallies),
+            },
+        }
+    }
+
+    pub fn disclose_option_tallies(
+        &self,
+        option_tallies: &OptionTallies,
+        authorized_reader_pubkey: [u8; 32],
+        authorized_reader_nonce: u128,
+    ) -> DiscloseOptionTalliesOutput {
+        let tallies = reference::disclose_option_tallies(
+            self.option_tallies(option_tallies),
+            option_tallies.is_initialized,
+        );
+        let values = tallies.amounts.map(|amount| amount as u128);
+        DiscloseOptionTalliesOutput {
+            field_0: self.encrypt(authorized_reader_pubkey, &values, authorized_reader_nonce),
+        }
+    }
+
+    pub fn reveal_shares(
+        &self,
+        share_account: &ShareAccount,
+        user_eta: &EncryptedTokenAccount,
+        winning_options: [u64; 4],
+        slash_bps: u64,
+    ) -> RevealSharesOutput {
+        let (overflow, amount, option, weighted_staked_at, slashed, balance) = reference::reveal_shares(
+            self.share_purchase(share_account, &user_eta.user_pubkey),
+            self.token_balance(user_eta),
+            user_eta.is_initialized,
+            winning_options,
+            slash_bps,
+        );
+
+        RevealSharesOutput {
+            field_0: RevealSharesOutputStruct0 {
+                field_0: overflow,
+                field_1: amount,
+                field_2: option,
+                field_3: weighted_staked_at,
+                field_4: slashed,
+                field_5: self.encrypt_balance(user_eta, balance.amount),
+            },
+        }
+    }
+}
@@ -0,0 +1,1671 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use opportunity_market_client::{
+    accounts::{
+        EncryptedTokenAccount, MarketParams, MarketSponsor, MarketTemplate, OpportunityMarket, OpportunityMarketOption, OptionTallies,
+        ScoringConfig, ShareAccount, StakeDelegation, WinningOption, MAX_TALLIED_OPTIONS,
+    },
+    instructions::{
+        central_state::{init_central_state, init_token_vault, InitCentralStateParams, InitTokenVaultParams},
+        encrypted_token_account::{
+            init_encrypted_token_account, init_ephemeral_encrypted_token_account, InitEncryptedTokenAccountParams,
+            InitEphemeralEncryptedTokenAccountParams,
+        },
+        market::{
+            activate_market, add_market_option_as_creator, cancel_market, close_market, close_market_option,
+            close_market_sponsor, close_share_account, create_market, create_market_from_template, create_market_template,
+            increment_option_tally, open_market, reclaim_market_rewards, refund_sponsor, select_option,
+            select_winning_options, sponsor_market, ActivateMarketParams, AddMarketOptionAsCreatorParams,
+            CancelMarketParams, CloseMarketOptionParams, CloseMarketParams, CloseMarketSponsorParams,
+            CloseShareAccountParams,
+            CreateMarketFromTemplateParams, CreateMarketParams, CreateMarketTemplateParams, IncrementOptionTallyParams,
+            OpenMarketParams, ReclaimMarketRewardsParams, RefundSponsorParams, SelectOptionParams,
+            SelectWinningOptionsParams, SponsorMarketParams,
+        },
+        share_account::{
+            init_share_account, reveal_shares, unstake_early, InitShareAccountParams, RevealSharesParams,
+            UnstakeEarlyParams,
+        },
+        stake_delegation::{
+            create_stake_delegation, init_share_account_as_delegate, revoke_stake_delegation,
+            CreateStakeDelegationParams, InitShareAccountAsDelegateParams, RevokeStakeDelegationParams,
+        },
+    },
+    pda::{
+        encrypted_token_account_address, encrypted_token_account_address_with_index, market_sponsor_address,
+        market_template_address, market_token_address, opportunity_market_address,
+        opportunity_market_option_address, option_tallies_address, share_account_address, stake_delegation_address,
+        template_market_address, token_vault_address,
+    },
+    x25519::{random_nonce, Cipher, X25519Keypair},
+};
+use opportunity_market_program_tests::harness::{TestContext, User};
+
+const REWARD_AMOUNT: u64 = 1_000;
+const WRAPPED_AMOUNT: u64 = 1_000;
+const TIME_TO_STAKE: u64 = 1_000;
+const TIME_TO_REVEAL: u64 = 1_000;
+const MIN_STAKE: u64 = 10;
+
+struct Setup {
+    ctx: TestContext,
+    mint: Pubkey,
+    creator: User,
+    reader: X25519Keypair,
+    market: Pubkey,
+    open_timestamp: u64,
+}
+
+/// Central state, token vault, a funded market with two options opened for staking
+async fn setup() -> Setup {
+    let mut ctx = TestContext::new().await;
+    let payer = ctx.payer();
+
+    ctx.process(
+        &[
+            init_central_state(InitCentralStateParams {
+                payer,
+                earliness_cutoff_seconds: TIME_TO_STAKE,
+                min_option_deposit: 1,
+                reward_reclaim_grace_seconds: 86_400,
+                protocol_fee_bps: 0,
+                treasury: payer,
+            }),
+            init_token_vault(InitTokenVaultParams {
+                payer,
+                fund_manager: payer,
+            }),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let mint = ctx.create_mint(6).await;
+    ctx.create_token_account(&token_vault_address().0, &mint).await;
+
+    let creator = ctx.create_user();
+    let reader = X25519Keypair::generate();
+    let market = opportunity_market_address(&creator.pubkey(), 0).0;
+
+    ctx.process(
+        &[create_market(CreateMarketParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            market_index: 0,
+            reward_amount: REWARD_AMOUNT,
+            time_to_stake: TIME_TO_STAKE,
+            time_to_reveal: TIME_TO_REVEAL,
+            market_authority: None,
+            unstake_delay_seconds: 0,
+            authorized_reader_pubkey: reader.public_key,
+            allow_closing_early: false,
+            earliness_cutoff_seconds: None,
+            scoring_config: ScoringConfig::Flat,
+            losing_stake_slash_bps: 0,
+            min_stake: MIN_STAKE,
+            max_stake: None,
+            planned_open_timestamp: None,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+
+    let market_token_account = market_token_address(&market, &mint, &spl_token::ID);
+    ctx.mint_to(&mint, &market_token_account, REWARD_AMOUNT).await;
+
+    for (option_index, name) in [(1, "Option A"), (2, "Option B")] {
+        ctx.process(
+            &[add_market_option_as_creator(AddMarketOptionAsCreatorParams {
+                creator: creator.pubkey(),
+                market,
+                option_index,
+                name: name.to_string(),
+            })],
+            &[&creator.keypair],
+        )
+        .await
+        .unwrap();
+    }
+
+    let open_timestamp = ctx.now().await + 10;
+    ctx.process(
+        &[open_market(OpenMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            open_timestamp,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.warp_to_timestamp(open_timestamp).await;
+
+    Setup {
+        ctx,
+        mint,
+        creator,
+        reader,
+        market,
+        open_timestamp,
+    }
+}
+
+/// New user with an ETA holding `WRAPPED_AMOUNT` wrapped tokens and one share account per id
+async fn staker(ctx: &mut TestContext, mint: Pubkey, market: Pubkey, share_account_ids: &[u32]) -> User {
+    let user = ctx.create_user();
+    let token_account = ctx.create_token_account(&user.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &token_account, WRAPPED_AMOUNT).await;
+
+    ctx.process(
+        &[init_encrypted_token_account(InitEncryptedTokenAccountParams {
+            signer: user.pubkey(),
+            token_mint: mint,
+            user_pubkey: user.x25519.public_key,
+            state_nonce: random_nonce(),
+        })],
+        &[&user.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.wrap_encrypted_tokens(&user, mint, WRAPPED_AMOUNT).await.unwrap();
+
+    for &share_account_id in share_account_ids {
+        ctx.process(
+            &[init_share_account(InitShareAccountParams {
+                signer: user.pubkey(),
+                market,
+                state_nonce: random_nonce(),
+                share_account_id,
+            })],
+            &[&user.keypair],
+        )
+        .await
+        .unwrap();
+    }
+    user
+}
+
+/// New delegate allowed to stake up to `spending_cap` from the owner's ETA until `expires_at`,
+/// with one share account of the owner per id
+async fn delegate(
+    ctx: &mut TestContext,
+    owner: &User,
+    mint: Pubkey,
+    market: Pubkey,
+    spending_cap: u64,
+    expires_at: u64,
+    share_account_ids: &[u32],
+) -> User {
+    let delegate = ctx.create_user();
+    let encrypted_token_account = encrypted_token_account_address(&mint, &owner.pubkey()).0;
+
+    ctx.process(
+        &[create_stake_delegation(CreateStakeDelegationParams {
+            owner: owner.pubkey(),
+            encrypted_token_account,
+            delegate: delegate.pubkey(),
+            spending_cap,
+            expires_at,
+            state_nonce: random_nonce(),
+        })],
+        &[&owner.keypair],
+    )
+    .await
+    .unwrap();
+
+    for &share_account_id in share_account_ids {
+        ctx.process(
+            &[init_share_account_as_delegate(InitShareAccountAsDelegateParams {
+                delegate: delegate.pubkey(),
+                owner: owner.pubkey(),
+                encrypted_token_account,
+                market,
+                state_nonce: random_nonce(),
+                share_account_id,
+            })],
+            &[&delegate.keypair],
+        )
+        .await
+        .unwrap();
+    }
+    delegate
+}
+
+async fn balance(ctx: &mut TestContext, user: &User, mint: Pubkey) -> u64 {
+    let eta = ctx
+        .get_account::<EncryptedTokenAccount>(encrypted_token_account_address(&mint, &user.pubkey()).0)
+        .await;
+    assert!(!eta.locked);
+    user.cipher.decrypt_balance(&eta).unwrap()
+}
+
+#[tokio::test]
+async fn market_lifecycle() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        market,
+        open_timestamp,
+    } = setup().await;
+
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    let bob = staker(&mut ctx, mint, market, &[0, 1]).await;
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+
+    // Stake
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+    ctx.stake(&bob, market, 0, 300, 2).await.unwrap();
+    ctx.stake(&bob, market, 1, 200, 1).await.unwrap();
+    assert_eq!(balance(&mut ctx, &alice, mint).await, 600);
+    assert_eq!(balance(&mut ctx, &bob, mint).await, 500);
+
+    let alice_share = share_account_address(&alice.pubkey(), &market, 0).0;
+    let share = ctx.get_account::<ShareAccount>(alice_share).await;
+    let staked_at = share.staked_at_timestamp.unwrap();
+    let state = alice.cipher.decrypt_share_state(&share).unwrap();
+    assert_eq!((state.amount, state.selected_option), (400, 1));
+    assert_eq!(state.weighted_staked_at, 400 * staked_at as u128);
+
+    // The authorized reader sees the same stake through the disclosure
+    let reader_cipher = Cipher::new(&reader.secret_key, &ctx.mxe.x25519_public_key()).unwrap();
+    let disclosed = reader_cipher.decrypt_share_state_disclosure(&share).unwrap();
+    assert_eq!(disclosed, state);
+
+    // Unstake
+    ctx.process(
+        &[unstake_early(UnstakeEarlyParams {
+            signer: bob.pubkey(),
+            market,
+            share_account_id: 1,
+        })],
+        &[&bob.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.do_unstake_early(&bob, market, 1).await.unwrap();
+    assert_eq!(balance(&mut ctx, &bob, mint).await, 700);
+    let bob_unstaked = share_account_address(&bob.pubkey(), &market, 1).0;
+    assert!(ctx
+        .get_account::<ShareAccount>(bob_unstaked)
+        .await
+        .unstaked_at_timestamp
+        .is_some());
+
+    // Resolve
+    let stake_end = open_timestamp + TIME_TO_STAKE;
+    ctx.warp_to_timestamp(stake_end).await;
+    ctx.process(
+        &[select_option(SelectOptionParams {
+            authority: creator.pubkey(),
+            market,
+            option_index: 1,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+
+    // Reveal
+    ctx.reveal_shares(&alice, market, 0).await.unwrap();
+    ctx.reveal_shares(&bob, market, 0).await.unwrap();
+    ctx.reveal_shares(&bob, market, 1).await.unwrap();
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+    // The unstaked share was refunded already and is not credited twice
+    assert_eq!(balance(&mut ctx, &bob, mint).await, WRAPPED_AMOUNT);
+
+    let share = ctx.get_account::<ShareAccount>(alice_share).await;
+    assert_eq!(share.revealed_amount, Some(400));
+    assert_eq!(share.revealed_option, Some(1));
+    assert_eq!(share.revealed_entry_timestamp, Some(staked_at));
+
+    // Tally
+    let payer = ctx.payer();
+    for (owner, share_account_id, option_index) in [(&alice, 0, 1), (&bob, 0, 2)] {
+        ctx.process(
+            &[increment_option_tally(IncrementOptionTallyParams {
+                signer: payer,
+                owner: owner.pubkey(),
+                market,
+                option_index,
+                share_account_id,
+            })],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+    let option = ctx
+        .get_account::<OpportunityMarketOption>(opportunity_market_option_address(&market, 1).0)
+        .await;
+    assert_eq!(option.total_shares, Some(400));
+
+    // Claim rewards and close
+    ctx.warp_to_timestamp(stake_end + TIME_TO_REVEAL).await;
+    for (owner, share_account_id, option_index) in [(&alice, 0, 1), (&bob, 0, 2), (&bob, 1, 1)] {
+        let owner_token_account = ctx.create_token_account(&owner.pubkey(), &mint).await;
+        ctx.process(
+            &[close_share_account(CloseShareAccountParams {
+                owner: owner.pubkey(),
+                market,
+                reward_mint: mint,
+                owner_token_account,
+                token_program: spl_token::ID,
+                option_index,
+                share_account_id,
+            })],
+            &[&owner.keypair],
+        )
+        .await
+        .unwrap();
+    }
+    let alice_token_account = ctx.create_token_account(&alice.pubkey(), &mint).await;
+    let bob_token_account = ctx.create_token_account(&bob.pubkey(), &mint).await;
+    // Alice holds the whole winning tally
+    assert_eq!(ctx.token_balance(alice_token_account).await, REWARD_AMOUNT);
+    assert_eq!(ctx.token_balance(bob_token_account).await, 0);
+
+    ctx.unwrap_encrypted_tokens(&alice, mint, WRAPPED_AMOUNT).await.unwrap();
+    assert_eq!(ctx.token_balance(alice_token_account).await, REWARD_AMOUNT + WRAPPED_AMOUNT);
+    assert_eq!(balance(&mut ctx, &alice, mint).await, 0);
+
+    for option_index in [1, 2] {
+        ctx.process(
+            &[close_market_option(CloseMarketOptionParams {
+                creator: creator.pubkey(),
+                market,
+                option_creator: creator.pubkey(),
+                option_index,
+            })],
+            &[&creator.keypair],
+        )
+        .await
+        .unwrap();
+    }
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    ctx.process(
+        &[close_market(CloseMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    assert!(!ctx.account_exists(market).await);
+}
+
+#[tokio::test]
+async fn expired_market_closes_with_share_accounts_left() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        market,
+        open_timestamp,
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    let bob = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+
+    // Bob never staked, he can close his share account without a reveal
+    let bob_token_account = ctx.create_token_account(&bob.pubkey(), &mint).await;
+    ctx.process(
+        &[close_share_account(CloseShareAccountParams {
+            owner: bob.pubkey(),
+            market,
+            reward_mint: mint,
+            owner_token_account: bob_token_account,
+            token_program: spl_token::ID,
+            option_index: 1,
+            share_account_id: 0,
+        })],
+        &[&bob.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.open_share_accounts, 1);
+
+    // Share accounts opened after staking could never be staked
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE + 1).await;
+    let init_share = init_share_account(InitShareAccountParams {
+        signer: bob.pubkey(),
+        market,
+        state_nonce: random_nonce(),
+        share_account_id: 1,
+    });
+    assert!(ctx.process(&[init_share], &[&bob.keypair]).await.is_err());
+
+    // Alice never reveals, her share account holds up closing until the market expires
+    let close_options = [1, 2].map(|option_index| {
+        close_market_option(CloseMarketOptionParams {
+            creator: creator.pubkey(),
+            market,
+            option_creator: creator.pubkey(),
+            option_index,
+        })
+    });
+    let reveal_end = open_timestamp + TIME_TO_STAKE + TIME_TO_REVEAL;
+    ctx.warp_to_timestamp(reveal_end).await;
+    assert!(ctx.process(&close_options[..1], &[&creator.keypair]).await.is_err());
+
+    ctx.warp_to_timestamp(reveal_end + 180 * 86_400).await;
+    ctx.process(&close_options, &[&creator.keypair]).await.unwrap();
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    ctx.process(
+        &[close_market(CloseMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    assert!(!ctx.account_exists(market).await);
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT);
+
+    // The option tallies stay behind, so alice's share account can't be used against a market
+    // re-created at the same address
+    assert!(ctx.account_exists(option_tallies_address(&market).0).await);
+    let recreate = create_market(CreateMarketParams {
+        creator: creator.pubkey(),
+        token_mint: mint,
+        reward_mint: mint,
+        token_program: spl_token::ID,
+        market_index: 0,
+        reward_amount: REWARD_AMOUNT,
+        time_to_stake: TIME_TO_STAKE,
+        time_to_reveal: TIME_TO_REVEAL,
+        market_authority: None,
+        unstake_delay_seconds: 0,
+        authorized_reader_pubkey: reader.public_key,
+        allow_closing_early: false,
+        earliness_cutoff_seconds: None,
+        scoring_config: ScoringConfig::Flat,
+        losing_stake_slash_bps: 0,
+        min_stake: MIN_STAKE,
+        max_stake: None,
+        planned_open_timestamp: None,
+    });
+    assert!(ctx.process(&[recreate], &[&creator.keypair]).await.is_err());
+}
+
+#[tokio::test]
+async fn cancelled_market_refunds_staked_shares_only() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0, 1]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+
+    // Cancelling is possible until the last second before stake end, when reveals start
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE - 1).await;
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    ctx.process(
+        &[cancel_market(CancelMarketParams {
+            authority: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+
+    ctx.do_unstake_early(&alice, market, 0).await.unwrap();
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+
+    // Share account 1 never held a stake, there is nothing to refund
+    assert!(ctx.do_unstake_early(&alice, market, 1).await.is_err());
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+}
+
+#[tokio::test]
+async fn stake_above_balance_is_rolled_back() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+
+    ctx.stake(&alice, market, 0, WRAPPED_AMOUNT + 1, 1).await.unwrap();
+
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(!share.locked);
+    assert!(share.staked_at_timestamp.is_none());
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+
+    // Nothing was staked, so there is nothing to reveal and nothing gets locked
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE).await;
+    assert!(ctx.reveal_shares(&alice, market, 0).await.is_err());
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(!share.locked);
+
+    let market = ctx.get_account::<OpportunityMarket>(market).await;
+    assert!(market.open_timestamp.is_some());
+    assert_eq!(market.pending_reveals, 0);
+}
+
+#[tokio::test]
+async fn stake_below_minimum_is_rolled_back() {
+    let Setup {
+        mut ctx, mint, market, ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+
+    ctx.stake(&alice, market, 0, MIN_STAKE - 1, 1).await.unwrap();
+
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(!share.locked);
+    assert!(share.staked_at_timestamp.is_none());
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+}
+
+#[tokio::test]
+async fn funded_market_is_activated_by_anyone_at_planned_time() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        ..
+    } = setup().await;
+    let now = ctx.now().await;
+    let create = |market_index: u64, planned_open_timestamp: Option<u64>| {
+        create_market(CreateMarketParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            market_index,
+            reward_amount: REWARD_AMOUNT,
+            time_to_stake: TIME_TO_STAKE,
+            time_to_reveal: TIME_TO_REVEAL,
+            market_authority: None,
+            unstake_delay_seconds: 0,
+            authorized_reader_pubkey: reader.public_key,
+            allow_closing_early: false,
+            earliness_cutoff_seconds: None,
+            scoring_config: ScoringConfig::Flat,
+            losing_stake_slash_bps: 0,
+            min_stake: MIN_STAKE,
+            max_stake: None,
+            planned_open_timestamp,
+        })
+    };
+    // The planned open time has to be in the future
+    assert!(ctx.process(&[create(1, Some(now))], &[&creator.keypair]).await.is_err());
+    ctx.process(
+        &[create(1, Some(now + 100)), create(2, Some(now + 100)), create(3, None)],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+
+    let cranker = ctx.create_user();
+    let other = ctx.create_user();
+    let activate = |signer: &User, market_index: u64| {
+        activate_market(ActivateMarketParams {
+            signer: signer.pubkey(),
+            market: opportunity_market_address(&creator.pubkey(), market_index).0,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+        })
+    };
+
+    // Not funded yet
+    assert!(ctx.process(&[activate(&creator, 1)], &[&creator.keypair]).await.is_err());
+
+    // A sponsor funds the markets without the creator
+    for market_index in [1, 2, 3] {
+        let market = opportunity_market_address(&creator.pubkey(), market_index).0;
+        let market_token_account = market_token_address(&market, &mint, &spl_token::ID);
+        ctx.mint_to(&mint, &market_token_account, REWARD_AMOUNT).await;
+    }
+
+    ctx.process(&[activate(&cranker, 1)], &[&cranker.keypair]).await.unwrap();
+    let market = ctx
+        .get_account::<OpportunityMarket>(opportunity_market_address(&creator.pubkey(), 1).0)
+        .await;
+    assert_eq!(market.open_timestamp, Some(now + 100));
+    assert!(ctx.process(&[activate(&other, 1)], &[&other.keypair]).await.is_err());
+
+    // Activated late, the market opens right away and keeps its full staking period
+    ctx.warp_to_timestamp(now + 500).await;
+    ctx.process(&[activate(&cranker, 2)], &[&cranker.keypair]).await.unwrap();
+    let market = ctx
+        .get_account::<OpportunityMarket>(opportunity_market_address(&creator.pubkey(), 2).0)
+        .await;
+    assert_eq!(market.open_timestamp, Some(now + 500));
+
+    // Markets without a planned open time are only opened by their creator
+    assert!(ctx.process(&[activate(&cranker, 3)], &[&cranker.keypair]).await.is_err());
+}
+
+#[tokio::test]
+async fn market_pays_rewards_in_its_reward_mint() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        ..
+    } = setup().await;
+    let reward_mint = ctx.create_mint(9).await;
+    let create = |market_index: u64, losing_stake_slash_bps: u16| {
+        create_market(CreateMarketParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint,
+            token_program: spl_token::ID,
+            market_index,
+            reward_amount: REWARD_AMOUNT,
+            time_to_stake: TIME_TO_STAKE,
+            time_to_reveal: TIME_TO_REVEAL,
+            market_authority: None,
+            unstake_delay_seconds: 0,
+            authorized_reader_pubkey: reader.public_key,
+            allow_closing_early: false,
+            earliness_cutoff_seconds: None,
+            scoring_config: ScoringConfig::Flat,
+            losing_stake_slash_bps,
+            min_stake: MIN_STAKE,
+            max_stake: None,
+            planned_open_timestamp: None,
+        })
+    };
+    // Slashed stake could not be paid out in a different reward mint
+    assert!(ctx.process(&[create(1, 500)], &[&creator.keypair]).await.is_err());
+    ctx.process(&[create(1, 0)], &[&creator.keypair]).await.unwrap();
+
+    let market = opportunity_market_address(&creator.pubkey(), 1).0;
+    let state = ctx.get_account::<OpportunityMarket>(market).await;
+    assert_eq!((state.mint, state.reward_mint), (mint, reward_mint));
+
+    let open = |reward_mint: Pubkey, open_timestamp: u64| {
+        open_market(OpenMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint,
+            token_program: spl_token::ID,
+            open_timestamp,
+        })
+    };
+    let open_timestamp = ctx.now().await + 10;
+    // Funding in the stake mint does not count towards the rewards
+    assert!(ctx.process(&[open(mint, open_timestamp)], &[&creator.keypair]).await.is_err());
+    let market_token_account = market_token_address(&market, &reward_mint, &spl_token::ID);
+    ctx.mint_to(&reward_mint, &market_token_account, REWARD_AMOUNT).await;
+    ctx.process(&[open(reward_mint, open_timestamp)], &[&creator.keypair]).await.unwrap();
+    assert_eq!(
+        ctx.get_account::<OpportunityMarket>(market).await.open_timestamp,
+        Some(open_timestamp)
+    );
+}
+
+#[tokio::test]
+async fn sponsors_are_refunded_when_market_is_unopened_or_cancelled() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        market,
+        ..
+    } = setup().await;
+    let sponsor = ctx.create_user();
+    let sponsor_token_account = ctx.create_token_account(&sponsor.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &sponsor_token_account, 1_000).await;
+
+    let sponsor_ix = |market: Pubkey, amount: u64| {
+        sponsor_market(SponsorMarketParams {
+            sponsor: sponsor.pubkey(),
+            market,
+            reward_mint: mint,
+            sponsor_token_account,
+            token_program: spl_token::ID,
+            amount,
+        })
+    };
+    let refund = |signer: &User, market: Pubkey| {
+        refund_sponsor(RefundSponsorParams {
+            signer: signer.pubkey(),
+            sponsor: sponsor.pubkey(),
+            market,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+        })
+    };
+
+    // Sponsoring an unopened market raises the funding it needs, refunding lowers it again
+    let unopened = opportunity_market_address(&creator.pubkey(), 1).0;
+    ctx.process(
+        &[create_market(CreateMarketParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            market_index: 1,
+            reward_amount: REWARD_AMOUNT,
+            time_to_stake: TIME_TO_STAKE,
+            time_to_reveal: TIME_TO_REVEAL,
+            market_authority: None,
+            unstake_delay_seconds: 0,
+            authorized_reader_pubkey: [0; 32],
+            allow_closing_early: false,
+            earliness_cutoff_seconds: None,
+            scoring_config: ScoringConfig::Flat,
+            losing_stake_slash_bps: 0,
+            min_stake: MIN_STAKE,
+            max_stake: None,
+            planned_open_timestamp: None,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.process(&[sponsor_ix(unopened, 100), sponsor_ix(unopened, 50)], &[&sponsor.keypair])
+        .await
+        .unwrap();
+    let account = ctx.get_account::<OpportunityMarket>(unopened).await;
+    assert_eq!(account.reward_amount, REWARD_AMOUNT + 150);
+    assert_eq!(account.total_sponsored, 150);
+    let sponsorship = ctx
+        .get_account::<MarketSponsor>(market_sponsor_address(&unopened, &sponsor.pubkey()).0)
+        .await;
+    assert_eq!(sponsorship.amount, 150);
+
+    let cranker = ctx.create_user();
+    ctx.process(&[refund(&cranker, unopened)], &[&cranker.keypair]).await.unwrap();
+    let account = ctx.get_account::<OpportunityMarket>(unopened).await;
+    assert_eq!(account.reward_amount, REWARD_AMOUNT);
+    assert_eq!(account.total_sponsored, 0);
+    assert_eq!(ctx.token_balance(sponsor_token_account).await, 1_000);
+    assert!(!ctx.account_exists(market_sponsor_address(&unopened, &sponsor.pubkey()).0).await);
+
+    // Sponsorships of an open market are locked in while it can still pay out
+    ctx.process(&[sponsor_ix(market, 200)], &[&sponsor.keypair]).await.unwrap();
+    let account = ctx.get_account::<OpportunityMarket>(market).await;
+    assert_eq!(account.reward_amount, REWARD_AMOUNT + 200);
+    assert!(ctx.process(&[refund(&cranker, market)], &[&cranker.keypair]).await.is_err());
+
+    // Cancelling refunds the creator's funding only, the sponsorship goes back to the sponsor
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    ctx.process(
+        &[cancel_market(CancelMarketParams {
+            authority: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT);
+    assert!(ctx.process(&[sponsor_ix(market, 100)], &[&sponsor.keypair]).await.is_err());
+
+    ctx.process(&[refund(&sponsor, market)], &[&sponsor.keypair]).await.unwrap();
+    assert_eq!(ctx.token_balance(sponsor_token_account).await, 1_000);
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.total_sponsored, 0);
+}
+
+#[tokio::test]
+async fn sponsors_are_refunded_when_no_winner_is_selected() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let sponsor = ctx.create_user();
+    let sponsor_token_account = ctx.create_token_account(&sponsor.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &sponsor_token_account, 1_000).await;
+    ctx.process(
+        &[sponsor_market(SponsorMarketParams {
+            sponsor: sponsor.pubkey(),
+            market,
+            reward_mint: mint,
+            sponsor_token_account,
+            token_program: spl_token::ID,
+            amount: 200,
+        })],
+        &[&sponsor.keypair],
+    )
+    .await
+    .unwrap();
+    let refund = |signer: &User| {
+        refund_sponsor(RefundSponsorParams {
+            signer: signer.pubkey(),
+            sponsor: sponsor.pubkey(),
+            market,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+        })
+    };
+
+    // A winner can still be selected until the reveal period is over
+    let reveal_end = open_timestamp + TIME_TO_STAKE + TIME_TO_REVEAL;
+    ctx.warp_to_timestamp(reveal_end - 1).await;
+    let cranker = ctx.create_user();
+    assert!(ctx.process(&[refund(&cranker)], &[&cranker.keypair]).await.is_err());
+
+    // After that the market can no longer pay out, and no winner can be selected anymore
+    ctx.warp_to_timestamp(reveal_end).await;
+    let select = select_option(SelectOptionParams {
+        authority: creator.pubkey(),
+        market,
+        option_index: 1,
+    });
+    assert!(ctx.process(&[select], &[&creator.keypair]).await.is_err());
+
+    // Reclaiming leaves the sponsorship in the market ATA
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    ctx.warp_to_timestamp(reveal_end + 86_400).await;
+    ctx.process(
+        &[reclaim_market_rewards(ReclaimMarketRewardsParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+            winning_options: vec![],
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT);
+
+    // The market can't be closed until the sponsor got their share back
+    let close_option = |option_index: u16| {
+        close_market_option(CloseMarketOptionParams {
+            creator: creator.pubkey(),
+            market,
+            option_creator: creator.pubkey(),
+            option_index,
+        })
+    };
+    assert!(ctx.process(&[close_option(1)], &[&creator.keypair]).await.is_err());
+
+    ctx.process(&[refund(&sponsor)], &[&sponsor.keypair]).await.unwrap();
+    assert_eq!(ctx.token_balance(sponsor_token_account).await, 1_000);
+    let account = ctx.get_account::<OpportunityMarket>(market).await;
+    assert_eq!(account.reward_amount, REWARD_AMOUNT);
+    assert_eq!(account.total_sponsored, 0);
+
+    ctx.process(&[close_option(1), close_option(2)], &[&creator.keypair])
+        .await
+        .unwrap();
+    ctx.process(
+        &[close_market(CloseMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT);
+    assert!(!ctx.account_exists(market).await);
+}
+
+#[tokio::test]
+async fn paid_out_sponsorships_are_closed_before_the_market() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let sponsor = ctx.create_user();
+    let sponsor_token_account = ctx.create_token_account(&sponsor.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &sponsor_token_account, 1_000).await;
+    ctx.process(
+        &[sponsor_market(SponsorMarketParams {
+            sponsor: sponsor.pubkey(),
+            market,
+            reward_mint: mint,
+            sponsor_token_account,
+            token_program: spl_token::ID,
+            amount: 200,
+        })],
+        &[&sponsor.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.open_sponsorships, 1);
+
+    let stake_end = open_timestamp + TIME_TO_STAKE;
+    ctx.warp_to_timestamp(stake_end).await;
+    ctx.process(
+        &[select_option(SelectOptionParams {
+            authority: creator.pubkey(),
+            market,
+            option_index: 1,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    let close_sponsor = |signer: &User| {
+        close_market_sponsor(CloseMarketSponsorParams {
+            signer: signer.pubkey(),
+            sponsor: sponsor.pubkey(),
+            market,
+        })
+    };
+
+    // The sponsorship is part of the reward until the reveal period is over
+    let cranker = ctx.create_user();
+    assert!(ctx.process(&[close_sponsor(&cranker)], &[&cranker.keypair]).await.is_err());
+    ctx.warp_to_timestamp(stake_end + TIME_TO_REVEAL).await;
+
+    // The market can't be closed while the sponsorship account is open
+    let close_option = |option_index: u16| {
+        close_market_option(CloseMarketOptionParams {
+            creator: creator.pubkey(),
+            market,
+            option_creator: creator.pubkey(),
+            option_index,
+        })
+    };
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    let close = close_market(CloseMarketParams {
+        creator: creator.pubkey(),
+        market,
+        reward_mint: mint,
+        creator_token_account,
+        token_program: spl_token::ID,
+    });
+    assert!(ctx
+        .process(&[close_option(1), close_option(2), close.clone()], &[&creator.keypair])
+        .await
+        .is_err());
+
+    // Anyone can close it, the rent goes to the sponsor
+    let sponsor_account = market_sponsor_address(&market, &sponsor.pubkey()).0;
+    ctx.process(&[close_sponsor(&sponsor)], &[&sponsor.keypair]).await.unwrap();
+    assert!(!ctx.account_exists(sponsor_account).await);
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.open_sponsorships, 0);
+
+    ctx.process(&[close_option(1), close_option(2)], &[&creator.keypair])
+        .await
+        .unwrap();
+    ctx.process(&[close], &[&creator.keypair]).await.unwrap();
+    // Nobody staked, so the whole reward including the sponsorship goes back to the creator
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT + 200);
+    assert!(!ctx.account_exists(market).await);
+}
+
+#[tokio::test]
+async fn markets_created_from_template_form_a_series() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        ..
+    } = setup().await;
+    let params = MarketParams {
+        reward_amount: REWARD_AMOUNT,
+        time_to_stake: TIME_TO_STAKE,
+        time_to_reveal: TIME_TO_REVEAL,
+        market_authority: None,
+        unstake_delay_seconds: 60,
+        authorized_reader_pubkey: reader.public_key,
+        allow_closing_early: true,
+        earliness_cutoff_seconds: None,
+        scoring_config: ScoringConfig::Linear,
+        losing_stake_slash_bps: 500,
+        min_stake: MIN_STAKE,
+        max_stake: Some(500),
+    };
+    ctx.process(
+        &[create_market_template(CreateMarketTemplateParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint: mint,
+            template_id: 0,
+            params,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    let template = market_template_address(&creator.pubkey(), 0).0;
+
+    let from_template = |creator: &User, series_index: u64| {
+        create_market_from_template(CreateMarketFromTemplateParams {
+            creator: creator.pubkey(),
+            template_id: 0,
+            token_mint: mint,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            series_index,
+            planned_open_timestamp: None,
+        })
+    };
+    ctx.process(&[from_template(&creator, 0)], &[&creator.keypair]).await.unwrap();
+    ctx.process(&[from_template(&creator, 1)], &[&creator.keypair]).await.unwrap();
+
+    // The market address follows the series, a stale series index no longer matches it
+    assert!(ctx.process(&[from_template(&creator, 0)], &[&creator.keypair]).await.is_err());
+
+    for series_index in [0, 1] {
+        let market = ctx
+            .get_account::<OpportunityMarket>(template_market_address(&template, series_index).0)
+            .await;
+        assert_eq!(market.template, Some(template));
+        assert_eq!(market.series_index, Some(series_index));
+        assert_eq!(market.index, series_index);
+        assert_eq!(market.mint, mint);
+        assert_eq!(market.reward_amount, REWARD_AMOUNT);
+        assert_eq!(market.unstake_delay_seconds, 60);
+        assert_eq!(market.authorized_reader_pubkey, reader.public_key);
+        assert!(market.allow_closing_early);
+        assert_eq!(market.scoring_config, ScoringConfig::Linear);
+        assert_eq!(market.losing_stake_slash_bps, 500);
+        assert_eq!((market.min_stake, market.max_stake), (MIN_STAKE, Some(500)));
+        assert_eq!(market.earliness_cutoff_seconds, TIME_TO_STAKE);
+        assert!(market.open_timestamp.is_none());
+    }
+    let template_state = ctx.get_account::<MarketTemplate>(template).await;
+    assert_eq!(template_state.next_series_index, 2);
+
+    // Markets created directly are not part of a series
+    let market = ctx
+        .get_account::<OpportunityMarket>(opportunity_market_address(&creator.pubkey(), 0).0)
+        .await;
+    assert_eq!((market.template, market.series_index), (None, None));
+
+    // Markets of a series sign with their template-derived seeds, e.g. to refund a sponsor
+    let first = template_market_address(&template, 0).0;
+    let sponsor = ctx.create_user();
+    let sponsor_token_account = ctx.create_token_account(&sponsor.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &sponsor_token_account, 100).await;
+    ctx.process(
+        &[sponsor_market(SponsorMarketParams {
+            sponsor: sponsor.pubkey(),
+            market: first,
+            reward_mint: mint,
+            sponsor_token_account,
+            token_program: spl_token::ID,
+            amount: 100,
+        })],
+        &[&sponsor.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.process(
+        &[refund_sponsor(RefundSponsorParams {
+            signer: sponsor.pubkey(),
+            sponsor: sponsor.pubkey(),
+            market: first,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+        })],
+        &[&sponsor.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.token_balance(sponsor_token_account).await, 100);
+
+    // Only the template's creator can use it
+    let mallory = ctx.create_user();
+    assert!(ctx.process(&[from_template(&mallory, 2)], &[&mallory.keypair]).await.is_err());
+}
+
+#[tokio::test]
+async fn delegate_stakes_within_spending_cap() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[]).await;
+    let expires_at = open_timestamp + TIME_TO_STAKE;
+    let bob = delegate(&mut ctx, &alice, mint, market, 500, expires_at, &[0, 1]).await;
+
+    ctx.stake_as_delegate(&bob, alice.pubkey(), market, 0, 300, 1).await.unwrap();
+
+    // The shares belong to Alice and are paid from her ETA
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(!share.locked);
+    assert_eq!(share.owner, alice.pubkey());
+    let shares = alice.cipher.decrypt_share_state(&share).unwrap();
+    assert_eq!((shares.amount, shares.selected_option), (300, 1));
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT - 300);
+
+    let alice_eta = encrypted_token_account_address(&mint, &alice.pubkey()).0;
+    let delegation_address = stake_delegation_address(&alice_eta, &bob.pubkey()).0;
+    let delegation = ctx.get_account::<StakeDelegation>(delegation_address).await;
+    assert!(!delegation.locked);
+    assert_eq!(alice.cipher.decrypt_delegation_spent(&delegation).unwrap(), 300);
+
+    // Only 200 of the cap is left
+    ctx.stake_as_delegate(&bob, alice.pubkey(), market, 1, 300, 2).await.unwrap();
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 1).0)
+        .await;
+    assert!(!share.locked);
+    assert!(share.staked_at_timestamp.is_none());
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT - 300);
+
+    ctx.stake_as_delegate(&bob, alice.pubkey(), market, 1, 200, 2).await.unwrap();
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT - 500);
+    let delegation = ctx.get_account::<StakeDelegation>(delegation_address).await;
+    assert_eq!(alice.cipher.decrypt_delegation_spent(&delegation).unwrap(), 500);
+
+    // Only the owner can revoke
+    let revoke = |owner: &User| {
+        revoke_stake_delegation(RevokeStakeDelegationParams {
+            owner: owner.pubkey(),
+            encrypted_token_account: alice_eta,
+            delegate: bob.pubkey(),
+        })
+    };
+    assert!(ctx.process(&[revoke(&bob)], &[&bob.keypair]).await.is_err());
+    ctx.process(&[revoke(&alice)], &[&alice.keypair]).await.unwrap();
+    assert!(!ctx.account_exists(delegation_address).await);
+}
+
+#[tokio::test]
+async fn expired_delegation_cannot_stake() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[]).await;
+    let expires_at = open_timestamp + 100;
+    let bob = delegate(&mut ctx, &alice, mint, market, 500, expires_at, &[0]).await;
+
+    ctx.warp_to_timestamp(expires_at).await;
+    assert!(ctx.stake_as_delegate(&bob, alice.pubkey(), market, 0, 300, 1).await.is_err());
+
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(share.staked_at_timestamp.is_none());
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+}
+
+#[tokio::test]
+async fn cranker_reveals_into_owner_regular_eta() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE).await;
+
+    // Anyone can open an ephemeral ETA for alice, but a cranker can't send her refund there
+    let payer = ctx.payer();
+    ctx.process(
+        &[init_ephemeral_encrypted_token_account(InitEphemeralEncryptedTokenAccountParams {
+            signer: payer,
+            owner: alice.pubkey(),
+            token_mint: mint,
+            index: 1,
+            state_nonce: random_nonce(),
+        })],
+        &[],
+    )
+    .await
+    .unwrap();
+    let config = ctx.next_arcium_config();
+    let result = ctx
+        .process(
+            &[reveal_shares(
+                RevealSharesParams {
+                    signer: payer,
+                    owner: alice.pubkey(),
+                    market,
+                    user_eta: encrypted_token_account_address_with_index(&mint, &alice.pubkey(), 1).0,
+                    share_account_id: 0,
+                    token_mint: mint,
+                    reward_mint: mint,
+                    token_program: spl_token::ID,
+                    tally_option_index: None,
+                },
+                config,
+            )],
+            &[],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // The harness reveals as the payer, crediting alice's regular ETA
+    ctx.reveal_shares(&alice, market, 0).await.unwrap();
+    assert_eq!(balance(&mut ctx, &alice, mint).await, WRAPPED_AMOUNT);
+}
+
+#[tokio::test]
+async fn reveal_tallies_passed_option() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    let bob = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+    ctx.stake(&bob, market, 0, 300, 2).await.unwrap();
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE).await;
+
+    ctx.reveal_and_tally_shares(&alice, market, 0, Some(1)).await.unwrap();
+    // Bob revealed option 2, so passing option 1 only reveals
+    ctx.reveal_and_tally_shares(&bob, market, 0, Some(1)).await.unwrap();
+
+    let alice_share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert!(alice_share.total_incremented);
+    let bob_share = ctx
+        .get_account::<ShareAccount>(share_account_address(&bob.pubkey(), &market, 0).0)
+        .await;
+    assert_eq!(bob_share.revealed_option, Some(2));
+    assert!(!bob_share.total_incremented);
+
+    let option = ctx
+        .get_account::<OpportunityMarketOption>(opportunity_market_option_address(&market, 1).0)
+        .await;
+    assert_eq!(option.total_shares, Some(400));
+    assert_eq!(balance(&mut ctx, &bob, mint).await, WRAPPED_AMOUNT);
+}
+
+#[tokio::test]
+async fn reveal_leaves_share_untallied_when_score_overflows() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let whale = staker(&mut ctx, mint, market, &[0]).await;
+    let amount = u64::MAX / 2;
+    let token_account = ctx.create_token_account(&whale.pubkey(), &mint).await;
+    ctx.mint_to(&mint, &token_account, amount).await;
+    ctx.wrap_encrypted_tokens(&whale, mint, amount).await.unwrap();
+    ctx.stake(&whale, market, 0, amount, 1).await.unwrap();
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE).await;
+
+    // The score of the whole stake held for the whole period doesn't fit a u64, the share is
+    // still revealed and refunded
+    ctx.reveal_and_tally_shares(&whale, market, 0, Some(1)).await.unwrap();
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&whale.pubkey(), &market, 0).0)
+        .await;
+    assert!(!share.locked);
+    assert_eq!(share.revealed_amount, Some(amount));
+    assert!(!share.total_incremented);
+    let option = ctx
+        .get_account::<OpportunityMarketOption>(opportunity_market_option_address(&market, 1).0)
+        .await;
+    assert_eq!(option.total_shares, None);
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.pending_reveals, 0);
+    assert_eq!(balance(&mut ctx, &whale, mint).await, WRAPPED_AMOUNT + amount);
+}
+
+#[tokio::test]
+async fn reveal_after_reveal_period_is_rejected() {
+    let Setup {
+        mut ctx,
+        mint,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+
+    // Slashed stake revealed now would grow the pool winners are already claiming from
+    ctx.warp_to_timestamp(open_timestamp + TIME_TO_STAKE + TIME_TO_REVEAL).await;
+    assert!(ctx.reveal_shares(&alice, market, 0).await.is_err());
+    let share = ctx
+        .get_account::<ShareAccount>(share_account_address(&alice.pubkey(), &market, 0).0)
+        .await;
+    assert_eq!(share.revealed_amount, None);
+}
+
+#[tokio::test]
+async fn reclaim_leaves_tallied_winners_their_slice() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        market,
+        open_timestamp,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+
+    // Option 2 wins half the reward but nobody backed it
+    let stake_end = open_timestamp + TIME_TO_STAKE;
+    ctx.warp_to_timestamp(stake_end).await;
+    ctx.process(
+        &[select_winning_options(SelectWinningOptionsParams {
+            authority: creator.pubkey(),
+            market,
+            winning_options: vec![
+                WinningOption { option_index: 1, weight: 1 },
+                WinningOption { option_index: 2, weight: 1 },
+            ],
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.reveal_and_tally_shares(&alice, market, 0, Some(1)).await.unwrap();
+
+    let creator_token_account = ctx.create_token_account(&creator.pubkey(), &mint).await;
+    let reclaim = |winning_options: Vec<u16>| {
+        reclaim_market_rewards(ReclaimMarketRewardsParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            creator_token_account,
+            token_program: spl_token::ID,
+            winning_options,
+        })
+    };
+    ctx.warp_to_timestamp(stake_end + TIME_TO_REVEAL + 86_400).await;
+    // Every winning option has to be passed
+    assert!(ctx.process(&[reclaim(vec![1])], &[&creator.keypair]).await.is_err());
+    assert!(ctx.process(&[reclaim(vec![2, 1])], &[&creator.keypair]).await.is_err());
+    ctx.process(&[reclaim(vec![1, 2])], &[&creator.keypair]).await.unwrap();
+    assert_eq!(ctx.token_balance(creator_token_account).await, REWARD_AMOUNT / 2);
+
+    // Alice still gets option 1's slice
+    let alice_token_account = ctx.create_token_account(&alice.pubkey(), &mint).await;
+    ctx.process(
+        &[close_share_account(CloseShareAccountParams {
+            owner: alice.pubkey(),
+            market,
+            reward_mint: mint,
+            owner_token_account: alice_token_account,
+            token_program: spl_token::ID,
+            option_index: 1,
+            share_account_id: 0,
+        })],
+        &[&alice.keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.token_balance(alice_token_account).await, REWARD_AMOUNT / 2);
+}
+
+#[tokio::test]
+async fn pending_reveals_hold_up_claims_until_they_time_out() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        ..
+    } = setup().await;
+    let market = opportunity_market_address(&creator.pubkey(), 1).0;
+    ctx.process(
+        &[create_market(CreateMarketParams {
+            creator: creator.pubkey(),
+            token_mint: mint,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            market_index: 1,
+            reward_amount: REWARD_AMOUNT,
+            time_to_stake: TIME_TO_STAKE,
+            time_to_reveal: TIME_TO_REVEAL,
+            market_authority: None,
+            unstake_delay_seconds: 0,
+            authorized_reader_pubkey: reader.public_key,
+            allow_closing_early: false,
+            earliness_cutoff_seconds: None,
+            scoring_config: ScoringConfig::Flat,
+            losing_stake_slash_bps: 500,
+            min_stake: MIN_STAKE,
+            max_stake: None,
+            planned_open_timestamp: None,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.mint_to(&mint, &market_token_address(&market, &mint, &spl_token::ID), REWARD_AMOUNT)
+        .await;
+    for (option_index, name) in [(1, "Option A"), (2, "Option B")] {
+        ctx.process(
+            &[add_market_option_as_creator(AddMarketOptionAsCreatorParams {
+                creator: creator.pubkey(),
+                market,
+                option_index,
+                name: name.to_string(),
+            })],
+            &[&creator.keypair],
+        )
+        .await
+        .unwrap();
+    }
+    let open_timestamp = ctx.now().await + 10;
+    ctx.process(
+        &[open_market(OpenMarketParams {
+            creator: creator.pubkey(),
+            market,
+            reward_mint: mint,
+            token_program: spl_token::ID,
+            open_timestamp,
+        })],
+        &[&creator.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.warp_to_timestamp(open_timestamp).await;
+
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    let carol = staker(&mut ctx, mint, market, &[0]).await;
+    let bob = staker(&mut ctx, mint, market, &[0]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+    ctx.stake(&carol, market, 0, 400, 1).await.unwrap();
+    ctx.stake(&bob, market, 0, 400, 2).await.unwrap();
+
+    let stake_end = open_timestamp + TIME_TO_STAKE;
+    ctx.warp_to_timestamp(stake_end).await;
+    let select = |option_index: u16| {
+        select_option(SelectOptionParams {
+            authority: creator.pubkey(),
+            market,
+            option_index,
+        })
+    };
+    ctx.process(&[select(1)], &[&creator.keypair]).await.unwrap();
+    // Reveals are slashed against the winner, it can't be swapped out afterwards
+    assert!(ctx.process(&[select(2)], &[&creator.keypair]).await.is_err());
+
+    ctx.reveal_and_tally_shares(&alice, market, 0, Some(1)).await.unwrap();
+    ctx.reveal_and_tally_shares(&carol, market, 0, Some(1)).await.unwrap();
+
+    // Bob's reveal is queued but never called back
+    let config = ctx.next_arcium_config();
+    let payer = ctx.payer();
+    ctx.process(
+        &[reveal_shares(
+            RevealSharesParams {
+                signer: payer,
+                owner: bob.pubkey(),
+                market,
+                user_eta: encrypted_token_account_address(&mint, &bob.pubkey()).0,
+                share_account_id: 0,
+                token_mint: mint,
+                reward_mint: mint,
+                token_program: spl_token::ID,
+                tally_option_index: None,
+            },
+            config,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(ctx.get_account::<OpportunityMarket>(market).await.pending_reveals, 1);
+
+    let close = |owner: &User, owner_token_account: Pubkey| {
+        close_share_account(CloseShareAccountParams {
+            owner: owner.pubkey(),
+            market,
+            reward_mint: mint,
+            owner_token_account,
+            token_program: spl_token::ID,
+            option_index: 1,
+            share_account_id: 0,
+        })
+    };
+    let alice_token_account = ctx.create_token_account(&alice.pubkey(), &mint).await;
+    let carol_token_account = ctx.create_token_account(&carol.pubkey(), &mint).await;
+    let reveal_end = stake_end + TIME_TO_REVEAL;
+    ctx.warp_to_timestamp(reveal_end).await;
+    assert!(ctx
+        .process(&[close(&carol, carol_token_account)], &[&carol.keypair])
+        .await
+        .is_err());
+
+    // An hour after reveal end the pending reveal no longer holds up the winners
+    ctx.warp_to_timestamp(reveal_end + 3_600).await;
+    ctx.process(&[close(&alice, alice_token_account)], &[&alice.keypair])
+        .await
+        .unwrap();
+    assert_eq!(ctx.token_balance(alice_token_account).await, REWARD_AMOUNT / 2);
+}
+
+#[tokio::test]
+async fn running_tallies_are_disclosed_to_reader() {
+    let Setup {
+        mut ctx,
+        mint,
+        creator,
+        reader,
+        market,
+        ..
+    } = setup().await;
+    let alice = staker(&mut ctx, mint, market, &[0]).await;
+    let bob = staker(&mut ctx, mint, market, &[0, 1]).await;
+    ctx.stake(&alice, market, 0, 400, 1).await.unwrap();
+    ctx.stake(&bob, market, 0, 300, 2).await.unwrap();
+    ctx.stake(&bob, market, 1, 100, 1).await.unwrap();
+
+    // Stakes only flag their share account, anyone can then move them into the tallies
+    let share_account = share_account_address(&bob.pubkey(), &market, 1).0;
+    assert!(ctx.get_account::<ShareAccount>(share_account).await.tally_pending);
+    ctx.sync_option_tally(alice.pubkey(), market, 0).await.unwrap();
+    ctx.sync_option_tally(bob.pubkey(), market, 0).await.unwrap();
+    ctx.sync_option_tally(bob.pubkey(), market, 1).await.unwrap();
+    assert!(!ctx.get_account::<ShareAccount>(share_account).await.tally_pending);
+    assert!(ctx.sync_option_tally(bob.pubkey(), market, 1).await.is_err());
+
+    let option_tallies = ctx.get_account::<OptionTallies>(option_tallies_address(&market).0).await;
+    assert_eq!(ctx.mxe.option_tallies(&option_tallies).amounts, [500, 300, 0, 0, 0, 0, 0, 0]);
+
+    // Bob pulls his second stake before the staking period ends
+    ctx.process(
+        &[unstake_early(UnstakeEarlyParams {
+            signer: bob.pubkey(),
+            market,
+            share_account_id: 1,
+        })],
+        &[&bob.keypair],
+    )
+    .await
+    .unwrap();
+    ctx.do_unstake_early(&bob, market, 1).await.unwrap();
+    ctx.sync_option_tally(bob.pubkey(), market, 1).await.unwrap();
+
+    // Only the creator or market authority can disclose
+    assert!(ctx.disclose_option_tallies(&alice, market).await.is_err());
+    ctx.disclose_option_tallies(&creator, market).await.unwrap();
+
+    let tallies = ctx.get_account::<OptionTallies>(option_tallies_address(&market).0).await;
+    assert!(!tallies.locked);
+    assert!(tallies.disclosed_at_timestamp.is_some());
+    let reader_cipher = Cipher::new(&reader.secret_key, &ctx.mxe.x25519_public_key()).unwrap();
+    let amounts = reader_cipher.decrypt_option_tallies_disclosure(&tallies).unwrap();
+    assert_eq!(amounts, [400, 300, 0, 0, 0, 0, 0, 0]);
+}
+
+#[tokio::test]
+async fn markets_are_capped_at_tallied_options() {
+    let Setup {
+        mut ctx,
+        creator,
+        market,
+        ..
+    } = setup().await;
+
+    let add_option = |option_index: u16| {
+        add_market_option_as_creator(AddMarketOptionAsCreatorParams {
+            creator: creator.pubkey(),
+            market,
+            option_index,
+            name: format!("Option {option_index}"),
+        })
+    };
+
+    // Setup already added options 1 and 2
+    for option_index in 3..=MAX_TALLIED_OPTIONS as u16 {
+        ctx.process(&[add_option(option_index)], &[&creator.keypair]).await.unwrap();
+    }
+    let next_option = MAX_TALLIED_OPTIONS as u16 + 1;
+    assert!(ctx.process(&[add_option(next_option)], &[&creator.keypair]).await.is_err());
+
+    let market_state = ctx.get_account::<OpportunityMarket>(market).await;
+    assert_eq!(market_state.total_options as usize, MAX_TALLIED_OPTIONS);
+}
@@ -56,4 +56,60 @@ pub enum ErrorCode {
     UnstakeNotInitiated,
     #[msg("Market cannot be closed before stake period ends")]
     ClosingEarlyNotAllowed,
+    #[msg("Earliness cutoff must be non-zero and at most one year")]
+    InvalidEarlinessCutoff,
+    #[msg("Invalid scoring configuration")]
+    InvalidScoringConfig,
+    #[msg("Reward reclaim grace period has not passed yet")]
+    ReclaimGracePeriodNotOver,
+    #[msg("Market rewards already reclaimed")]
+    RewardsAlreadyReclaimed,
+    #[msg("Market has been cancelled")]
+    MarketCancelled,
+    #[msg("Market can only be cancelled before the stake period ends")]
+    CancellationWindowClosed,
+    #[msg("Market still has open share accounts")]
+    ShareAccountsStillOpen,
+    #[msg("Market still has open options")]
+    OptionsStillOpen,
+    #[msg("Winning options must be unique, non-empty and have non-zero weights")]
+    InvalidWinningOptions,
+    #[msg("Protocol fee must be at most 1000 basis points")]
+    InvalidProtocolFee,
+    #[msg("Market still holds unclaimed protocol fees")]
+    ProtocolFeesUnclaimed,
+    #[msg("Losing stake slash must be at most 10000 basis points")]
+    InvalidSlashBps,
+    #[msg("Minimum stake must not exceed maximum stake")]
+    InvalidStakeLimits,
+    #[msg("Stake delegation has expired")]
+    DelegationExpired,
+    #[msg("Market has no planned open time")]
+    NoPlannedOpenTime,
+    #[msg("Sponsorship amount must be greater than zero")]
+    ZeroSponsorAmount,
+    #[msg("Markets can only be sponsored before the stake period ends")]
+    SponsorshipWindowClosed,
+    #[msg("Sponsorships can only be refunded before the market opens, after it is cancelled or once it ended without a winner")]
+    SponsorshipNotRefundable,
+    #[msg("Market still holds sponsorships that have not been refunded")]
+    SponsorshipsNotRefunded,
+    #[msg("Losing stake can only be slashed when rewards are paid in the staked token")]
+    SlashRequiresStakeMintRewards,
+    #[msg("Market token account is required to slash losing stake")]
+    MarketTokenAccountRequired,
+    #[msg("Reward reclaim grace period must be at least one day")]
+    InvalidReclaimGracePeriod,
+    #[msg("Recipient accepts no incoming transfer until its cooldown has passed")]
+    IncomingTransferCooldown,
+    #[msg("Reveals that may still slash stake are pending")]
+    RevealsPending,
+    #[msg("Share account stake is already counted in the option tallies")]
+    TallyUpToDate,
+    #[msg("Market already has the maximum number of options")]
+    TooManyOptions,
+    #[msg("Sponsorships can only be closed once a winner is selected and the reveal period is over")]
+    SponsorshipNotSettled,
+    #[msg("Market still has sponsorship accounts that have not been refunded or closed")]
+    SponsorshipsStillOpen,
 }
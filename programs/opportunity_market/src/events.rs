@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{MarketParams, ScoringConfig, WinningOption, MAX_TALLIED_OPTIONS};
+
 /// Emits an event with `timestamp` automatically set from `Clock::get()`.
 macro_rules! emit_ts {
     ($event:ident { $($field:ident : $value:expr),* $(,)? }) => {{
@@ -24,6 +26,51 @@ pub struct MarketCreatedEvent {
     pub authorized_reader_pubkey: [u8; 32],
     pub unstake_delay_seconds: u64,
     pub allow_closing_early: bool,
+    pub earliness_cutoff_seconds: u64,
+    pub scoring_config: ScoringConfig,
+    pub losing_stake_slash_bps: u16,
+    pub min_stake: u64,
+    pub max_stake: Option<u64>,
+    pub planned_open_timestamp: Option<u64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketTemplateCreatedEvent {
+    pub template: Pubkey,
+    pub creator: Pubkey,
+    pub template_id: u64,
+    pub mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub params: MarketParams,
+    pub timestamp: i64,
+}
+
+// Emitted next to `MarketCreatedEvent` for markets created from a template
+#[event]
+pub struct MarketCreatedFromTemplateEvent {
+    pub market: Pubkey,
+    pub template: Pubkey,
+    pub series_index: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeDelegationCreatedEvent {
+    pub delegation: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub spending_cap: u64,
+    pub expires_at: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeDelegationRevokedEvent {
+    pub delegation: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
     pub timestamp: i64,
 }
 
@@ -47,15 +94,42 @@ pub struct EncryptedTokensWrappedEvent {
     pub timestamp: i64,
 }
 
+// Emitted next to `StakedEvent` when a delegate staked from the owner's ETA
+#[event]
+pub struct DelegatedStakeEvent {
+    pub delegation: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub share_account: Pubkey,
+    pub encrypted_spent: [u8; 32],
+    pub spent_nonce: u128,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct StakedEvent {
     pub user: Pubkey,
     pub market: Pubkey,
     pub encrypted_token_account: Pubkey,
     pub share_account: Pubkey,
-    pub share_encrypted_state: [[u8; 32]; 2], // share amount and option
+    pub share_encrypted_state: [[u8; 32]; 3], // share amount, option and weighted stake time
     pub share_state_nonce: u128,
-    pub share_encrypted_state_disclosure: [[u8; 32]; 2],
+    pub share_encrypted_state_disclosure: [[u8; 32]; 3],
+    pub share_state_disclosure_nonce: u128,
+    pub encrypted_eta_balance: [u8; 32],
+    pub eta_balance_nonce: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeIncreasedEvent {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub share_account: Pubkey,
+    pub share_encrypted_state: [[u8; 32]; 3],
+    pub share_state_nonce: u128,
+    pub share_encrypted_state_disclosure: [[u8; 32]; 3],
     pub share_state_disclosure_nonce: u128,
     pub encrypted_eta_balance: [u8; 32],
     pub eta_balance_nonce: u128,
@@ -70,6 +144,8 @@ pub struct StakeRevealedEvent {
     pub share_account: Pubkey,
     pub shares_amount: u64,
     pub selected_option: u16,
+    pub entry_timestamp: u64,
+    pub slashed_amount: u64,
     pub timestamp: i64,
 }
 
@@ -82,15 +158,55 @@ pub struct UnstakedEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct EncryptedTokensWrappedError {
+    pub user: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub pending_deposit: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct StakeRevealedError {
     pub user: Pubkey,
     pub timestamp: i64,
 }
 
+/// Why a stake was rolled back
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StakeErrorReason {
+    /// The computation output failed verification
+    ComputationFailed,
+    InsufficientBalance,
+    /// Below the market's `min_stake`, or the central state's `min_deposit` when adding an option
+    BelowMinimum,
+    /// Above the market's `max_stake`
+    AboveMaximum,
+    /// Above what is left of a stake delegation's `spending_cap`
+    AboveDelegationCap,
+    /// The staked amount, or the running tally of its option, would overflow u64
+    Overflow,
+}
+
+impl StakeErrorReason {
+    /// Reason for the error code revealed by the staking circuits, `None` if the stake went through
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => None,
+            1 => Some(Self::InsufficientBalance),
+            2 => Some(Self::BelowMinimum),
+            3 => Some(Self::AboveMaximum),
+            4 => Some(Self::AboveDelegationCap),
+            5 => Some(Self::Overflow),
+            _ => Some(Self::ComputationFailed),
+        }
+    }
+}
+
 #[event]
 pub struct StakedError {
     pub user: Pubkey,
+    pub reason: StakeErrorReason,
     pub timestamp: i64,
 }
 
@@ -106,6 +222,26 @@ pub struct EncryptedTokensUnwrappedError {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct EncryptedTokensTransferredError {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EncryptedTokensTransferredEvent {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub sender_encrypted_token_account: Pubkey,
+    pub recipient_encrypted_token_account: Pubkey,
+    pub sender_encrypted_new_balance: [u8; 32],
+    pub sender_nonce: u128,
+    pub recipient_encrypted_new_balance: [u8; 32],
+    pub recipient_nonce: u128,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct EncryptedTokensUnwrappedEvent {
     pub user: Pubkey,
@@ -124,11 +260,63 @@ pub struct MarketOpenedEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct MarketSponsoredEvent {
+    pub market: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub sponsor_total: u64,
+    pub reward_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SponsorRefundedEvent {
+    pub market: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub reward_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketSponsorClosedEvent {
+    pub market: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketClosedEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketOptionClosedEvent {
+    pub market: Pubkey,
+    pub option: Pubkey,
+    pub index: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketCancelledEvent {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub refunded_amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct OptionSelectedEvent {
     pub market: Pubkey,
     pub authority: Pubkey,
     pub selected_option: u16,
+    pub winning_options: Vec<WinningOption>,
     pub timestamp: i64,
 }
 
@@ -142,6 +330,39 @@ pub struct RewardClaimedEvent {
     pub timestamp: i64,
 }
 
+// Share account of a cancelled market closed without a reward
+#[event]
+pub struct ShareAccountClosedEvent {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub share_account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeeCollectedEvent {
+    pub market: Pubkey,
+    pub share_account: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeesClaimedEvent {
+    pub market: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketRewardsReclaimedEvent {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TallyIncrementedEvent {
     pub owner: Pubkey,
@@ -153,6 +374,15 @@ pub struct TallyIncrementedEvent {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct TallyIncrementedError {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub share_account: Pubkey,
+    pub option: u16,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct RevealPeriodExtendedEvent {
     pub market: Pubkey,
@@ -218,3 +448,31 @@ pub struct ShareAccountInitializedEvent {
     pub market: Pubkey,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct OptionTalliesDisclosedEvent {
+    pub market: Pubkey,
+    pub encrypted_tallies: [[u8; 32]; MAX_TALLIED_OPTIONS],
+    pub nonce: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OptionTalliesDisclosedError {
+    pub market: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OptionTallySyncedEvent {
+    pub market: Pubkey,
+    pub share_account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OptionTallySyncedError {
+    pub market: Pubkey,
+    pub share_account: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketOpenedEvent};
+use crate::state::OpportunityMarket;
+
+#[derive(Accounts)]
+pub struct ActivateMarket<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.open_timestamp.is_none() @ ErrorCode::MarketAlreadyOpen,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Market's ATA holding reward tokens
+    #[account(
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Permissionless counterpart of `open_market` for markets created with a planned open time.
+// Called late, the market opens right away so the staking period is not cut short.
+pub fn activate_market(ctx: Context<ActivateMarket>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let planned_open_timestamp = market
+        .planned_open_timestamp
+        .ok_or(ErrorCode::NoPlannedOpenTime)?;
+
+    // Check that market ATA has enough tokens for rewards
+    require!(
+        ctx.accounts.market_token_ata.amount >= market.reward_amount,
+        ErrorCode::InsufficientRewardFunding
+    );
+
+    let clock = Clock::get()?;
+    let open_timestamp = planned_open_timestamp.max(clock.unix_timestamp as u64);
+    market.open_timestamp = Some(open_timestamp);
+
+    emit_ts!(MarketOpenedEvent {
+        market: market.key(),
+        creator: market.creator,
+        open_timestamp: open_timestamp,
+    });
+
+    Ok(())
+}
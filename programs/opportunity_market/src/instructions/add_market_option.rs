@@ -1,11 +1,16 @@
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
-use arcium_client::idl::arcium::types::CallbackAccount;
 
 use crate::error::ErrorCode;
-use crate::events::{emit_ts, MarketOptionCreatedEvent, StakedError, StakedEvent};
-use crate::state::{CentralState, OpportunityMarket, OpportunityMarketOption, ShareAccount, EncryptedTokenAccount};
-use crate::instructions::stake::SHARE_ACCOUNT_SEED;
+use crate::events::{emit_ts, MarketOptionCreatedEvent};
+use crate::state::{
+    CentralState, OpportunityMarket, OpportunityMarketOption, ShareAccount, EncryptedTokenAccount, StakeDelegation,
+    MAX_TALLIED_OPTIONS,
+};
+use crate::instructions::stake::{
+    begin_stake, delegation_args, settle_stake, stake_account_args, stake_callback_accounts, StakeInput, StakeOutput,
+    SHARE_ACCOUNT_SEED,
+};
 use crate::COMP_DEF_OFFSET_ADD_OPTION_STAKE;
 use crate::{ID, ID_CONST, ArciumSignerAccount};
 
@@ -19,6 +24,7 @@ pub struct AddMarketOption<'info> {
     #[account(
         mut,
         constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
     )]
     pub market: Box<Account<'info, OpportunityMarket>>,
 
@@ -87,26 +93,28 @@ pub struct AddMarketOption<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-pub fn add_market_option(
-    ctx: Context<AddMarketOption>,
-    computation_offset: u64,
+// Adds option `option_index` to the market in the name of `creator`, as long as the staking
+// period is not over. Returns the current timestamp.
+pub fn add_option(
+    market: &mut OpportunityMarket,
+    option: &mut OpportunityMarketOption,
+    bump: u8,
     option_index: u16,
-    _share_account_id: u32,
     name: String,
-    amount_ciphertext: [u8; 32],
-    input_nonce: u128,
-    authorized_reader_nonce: u128,
-) -> Result<()> {
-    let user_pubkey = ctx.accounts.source_eta.user_pubkey;
-    let market = &mut ctx.accounts.market;
-    let authorized_reader_pubkey = market.authorized_reader_pubkey;
-
+    creator: Pubkey,
+) -> Result<u64> {
     // Option index must match total_options + 1
     require!(
         option_index == market.total_options + 1,
         ErrorCode::InvalidOptionIndex
     );
 
+    // Stake on higher options could not be tracked in the option tallies
+    require!(
+        option_index as usize <= MAX_TALLIED_OPTIONS,
+        ErrorCode::TooManyOptions
+    );
+
     // Enforce staking period is not over (if market is open)
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp as u64;
@@ -122,56 +130,93 @@ pub fn add_market_option(
     market.total_options = option_index;
 
     // Initialize the option account
-    let option = &mut ctx.accounts.option;
-    option.bump = ctx.bumps.option;
+    option.bump = bump;
     option.index = option_index;
     option.name = name;
     option.total_shares = None;
     option.total_score = None;
-    option.creator = ctx.accounts.creator.key();
+    option.creator = creator;
     option.initialized = false;
 
-    // Lock share account and set staked timestamp
-    ctx.accounts.share_account.staked_at_timestamp = Some(current_timestamp);
-    ctx.accounts.share_account.locked = true;
-
-    let source_eta_key = ctx.accounts.source_eta.key();
-    let source_eta_nonce = ctx.accounts.source_eta.state_nonce;
-
-    let share_account_key = ctx.accounts.share_account.key();
-    let option_key = ctx.accounts.option.key();
-
-    ctx.accounts.source_eta.locked = true;
+    Ok(current_timestamp)
+}
 
-    // Build args for encrypted computation
+// Args of add_option_stake, or of its delegated variant when staking through a delegation
+#[allow(clippy::too_many_arguments)]
+pub fn add_option_stake_args(
+    input: &StakeInput,
+    market: &OpportunityMarket,
+    min_deposit: u64,
+    option_index: u16,
+    user_eta: &Account<EncryptedTokenAccount>,
+    share_account: &ShareAccount,
+    staked_at: u64,
+    delegation: Option<&Account<StakeDelegation>>,
+) -> ArgBuilder {
     let args = ArgBuilder::new()
         // Encrypted amount input (Enc<Shared, AddOptionStakeInput>)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(input_nonce)
-        .encrypted_u64(amount_ciphertext)
-
-        // Authorized reader context (Shared) - voluntary disclosure
-        .x25519_pubkey(authorized_reader_pubkey)
-        .plaintext_u128(authorized_reader_nonce)
-
-        // User's ETA (Enc<Shared, EncryptedTokenBalance>)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(source_eta_nonce)
-        .account(source_eta_key, 8, 32 * 1)
-
-        // Share account context (Shared)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(ctx.accounts.share_account.state_nonce)
-
+        .x25519_pubkey(input.pubkey)
+        .plaintext_u128(input.nonce)
+        .encrypted_u64(input.amount_ciphertext);
+    let args = stake_account_args(args, market, input.authorized_reader_nonce, user_eta, share_account)
         // Plaintext: min_deposit from central_state
-        .plaintext_u64(ctx.accounts.central_state.min_option_deposit)
+        .plaintext_u64(min_deposit)
 
         // Plaintext: selected_option (u64 because no plaintext_u16)
         .plaintext_u64(option_index as u64)
-        .build();
+
+        // Plaintext: stake timestamp, weights the amount for average entry time
+        .plaintext_u64(staked_at);
+    delegation_args(args, user_eta, delegation)
+}
+
+pub fn add_market_option(
+    ctx: Context<AddMarketOption>,
+    computation_offset: u64,
+    option_index: u16,
+    _share_account_id: u32,
+    name: String,
+    amount_ciphertext: [u8; 32],
+    input_nonce: u128,
+    authorized_reader_nonce: u128,
+) -> Result<()> {
+    let current_timestamp = add_option(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.option,
+        ctx.bumps.option,
+        option_index,
+        name,
+        ctx.accounts.creator.key(),
+    )?;
+    begin_stake(&mut ctx.accounts.source_eta, &mut ctx.accounts.share_account, None, current_timestamp)?;
+
+    // Build args for encrypted computation
+    let input = StakeInput {
+        pubkey: ctx.accounts.source_eta.user_pubkey,
+        nonce: input_nonce,
+        amount_ciphertext,
+        authorized_reader_nonce,
+    };
+    let args = add_option_stake_args(
+        &input,
+        &ctx.accounts.market,
+        ctx.accounts.central_state.min_option_deposit,
+        option_index,
+        &ctx.accounts.source_eta,
+        &ctx.accounts.share_account,
+        current_timestamp,
+        None,
+    )
+    .build();
 
     // Queue computation with callback
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    let callback_accounts = stake_callback_accounts(
+        ctx.accounts.source_eta.key(),
+        ctx.accounts.share_account.key(),
+        Some(ctx.accounts.option.key()),
+        None,
+    );
     queue_computation(
         ctx.accounts,
         computation_offset,
@@ -179,20 +224,7 @@ pub fn add_market_option(
         vec![AddOptionStakeCallback::callback_ix(
             computation_offset,
             &ctx.accounts.mxe_account,
-            &[
-                CallbackAccount {
-                    pubkey: source_eta_key,
-                    is_writable: true,
-                },
-                CallbackAccount {
-                    pubkey: share_account_key,
-                    is_writable: true,
-                },
-                CallbackAccount {
-                    pubkey: option_key,
-                    is_writable: true,
-                },
-            ],
+            &callback_accounts,
         )?],
         1,
         0,
@@ -228,78 +260,57 @@ pub struct AddOptionStakeCallback<'info> {
     pub option: Account<'info, OpportunityMarketOption>,
 }
 
-pub fn add_market_option_callback(
-    ctx: Context<AddOptionStakeCallback>,
-    output: SignedComputationOutputs<AddOptionStakeOutput>,
+// Settles the stake on a new option, which is only initialized when the stake went through
+pub fn settle_option_stake(
+    output: Option<StakeOutput>,
+    option: &mut Account<OpportunityMarketOption>,
+    user_eta: &mut Account<EncryptedTokenAccount>,
+    share_account: &mut Account<ShareAccount>,
+    delegation: Option<&mut Account<StakeDelegation>>,
 ) -> Result<()> {
-    // Unlock
-    ctx.accounts.source_eta.locked = false;
-    ctx.accounts.share_account.locked = false;
-
-    // Verify output - on error, rollback and return Ok so mutations persist
-    let res = match output.verify_output(
-        &ctx.accounts.cluster_account,
-        &ctx.accounts.computation_account,
-    ) {
-        Ok(AddOptionStakeOutput { field_0 }) => field_0,
-        Err(_) => {
-            // Rollback
-            ctx.accounts.share_account.staked_at_timestamp = None;
-            emit_ts!(StakedError {
-                user: ctx.accounts.source_eta.owner,
-            });
-            return Ok(());
-        }
-    };
-
-    if res.field_0 {
-        // Rollback
-        ctx.accounts.share_account.staked_at_timestamp = None;
-        emit_ts!(StakedError {
-            user: ctx.accounts.source_eta.owner,
-        });
+    if !settle_stake(output, user_eta, share_account, delegation)? {
         return Ok(());
     }
 
-    let new_user_balance = res.field_1;
-    let bought_shares = res.field_2;
-    let bought_shares_disclosed = res.field_3;
-
-    // Update source ETA balance
-    ctx.accounts.source_eta.state_nonce = new_user_balance.nonce;
-    ctx.accounts.source_eta.encrypted_state = new_user_balance.ciphertexts;
-    ctx.accounts.source_eta.is_initialized = true;
-
-    // Update share account encrypted state
-    ctx.accounts.share_account.state_nonce = bought_shares.nonce;
-    ctx.accounts.share_account.encrypted_state = bought_shares.ciphertexts;
-    ctx.accounts.share_account.state_nonce_disclosure = bought_shares_disclosed.nonce;
-    ctx.accounts.share_account.encrypted_state_disclosure =bought_shares_disclosed.ciphertexts;
-
     // Mark option as initialized
-    ctx.accounts.option.initialized = true;
+    option.initialized = true;
 
     emit_ts!(MarketOptionCreatedEvent {
-        option: ctx.accounts.option.key(),
-        market: ctx.accounts.share_account.market,
-        index: ctx.accounts.option.index,
-        name: ctx.accounts.option.name.clone(),
-        creator: ctx.accounts.option.creator,
+        option: option.key(),
+        market: share_account.market,
+        index: option.index,
+        name: option.name.clone(),
+        creator: option.creator,
         by_market_creator: false
     });
 
-    emit_ts!(StakedEvent {
-        user: ctx.accounts.source_eta.owner,
-        market: ctx.accounts.share_account.market,
-        encrypted_token_account: ctx.accounts.source_eta.key(),
-        share_account: ctx.accounts.share_account.key(),
-        share_encrypted_state: bought_shares.ciphertexts,
-        share_state_nonce: bought_shares.nonce,
-        share_encrypted_state_disclosure: bought_shares_disclosed.ciphertexts,
-        share_state_disclosure_nonce: bought_shares_disclosed.nonce,
-        encrypted_eta_balance: new_user_balance.ciphertexts[0],
-        eta_balance_nonce: new_user_balance.nonce,
-    });
-
     Ok(())
 }
+
+pub fn add_market_option_callback(
+    ctx: Context<AddOptionStakeCallback>,
+    output: SignedComputationOutputs<AddOptionStakeOutput>,
+) -> Result<()> {
+    // Verify output - on error, settle_stake rolls back and we return Ok so mutations persist
+    let output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(AddOptionStakeOutput { field_0 }) => Some(StakeOutput {
+            error_code: field_0.field_0,
+            new_user_balance: field_0.field_1,
+            bought_shares: field_0.field_2,
+            bought_shares_disclosure: field_0.field_3,
+            new_spend: None,
+        }),
+        Err(_) => None,
+    };
+
+    settle_option_stake(
+        output,
+        &mut ctx.accounts.option,
+        &mut ctx.accounts.source_eta,
+        &mut ctx.accounts.share_account,
+        None,
+    )
+}
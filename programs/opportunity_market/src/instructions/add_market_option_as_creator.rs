@@ -3,6 +3,7 @@ use anchor_lang::prelude::*;
 use crate::error::ErrorCode;
 use crate::state::{OpportunityMarket, OpportunityMarketOption};
 use crate::events::{emit_ts, MarketOptionCreatedEvent};
+use crate::instructions::add_market_option::add_option;
 
 #[derive(Accounts)]
 #[instruction(option_index: u16)]
@@ -13,6 +14,7 @@ pub struct AddMarketOptionAsCreator<'info> {
     #[account(
         mut,
         constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
         constraint = market.creator == creator.key() || market.market_authority == Some(creator.key()) @ ErrorCode::Unauthorized,
     )]
     pub market: Box<Account<'info, OpportunityMarket>>,
@@ -34,36 +36,18 @@ pub fn add_market_option_as_creator(
     option_index: u16,
     name: String,
 ) -> Result<()> {
-    let market = &mut ctx.accounts.market;
-
-    // Option index must match total_options + 1
-    require!(
-        option_index == market.total_options + 1,
-        ErrorCode::InvalidOptionIndex
-    );
-
-    // Enforce staking period is not over (if market is open)
-    let clock = Clock::get()?;
-    let current_timestamp = clock.unix_timestamp as u64;
-    if let Some(open_timestamp) = market.open_timestamp {
-        let stake_end_timestamp = open_timestamp + market.time_to_stake;
-        require!(
-            current_timestamp <= stake_end_timestamp,
-            ErrorCode::StakingNotActive
-        );
-    }
-
-    // Increment total options
-    market.total_options = option_index;
-
-    // Initialize the option account
+    add_option(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.option,
+        ctx.bumps.option,
+        option_index,
+        name,
+        ctx.accounts.creator.key(),
+    )?;
+
+    // Options added by the creator carry no stake, so they are usable right away
+    let market = &ctx.accounts.market;
     let option = &mut ctx.accounts.option;
-    option.bump = ctx.bumps.option;
-    option.index = option_index;
-    option.name = name;
-    option.total_shares = None;
-    option.total_score = None;
-    option.creator = ctx.accounts.creator.key();
     option.initialized = true;
 
     emit_ts!(MarketOptionCreatedEvent {
@@ -0,0 +1,227 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::{
+    CentralState, OpportunityMarket, OpportunityMarketOption, ShareAccount, EncryptedTokenAccount, StakeDelegation,
+};
+use crate::instructions::add_market_option::{add_option, add_option_stake_args, settle_option_stake};
+use crate::instructions::create_stake_delegation::STAKE_DELEGATION_SEED;
+use crate::instructions::stake::{begin_stake, stake_callback_accounts, StakeInput, StakeOutput, SHARE_ACCOUNT_SEED};
+use crate::COMP_DEF_OFFSET_DELEGATED_ADD_OPTION_STAKE;
+use crate::{ID, ID_CONST, ArciumSignerAccount};
+
+#[queue_computation_accounts("delegated_add_option_stake", delegate)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, option_index: u16, share_account_id: u32)]
+pub struct AddMarketOptionAsDelegate<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    #[account(
+        seeds = [b"central_state"],
+        bump = central_state.bump,
+    )]
+    pub central_state: Box<Account<'info, CentralState>>,
+
+    #[account(
+        init,
+        payer = delegate,
+        space = 8 + OpportunityMarketOption::INIT_SPACE,
+        seeds = [b"option", market.key().as_ref(), &option_index.to_le_bytes()],
+        bump,
+    )]
+    pub option: Box<Account<'info, OpportunityMarketOption>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DELEGATION_SEED, source_eta.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+        constraint = !delegation.locked @ ErrorCode::Locked,
+    )]
+    pub delegation: Box<Account<'info, StakeDelegation>>,
+
+    #[account(
+        mut,
+        constraint = source_eta.token_mint == market.mint @ ErrorCode::InvalidMint,
+        constraint = !source_eta.locked @ ErrorCode::Locked,
+    )]
+    pub source_eta: Box<Account<'info, EncryptedTokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [SHARE_ACCOUNT_SEED, source_eta.owner.as_ref(), market.key().as_ref(), &share_account_id.to_le_bytes()],
+        bump,
+        constraint = share_account.staked_at_timestamp.is_none() @ ErrorCode::AlreadyPurchased,
+        constraint = !share_account.locked @ ErrorCode::Locked,
+    )]
+    pub share_account: Box<Account<'info, ShareAccount>>,
+
+    // Arcium accounts
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = delegate,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_DELEGATED_ADD_OPTION_STAKE))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Adds an option and stakes on it from the owner's ETA, on behalf of the owner. The option is
+// created in the owner's name; the delegate only pays its rent.
+pub fn add_market_option_as_delegate(
+    ctx: Context<AddMarketOptionAsDelegate>,
+    computation_offset: u64,
+    option_index: u16,
+    _share_account_id: u32,
+    name: String,
+    amount_ciphertext: [u8; 32],
+    delegate_pubkey: [u8; 32],
+    input_nonce: u128,
+    authorized_reader_nonce: u128,
+) -> Result<()> {
+    let current_timestamp = add_option(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.option,
+        ctx.bumps.option,
+        option_index,
+        name,
+        ctx.accounts.source_eta.owner,
+    )?;
+    begin_stake(
+        &mut ctx.accounts.source_eta,
+        &mut ctx.accounts.share_account,
+        Some(&mut ctx.accounts.delegation),
+        current_timestamp,
+    )?;
+
+    // Build args for encrypted computation, the input encrypted to the delegate
+    let input = StakeInput {
+        pubkey: delegate_pubkey,
+        nonce: input_nonce,
+        amount_ciphertext,
+        authorized_reader_nonce,
+    };
+    let args = add_option_stake_args(
+        &input,
+        &ctx.accounts.market,
+        ctx.accounts.central_state.min_option_deposit,
+        option_index,
+        &ctx.accounts.source_eta,
+        &ctx.accounts.share_account,
+        current_timestamp,
+        Some(&ctx.accounts.delegation),
+    )
+    .build();
+
+    // Queue computation with callback
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    let callback_accounts = stake_callback_accounts(
+        ctx.accounts.source_eta.key(),
+        ctx.accounts.share_account.key(),
+        Some(ctx.accounts.option.key()),
+        Some(ctx.accounts.delegation.key()),
+    );
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![DelegatedAddOptionStakeCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+#[callback_accounts("delegated_add_option_stake")]
+#[derive(Accounts)]
+pub struct DelegatedAddOptionStakeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_DELEGATED_ADD_OPTION_STAKE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    // Callback accounts
+    #[account(mut)]
+    pub source_eta: Account<'info, EncryptedTokenAccount>,
+
+    #[account(mut)]
+    pub share_account: Account<'info, ShareAccount>,
+
+    #[account(mut)]
+    pub option: Account<'info, OpportunityMarketOption>,
+
+    #[account(mut)]
+    pub delegation: Account<'info, StakeDelegation>,
+}
+
+pub fn add_market_option_as_delegate_callback(
+    ctx: Context<DelegatedAddOptionStakeCallback>,
+    output: SignedComputationOutputs<DelegatedAddOptionStakeOutput>,
+) -> Result<()> {
+    // Verify output - on error, settle_stake rolls back and we return Ok so mutations persist
+    let output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(DelegatedAddOptionStakeOutput { field_0 }) => Some(StakeOutput {
+            error_code: field_0.field_0,
+            new_user_balance: field_0.field_1,
+            bought_shares: field_0.field_2,
+            bought_shares_disclosure: field_0.field_3,
+            new_spend: Some(field_0.field_4),
+        }),
+        Err(_) => None,
+    };
+
+    settle_option_stake(
+        output,
+        &mut ctx.accounts.option,
+        &mut ctx.accounts.source_eta,
+        &mut ctx.accounts.share_account,
+        Some(&mut ctx.accounts.delegation),
+    )
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketCancelledEvent};
+use crate::state::OpportunityMarket;
+
+#[derive(Accounts)]
+pub struct CancelMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.creator == authority.key()
+            || market.market_authority == Some(authority.key()) @ ErrorCode::Unauthorized,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+        constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+    )]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Market's ATA holding reward tokens
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Creator's token account, receives the reward refund
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = market.creator,
+        token::token_program = token_program,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Markets can be cancelled before opening or while staking is still active
+    if let Some(open_timestamp) = market.open_timestamp {
+        let stake_end = open_timestamp
+            .checked_add(market.time_to_stake)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp as u64;
+        // Reveals start at stake end, a market cancelled then could hold revealed shares
+        require!(current_time < stake_end, ErrorCode::CancellationWindowClosed);
+    }
+
+    market.cancelled = true;
+    // Nothing is left to reclaim once the reward has been refunded
+    market.rewards_reclaimed = true;
+
+    // Sponsorships stay in the ATA until each sponsor is refunded through `refund_sponsor`
+    let refunded_amount = ctx
+        .accounts
+        .market_token_ata
+        .amount
+        .checked_sub(market.total_sponsored)
+        .ok_or(ErrorCode::Overflow)?;
+    if refunded_amount > 0 {
+        let seed_key = market.seed_key();
+        let index_bytes = market.index.to_le_bytes();
+        let bump = market.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"opportunity_market",
+            seed_key.as_ref(),
+            &index_bytes,
+            &[bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.market_token_ata.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refunded_amount,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
+
+    emit_ts!(MarketCancelledEvent {
+        market: market.key(),
+        authority: ctx.accounts.authority.key(),
+        refunded_amount: refunded_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::events::{emit_ts, ProtocolFeesClaimedEvent};
+use crate::state::{CentralState, OpportunityMarket};
+
+/// Permissionless, fees can only ever go to the treasury set in the central state.
+#[derive(Accounts)]
+pub struct ClaimProtocolFees<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"central_state"],
+        bump = central_state.bump,
+    )]
+    pub central_state: Account<'info, CentralState>,
+
+    #[account(mut)]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Market's ATA holding reward tokens and withheld fees
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's token account to receive the fees
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = central_state.treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_protocol_fees(ctx: Context<ClaimProtocolFees>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let amount = market
+        .protocol_fees_accrued
+        .min(ctx.accounts.market_token_ata.amount);
+    market.protocol_fees_accrued = 0;
+
+    if amount > 0 {
+        let seed_key = market.seed_key();
+        let index_bytes = market.index.to_le_bytes();
+        let bump = market.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"opportunity_market",
+            seed_key.as_ref(),
+            &index_bytes,
+            &[bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.market_token_ata.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
+
+    emit_ts!(ProtocolFeesClaimedEvent {
+        market: market.key(),
+        treasury: ctx.accounts.central_state.treasury,
+        amount: amount,
+    });
+
+    Ok(())
+}
@@ -204,17 +204,28 @@ pub fn close_ephemeral_encrypted_token_account_callback(
         }
     };
 
+    // Regular balance would overflow, keep the ephemeral ETA open
+    if res.field_0 {
+        ephemeral_eta.locked = false;
+        emit_ts!(EphemeralAccountClosedError {
+            user: regular_eta.owner,
+        });
+        return Ok(());
+    }
+
+    let new_balance = res.field_1;
+
     // Update regular ETA with new balance (ephemeral balance transferred in)
     // No SPL token transfer needed - tokens are already in the common TokenVault
-    regular_eta.state_nonce = res.nonce;
-    regular_eta.encrypted_state = res.ciphertexts;
+    regular_eta.state_nonce = new_balance.nonce;
+    regular_eta.encrypted_state = new_balance.ciphertexts;
     regular_eta.is_initialized = true;
 
     emit_ts!(EphemeralAccountClosedEvent {
         user: regular_eta.owner,
         encrypted_token_account: regular_eta.key(),
-        encrypted_new_balance: res.ciphertexts[0],
-        nonce: res.nonce,
+        encrypted_new_balance: new_balance.ciphertexts[0],
+        nonce: new_balance.nonce,
     });
 
     // Close ephemeral ETA account (rent to rent_recipient)
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketClosedEvent};
+use crate::instructions::create_market::OPTION_TALLIES_SEED;
+use crate::instructions::refund_sponsor::sponsorships_refundable;
+use crate::state::{OpportunityMarket, OptionTallies};
+
+// Seconds after the reveal period ends after which a market can be closed
+// even if some share accounts were never closed (180 days)
+pub const MARKET_EXPIRY_SECONDS: u64 = 180 * 86_400;
+
+/// Checks that a market is settled and no share account still depends on its accounts, or that
+/// it has expired. Share accounts are seeded by the market key, so `close_market` keeps the
+/// option tallies of an expired market with share accounts left, see there.
+pub fn require_market_closable(market: &OpportunityMarket) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    require_sponsors_refunded(market, current_time)?;
+
+    let Some(open_timestamp) = market.open_timestamp else {
+        // Never opened, nothing could have been staked
+        return require_no_open_share_accounts(market);
+    };
+
+    if market.cancelled {
+        return require_no_open_share_accounts(market);
+    }
+
+    let reveal_end = open_timestamp
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .ok_or(ErrorCode::Overflow)?;
+    let expires_at = reveal_end
+        .checked_add(MARKET_EXPIRY_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(current_time >= reveal_end, ErrorCode::MarketNotResolved);
+
+    if current_time >= expires_at {
+        return Ok(());
+    }
+    require_no_open_share_accounts(market)
+}
+
+// Sponsorships of markets that never paid out go back to the sponsors, not to the creator
+fn require_sponsors_refunded(market: &OpportunityMarket, current_time: u64) -> Result<()> {
+    if sponsorships_refundable(market, current_time)? {
+        require!(market.total_sponsored == 0, ErrorCode::SponsorshipsNotRefunded);
+    }
+    Ok(())
+}
+
+fn require_no_open_share_accounts(market: &OpportunityMarket) -> Result<()> {
+    require!(market.open_share_accounts == 0, ErrorCode::ShareAccountsStillOpen);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        constraint = market.closed_options == market.total_options @ ErrorCode::OptionsStillOpen,
+        constraint = market.protocol_fees_accrued == 0 @ ErrorCode::ProtocolFeesUnclaimed,
+        // Sponsorship accounts are seeded by the market key and would outlive it
+        constraint = market.open_sponsorships == 0 @ ErrorCode::SponsorshipsStillOpen,
+        close = creator,
+    )]
+    pub market: Account<'info, OpportunityMarket>,
+
+    /// Closed along with the market, unless share accounts are left
+    #[account(
+        mut,
+        seeds = [OPTION_TALLIES_SEED, market.key().as_ref()],
+        bump = option_tallies.bump,
+        constraint = !option_tallies.locked @ ErrorCode::Locked,
+    )]
+    pub option_tallies: Account<'info, OptionTallies>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Market's ATA holding reward tokens, closed along with the market
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Creator's token account, receives any tokens left in the market ATA
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = creator,
+        token::token_program = token_program,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+    let market = &ctx.accounts.market;
+    require_market_closable(market)?;
+
+    let seed_key = market.seed_key();
+    let index_bytes = market.index.to_le_bytes();
+    let bump = market.bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"opportunity_market",
+        seed_key.as_ref(),
+        &index_bytes,
+        &[bump],
+    ]];
+
+    // Sweep leftovers (rewards nobody can claim, rounding dust) to the creator
+    let remaining_amount = ctx.accounts.market_token_ata.amount;
+    if remaining_amount > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.market_token_ata.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            remaining_amount,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.market_token_ata.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit_ts!(MarketClosedEvent {
+        market: market.key(),
+        creator: market.creator,
+        remaining_amount: remaining_amount,
+    });
+
+    // Share accounts left on an expired market would be valid against a market re-created at the
+    // same address. The option tallies stay open, so creating that market fails on their `init`.
+    if market.open_share_accounts == 0 {
+        ctx.accounts
+            .option_tallies
+            .close(ctx.accounts.creator.to_account_info())?;
+    }
+
+    // Market account will be closed automatically via the close constraint
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketOptionClosedEvent};
+use crate::instructions::close_market::require_market_closable;
+use crate::state::{OpportunityMarket, OpportunityMarketOption};
+
+#[derive(Accounts)]
+#[instruction(option_index: u16)]
+pub struct CloseMarketOption<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"option", market.key().as_ref(), &option_index.to_le_bytes()],
+        bump = option.bump,
+        close = option_creator,
+    )]
+    pub option: Account<'info, OpportunityMarketOption>,
+
+    /// CHECK: receives the option rent, must be whoever paid for the option
+    #[account(mut, address = option.creator @ ErrorCode::Unauthorized)]
+    pub option_creator: UncheckedAccount<'info>,
+}
+
+pub fn close_market_option(ctx: Context<CloseMarketOption>, option_index: u16) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require_market_closable(market)?;
+
+    market.closed_options = market
+        .closed_options
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit_ts!(MarketOptionClosedEvent {
+        market: market.key(),
+        option: ctx.accounts.option.key(),
+        index: option_index,
+    });
+
+    // Option account will be closed automatically via the close constraint
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketSponsorClosedEvent};
+use crate::instructions::sponsor_market::MARKET_SPONSOR_SEED;
+use crate::state::{MarketSponsor, OpportunityMarket};
+
+#[derive(Accounts)]
+pub struct CloseMarketSponsor<'info> {
+    /// Anyone can close a settled sponsorship, rent always goes to the sponsor
+    pub signer: Signer<'info>,
+
+    /// CHECK: Receives the sponsorship account's rent, checked against `market_sponsor`
+    #[account(mut, address = market_sponsor.sponsor)]
+    pub sponsor: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SPONSOR_SEED, market.key().as_ref(), sponsor.key().as_ref()],
+        bump = market_sponsor.bump,
+        close = sponsor,
+    )]
+    pub market_sponsor: Account<'info, MarketSponsor>,
+}
+
+/// Closes the record of a sponsorship that was paid out as part of the reward, once a winner is
+/// selected and the reveal period is over. Refundable sponsorships go through `refund_sponsor`.
+pub fn close_market_sponsor(ctx: Context<CloseMarketSponsor>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let open_timestamp = market.open_timestamp.ok_or(ErrorCode::MarketNotOpen)?;
+    let reveal_end = open_timestamp
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .ok_or(ErrorCode::Overflow)?;
+
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    require!(
+        market.selected_option.is_some() && current_time >= reveal_end,
+        ErrorCode::SponsorshipNotSettled
+    );
+
+    market.open_sponsorships = market
+        .open_sponsorships
+        .checked_sub(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit_ts!(MarketSponsorClosedEvent {
+        market: market.key(),
+        sponsor: ctx.accounts.sponsor.key(),
+        amount: ctx.accounts.market_sponsor.amount,
+    });
+
+    // Sponsorship account will be closed automatically via the close constraint
+    Ok(())
+}
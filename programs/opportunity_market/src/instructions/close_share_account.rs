@@ -4,7 +4,8 @@ use anchor_spl::token_interface::{
 };
 
 use crate::error::ErrorCode;
-use crate::events::{emit_ts, RewardClaimedEvent};
+use crate::events::{emit_ts, ProtocolFeeCollectedEvent, RewardClaimedEvent, ShareAccountClosedEvent};
+use crate::instructions::reveal_shares::require_reveals_settled;
 use crate::instructions::stake::SHARE_ACCOUNT_SEED;
 use crate::state::{OpportunityMarket, OpportunityMarketOption, ShareAccount};
 
@@ -31,13 +32,13 @@ pub struct CloseShareAccount<'info> {
     )]
     pub option: Account<'info, OpportunityMarketOption>,
 
-    #[account(address = market.mint)]
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     /// Market's ATA holding reward tokens
     #[account(
         mut,
-        associated_token::mint = token_mint,
+        associated_token::mint = reward_mint,
         associated_token::authority = market,
         associated_token::token_program = token_program,
     )]
@@ -46,7 +47,7 @@ pub struct CloseShareAccount<'info> {
     /// Owner's token account to receive rewards
     #[account(
         mut,
-        token::mint = token_mint,
+        token::mint = reward_mint,
         token::authority = owner,
         token::token_program = token_program,
     )]
@@ -58,9 +59,35 @@ pub struct CloseShareAccount<'info> {
 
 pub fn close_share_account(ctx: Context<CloseShareAccount>, option_index: u16, _share_account_id: u32) -> Result<()> {
     let share_account = &ctx.accounts.share_account;
-    let market = &ctx.accounts.market;
+    let market = &mut ctx.accounts.market;
     let option = &ctx.accounts.option;
 
+    market.open_share_accounts = market
+        .open_share_accounts
+        .checked_sub(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // On cancelled markets there is no reveal or reward. Accounts can be closed once
+    // the stake has been refunded through `do_unstake_early` (or if nothing was staked).
+    // Share accounts that were never staked, or whose stake was rolled back, have nothing to
+    // reveal and can be closed on any market.
+    if market.cancelled || share_account.staked_at_timestamp.is_none() {
+        require!(!share_account.locked, ErrorCode::Locked);
+        require!(
+            share_account.unstaked_at_timestamp.is_some()
+                || share_account.staked_at_timestamp.is_none(),
+            ErrorCode::InvalidAccountState
+        );
+
+        emit_ts!(ShareAccountClosedEvent {
+            owner: ctx.accounts.owner.key(),
+            market: market.key(),
+            share_account: ctx.accounts.share_account.key(),
+        });
+
+        return Ok(());
+    }
+
     // Check that shares have been revealed
     let revealed_option = share_account.revealed_option.ok_or(ErrorCode::NotRevealed)?;
     if share_account.revealed_amount.is_none() {
@@ -91,32 +118,79 @@ pub fn close_share_account(ctx: Context<CloseShareAccount>, option_index: u16, _
         return Err(ErrorCode::MarketNotOpen.into());
     }
 
-    // Check if this share was bought for the winning option and user incremented the tally
-    // If so, transfer proportional yield from market to user
+    // Slashed stake is part of the reward pool, it has to be final before anyone claims from it
+    if market.losing_stake_slash_bps > 0 {
+        require_reveals_settled(market, current_time)?;
+    }
+
+    // Check if this share was bought for a winning option and user incremented the tally
+    // If so, transfer proportional yield from market to user.
     let mut user_reward: u64 = 0;
-    if let Some(selected_option) = market.selected_option {
-        if revealed_option == selected_option && share_account.total_incremented {
+    let winner = market
+        .winning_options
+        .iter()
+        .find(|w| w.option_index == revealed_option)
+        .copied();
+    if let Some(winner) = winner {
+        if share_account.total_incremented {
             // User is eligible for yield
             let user_score = share_account.revealed_score.ok_or(ErrorCode::NotRevealed)?;
             let total_score = option.total_score.ok_or(ErrorCode::NotRevealed)?;
 
-            // Calculate proportional reward: (user_score / total_score) * reward_amount
+            // Slashed losing stake is added to the reward pool
+            let reward_pool = (market.reward_amount as u128)
+                .checked_add(market.total_slashed as u128)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // This option's slice of the reward: reward_pool * weight / total_weight
+            let total_weight: u128 = market.winning_options.iter().map(|w| w.weight as u128).sum();
+            let reward_amount = reward_pool
+                .checked_mul(winner.weight as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(total_weight)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Calculate proportional reward: (user_score / total_score) * option_reward
             // Use u128 to prevent overflow during multiplication
-            let reward_amount = market.reward_amount as u128;
             user_reward = (user_score as u128)
                 .checked_mul(reward_amount)
                 .ok_or(ErrorCode::Overflow)?
                 .checked_div(total_score as u128)
                 .ok_or(ErrorCode::Overflow)? as u64; // Round down
 
+            // Count the gross reward as claimed, the fee stays in the market ATA for the treasury
+            market.total_rewards_claimed = market
+                .total_rewards_claimed
+                .checked_add(user_reward)
+                .ok_or(ErrorCode::Overflow)?;
+
+            let protocol_fee = (user_reward as u128)
+                .checked_mul(market.protocol_fee_bps as u128)
+                .ok_or(ErrorCode::Overflow)?
+                .checked_div(10_000)
+                .ok_or(ErrorCode::Overflow)? as u64; // Round down
+            if protocol_fee > 0 {
+                user_reward -= protocol_fee;
+                market.protocol_fees_accrued = market
+                    .protocol_fees_accrued
+                    .checked_add(protocol_fee)
+                    .ok_or(ErrorCode::Overflow)?;
+
+                emit_ts!(ProtocolFeeCollectedEvent {
+                    market: market.key(),
+                    share_account: ctx.accounts.share_account.key(),
+                    amount: protocol_fee,
+                });
+            }
+
             // Transfer SPL tokens from market ATA to owner's token account
             if user_reward > 0 {
-                let creator_key = market.creator;
+                let seed_key = market.seed_key();
                 let index_bytes = market.index.to_le_bytes();
                 let bump = market.bump;
                 let signer_seeds: &[&[&[u8]]] = &[&[
                     b"opportunity_market",
-                    creator_key.as_ref(),
+                    seed_key.as_ref(),
                     &index_bytes,
                     &[bump],
                 ]];
@@ -126,14 +200,14 @@ pub fn close_share_account(ctx: Context<CloseShareAccount>, option_index: u16, _
                         ctx.accounts.token_program.to_account_info(),
                         TransferChecked {
                             from: ctx.accounts.market_token_ata.to_account_info(),
-                            mint: ctx.accounts.token_mint.to_account_info(),
+                            mint: ctx.accounts.reward_mint.to_account_info(),
                             to: ctx.accounts.owner_token_account.to_account_info(),
                             authority: market.to_account_info(),
                         },
                         signer_seeds,
                     ),
                     user_reward,
-                    ctx.accounts.token_mint.decimals,
+                    ctx.accounts.reward_mint.decimals,
                 )?;
             }
         }
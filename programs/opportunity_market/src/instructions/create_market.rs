@@ -4,9 +4,15 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::{CentralState, OpportunityMarket};
+use crate::error::ErrorCode;
+use crate::score::{validate_earliness_cutoff, validate_scoring_config};
+use crate::state::{
+    CentralState, MarketParams, OpportunityMarket, OptionTallies, ScoringConfig, MAX_LOSING_STAKE_SLASH_BPS,
+};
 use crate::events::{emit_ts, MarketCreatedEvent};
 
+pub const OPTION_TALLIES_SEED: &[u8] = b"option_tallies";
+
 #[derive(Accounts)]
 #[instruction(market_index: u64)]
 pub struct CreateMarket<'info> {
@@ -15,6 +21,9 @@ pub struct CreateMarket<'info> {
 
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// Mint the reward is paid in, may be `token_mint`
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(
         seeds = [b"central_state"],
         bump = central_state.bump,
@@ -34,13 +43,24 @@ pub struct CreateMarket<'info> {
     #[account(
         init,
         payer = creator,
-        associated_token::mint = token_mint,
+        associated_token::mint = reward_mint,
         associated_token::authority = market,
         associated_token::token_program = token_program,
     )]
     pub market_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Running stake per option, encrypted to the MXE
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + OptionTallies::INIT_SPACE,
+        seeds = [OPTION_TALLIES_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub option_tallies: Box<Account<'info, OptionTallies>>,
+
     pub system_program: Program<'info, System>,
+    /// Token program of the reward mint
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -55,33 +75,160 @@ pub fn create_market(
     unstake_delay_seconds: u64,
     authorized_reader_pubkey: [u8; 32],
     allow_closing_early: bool,
+    earliness_cutoff_seconds: Option<u64>,
+    scoring_config: ScoringConfig,
+    losing_stake_slash_bps: u16,
+    min_stake: u64,
+    max_stake: Option<u64>,
+    planned_open_timestamp: Option<u64>,
 ) -> Result<()> {
+    let params = MarketParams {
+        reward_amount,
+        time_to_stake,
+        time_to_reveal,
+        market_authority,
+        unstake_delay_seconds,
+        authorized_reader_pubkey,
+        allow_closing_early,
+        earliness_cutoff_seconds,
+        scoring_config,
+        losing_stake_slash_bps,
+        min_stake,
+        max_stake,
+    };
+
+    validate_reward_mint(&params, &ctx.accounts.token_mint.key(), &ctx.accounts.reward_mint.key())?;
+
     let market = &mut ctx.accounts.market;
     market.bump = ctx.bumps.market;
-    market.creator = ctx.accounts.creator.key();
+    initialize_market(
+        market,
+        ctx.accounts.creator.key(),
+        ctx.accounts.token_mint.key(),
+        market_index,
+        &ctx.accounts.central_state,
+        &params,
+        planned_open_timestamp,
+    )?;
+    market.reward_mint = ctx.accounts.reward_mint.key();
+
+    let option_tallies = &mut ctx.accounts.option_tallies;
+    option_tallies.bump = ctx.bumps.option_tallies;
+    option_tallies.market = ctx.accounts.market.key();
+    option_tallies.is_initialized = false;
+    option_tallies.locked = false;
+    option_tallies.disclosed_at_timestamp = None;
+
+    emit_market_created(&ctx.accounts.market)?;
+
+    Ok(())
+}
+
+// Checks market parameters against the central state. Returns the earliness cutoff the market
+// gets, the central state's one unless overridden.
+pub fn validate_market_params(params: &MarketParams, central_state: &CentralState) -> Result<u64> {
+    // Creators may override the protocol-wide earliness cutoff per market
+    let earliness_cutoff_seconds = params
+        .earliness_cutoff_seconds
+        .unwrap_or(central_state.earliness_cutoff_seconds);
+    validate_earliness_cutoff(earliness_cutoff_seconds)?;
+    validate_scoring_config(&params.scoring_config)?;
+    require!(
+        params.losing_stake_slash_bps <= MAX_LOSING_STAKE_SLASH_BPS,
+        ErrorCode::InvalidSlashBps
+    );
+    require!(
+        params.max_stake.map_or(true, |max_stake| params.min_stake <= max_stake),
+        ErrorCode::InvalidStakeLimits
+    );
+    Ok(earliness_cutoff_seconds)
+}
+
+// Slashed losing stake is moved into the market ATA and paid out as reward, which only
+// works when the reward is paid in the staked token.
+pub fn validate_reward_mint(params: &MarketParams, mint: &Pubkey, reward_mint: &Pubkey) -> Result<()> {
+    require!(
+        params.losing_stake_slash_bps == 0 || mint == reward_mint,
+        ErrorCode::SlashRequiresStakeMintRewards
+    );
+    Ok(())
+}
+
+// Sets up a freshly created market, shared by `create_market` and `create_market_from_template`.
+// The bump, reward mint, template and series index are left to the caller.
+pub fn initialize_market(
+    market: &mut OpportunityMarket,
+    creator: Pubkey,
+    mint: Pubkey,
+    market_index: u64,
+    central_state: &CentralState,
+    params: &MarketParams,
+    planned_open_timestamp: Option<u64>,
+) -> Result<()> {
+    let earliness_cutoff_seconds = validate_market_params(params, central_state)?;
+    if let Some(planned_open_timestamp) = planned_open_timestamp {
+        let clock = Clock::get()?;
+        require!(
+            planned_open_timestamp > clock.unix_timestamp as u64,
+            ErrorCode::InvalidTimestamp
+        );
+    }
+
+    market.creator = creator;
     market.index = market_index;
     market.total_options = 0;
-    market.time_to_stake = time_to_stake;
-    market.time_to_reveal = time_to_reveal;
+    market.open_timestamp = None;
+    market.planned_open_timestamp = planned_open_timestamp;
+    market.time_to_stake = params.time_to_stake;
+    market.time_to_reveal = params.time_to_reveal;
     market.selected_option = None;
-    market.reward_amount = reward_amount;
-    market.mint = ctx.accounts.token_mint.key();
-    market.market_authority = market_authority;
-    market.earliness_cutoff_seconds = ctx.accounts.central_state.earliness_cutoff_seconds;
-    market.unstake_delay_seconds = unstake_delay_seconds;
-    market.authorized_reader_pubkey = authorized_reader_pubkey;
-    market.allow_closing_early = allow_closing_early;
+    market.winning_options = Vec::new();
+    market.reward_amount = params.reward_amount;
+    market.total_sponsored = 0;
+    market.total_rewards_claimed = 0;
+    market.reward_reclaim_grace_seconds = central_state.reward_reclaim_grace_seconds;
+    market.rewards_reclaimed = false;
+    market.protocol_fee_bps = central_state.protocol_fee_bps;
+    market.protocol_fees_accrued = 0;
+    market.losing_stake_slash_bps = params.losing_stake_slash_bps;
+    market.total_slashed = 0;
+    market.pending_reveals = 0;
+    market.mint = mint;
+    market.market_authority = params.market_authority;
+    market.earliness_cutoff_seconds = earliness_cutoff_seconds;
+    market.scoring_config = params.scoring_config;
+    market.unstake_delay_seconds = params.unstake_delay_seconds;
+    market.min_stake = params.min_stake;
+    market.max_stake = params.max_stake;
+    market.authorized_reader_pubkey = params.authorized_reader_pubkey;
+    market.allow_closing_early = params.allow_closing_early;
+    market.cancelled = false;
+    market.open_share_accounts = 0;
+    market.open_sponsorships = 0;
+    market.closed_options = 0;
+    market.template = None;
+    market.series_index = None;
+
+    Ok(())
+}
 
+pub fn emit_market_created(market: &Account<OpportunityMarket>) -> Result<()> {
     emit_ts!(MarketCreatedEvent {
-        market: ctx.accounts.market.key(),
-        creator: ctx.accounts.creator.key(),
-        index: market_index,
-        time_to_reveal: time_to_reveal,
-        time_to_stake : time_to_stake,
-        market_authority: market_authority,
-        authorized_reader_pubkey: authorized_reader_pubkey,
-        unstake_delay_seconds: unstake_delay_seconds,
-        allow_closing_early: allow_closing_early,
+        market: market.key(),
+        creator: market.creator,
+        index: market.index,
+        time_to_reveal: market.time_to_reveal,
+        time_to_stake : market.time_to_stake,
+        market_authority: market.market_authority,
+        authorized_reader_pubkey: market.authorized_reader_pubkey,
+        unstake_delay_seconds: market.unstake_delay_seconds,
+        allow_closing_early: market.allow_closing_early,
+        earliness_cutoff_seconds: market.earliness_cutoff_seconds,
+        scoring_config: market.scoring_config,
+        losing_stake_slash_bps: market.losing_stake_slash_bps,
+        min_stake: market.min_stake,
+        max_stake: market.max_stake,
+        planned_open_timestamp: market.planned_open_timestamp,
     });
 
     Ok(())
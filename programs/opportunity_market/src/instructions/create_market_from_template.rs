@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketCreatedFromTemplateEvent};
+use crate::instructions::create_market::{emit_market_created, initialize_market, OPTION_TALLIES_SEED};
+use crate::instructions::create_market_template::MARKET_TEMPLATE_SEED;
+use crate::state::{CentralState, MarketTemplate, OpportunityMarket, OptionTallies};
+
+#[derive(Accounts)]
+pub struct CreateMarketFromTemplate<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_TEMPLATE_SEED, template.creator.as_ref(), &template.template_id.to_le_bytes()],
+        bump = template.bump,
+        constraint = template.creator == creator.key() @ ErrorCode::Unauthorized,
+    )]
+    pub template: Box<Account<'info, MarketTemplate>>,
+
+    #[account(address = template.mint @ ErrorCode::InvalidMint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = template.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"central_state"],
+        bump = central_state.bump,
+    )]
+    pub central_state: Box<Account<'info, CentralState>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + OpportunityMarket::INIT_SPACE,
+        seeds = [b"opportunity_market", template.key().as_ref(), &template.next_series_index.to_le_bytes()],
+        bump,
+    )]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    /// ATA owned by market PDA, holds reward tokens
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Running stake per option, encrypted to the MXE
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + OptionTallies::INIT_SPACE,
+        seeds = [OPTION_TALLIES_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub option_tallies: Box<Account<'info, OptionTallies>>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Creates the next market of a template's series. The market PDA is derived from the template
+// and the series index, which also serves as the market's index.
+pub fn create_market_from_template(
+    ctx: Context<CreateMarketFromTemplate>,
+    planned_open_timestamp: Option<u64>,
+) -> Result<()> {
+    let template = &mut ctx.accounts.template;
+    let series_index = template.next_series_index;
+    template.next_series_index = series_index.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    let market = &mut ctx.accounts.market;
+    market.bump = ctx.bumps.market;
+    initialize_market(
+        market,
+        ctx.accounts.creator.key(),
+        ctx.accounts.token_mint.key(),
+        series_index,
+        &ctx.accounts.central_state,
+        &ctx.accounts.template.params,
+        planned_open_timestamp,
+    )?;
+    market.reward_mint = ctx.accounts.reward_mint.key();
+    market.template = Some(ctx.accounts.template.key());
+    market.series_index = Some(series_index);
+
+    let option_tallies = &mut ctx.accounts.option_tallies;
+    option_tallies.bump = ctx.bumps.option_tallies;
+    option_tallies.market = ctx.accounts.market.key();
+    option_tallies.is_initialized = false;
+    option_tallies.locked = false;
+    option_tallies.disclosed_at_timestamp = None;
+
+    emit_market_created(&ctx.accounts.market)?;
+    emit_ts!(MarketCreatedFromTemplateEvent {
+        market: ctx.accounts.market.key(),
+        template: ctx.accounts.template.key(),
+        series_index: series_index,
+    });
+
+    Ok(())
+}
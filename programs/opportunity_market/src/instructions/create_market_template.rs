@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::events::{emit_ts, MarketTemplateCreatedEvent};
+use crate::instructions::create_market::{validate_market_params, validate_reward_mint};
+use crate::state::{CentralState, MarketParams, MarketTemplate};
+
+pub const MARKET_TEMPLATE_SEED: &[u8] = b"market_template";
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct CreateMarketTemplate<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Mint the series' rewards are paid in, may be `token_mint`
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"central_state"],
+        bump = central_state.bump,
+    )]
+    pub central_state: Box<Account<'info, CentralState>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MarketTemplate::INIT_SPACE,
+        seeds = [MARKET_TEMPLATE_SEED, creator.key().as_ref(), &template_id.to_le_bytes()],
+        bump,
+    )]
+    pub template: Box<Account<'info, MarketTemplate>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_market_template(
+    ctx: Context<CreateMarketTemplate>,
+    template_id: u64,
+    params: MarketParams,
+) -> Result<()> {
+    // Checked again for every market, the central state may change in between
+    validate_market_params(&params, &ctx.accounts.central_state)?;
+    validate_reward_mint(&params, &ctx.accounts.token_mint.key(), &ctx.accounts.reward_mint.key())?;
+
+    let template = &mut ctx.accounts.template;
+    template.bump = ctx.bumps.template;
+    template.creator = ctx.accounts.creator.key();
+    template.template_id = template_id;
+    template.mint = ctx.accounts.token_mint.key();
+    template.reward_mint = ctx.accounts.reward_mint.key();
+    template.params = params;
+    template.next_series_index = 0;
+
+    emit_ts!(MarketTemplateCreatedEvent {
+        template: template.key(),
+        creator: template.creator,
+        template_id: template_id,
+        mint: template.mint,
+        reward_mint: template.reward_mint,
+        params: params,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, StakeDelegationCreatedEvent};
+use crate::state::{EncryptedTokenAccount, StakeDelegation};
+
+pub const STAKE_DELEGATION_SEED: &[u8] = b"stake_delegation";
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct CreateStakeDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = encrypted_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub encrypted_token_account: Box<Account<'info, EncryptedTokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakeDelegation::INIT_SPACE,
+        seeds = [STAKE_DELEGATION_SEED, encrypted_token_account.key().as_ref(), delegate.as_ref()],
+        bump,
+    )]
+    pub delegation: Box<Account<'info, StakeDelegation>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_stake_delegation(
+    ctx: Context<CreateStakeDelegation>,
+    delegate: Pubkey,
+    spending_cap: u64,
+    expires_at: u64,
+    state_nonce: u128,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(expires_at > clock.unix_timestamp as u64, ErrorCode::DelegationExpired);
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.bump = ctx.bumps.delegation;
+    delegation.owner = ctx.accounts.owner.key();
+    delegation.encrypted_token_account = ctx.accounts.encrypted_token_account.key();
+    delegation.delegate = delegate;
+    delegation.spending_cap = spending_cap;
+    delegation.expires_at = expires_at;
+    delegation.state_nonce = state_nonce;
+    delegation.encrypted_state = [[0u8; 32]; 1];
+    delegation.is_initialized = false;
+    delegation.locked = false;
+
+    emit_ts!(StakeDelegationCreatedEvent {
+        delegation: delegation.key(),
+        encrypted_token_account: delegation.encrypted_token_account,
+        owner: delegation.owner,
+        delegate: delegate,
+        spending_cap: spending_cap,
+        expires_at: expires_at,
+    });
+
+    Ok(())
+}
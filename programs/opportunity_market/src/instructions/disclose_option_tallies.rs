@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, OptionTalliesDisclosedError, OptionTalliesDisclosedEvent};
+use crate::instructions::create_market::OPTION_TALLIES_SEED;
+use crate::state::{OpportunityMarket, OptionTallies, MAX_TALLIED_OPTIONS};
+use crate::COMP_DEF_OFFSET_DISCLOSE_OPTION_TALLIES;
+use crate::{ArciumSignerAccount, ID, ID_CONST};
+
+#[queue_computation_accounts("disclose_option_tallies", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct DiscloseOptionTallies<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = market.creator == authority.key()
+            || market.market_authority == Some(authority.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    #[account(
+        mut,
+        seeds = [OPTION_TALLIES_SEED, market.key().as_ref()],
+        bump = option_tallies.bump,
+        constraint = !option_tallies.locked @ ErrorCode::Locked,
+    )]
+    pub option_tallies: Box<Account<'info, OptionTallies>>,
+
+    // Arcium accounts
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = authority,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_DISCLOSE_OPTION_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// Re-encrypts the market's running option tallies to `authorized_reader_pubkey`, so the decision
+// maker can follow the staking while it is still going on. Restricted to the creator and market
+// authority since the pending computation locks the tallies against `sync_option_tally`.
+pub fn disclose_option_tallies(
+    ctx: Context<DiscloseOptionTallies>,
+    computation_offset: u64,
+    authorized_reader_nonce: u128,
+) -> Result<()> {
+    let authorized_reader_pubkey = ctx.accounts.market.authorized_reader_pubkey;
+
+    let option_tallies_key = ctx.accounts.option_tallies.key();
+    let option_tallies_nonce = ctx.accounts.option_tallies.state_nonce;
+    let is_tallies_initialized = ctx.accounts.option_tallies.is_initialized;
+
+    // Lock tallies while MPC computation is pending
+    ctx.accounts.option_tallies.locked = true;
+
+    // Build args for encrypted computation
+    let args = ArgBuilder::new()
+        // Market's running option tallies (Enc<Mxe, OptionTallies>)
+        .plaintext_u128(option_tallies_nonce)
+        .account(option_tallies_key, 8, 32 * MAX_TALLIED_OPTIONS as u32)
+        .plaintext_bool(is_tallies_initialized)
+
+        // Authorized reader context (Shared)
+        .x25519_pubkey(authorized_reader_pubkey)
+        .plaintext_u128(authorized_reader_nonce)
+        .build();
+
+    // Queue computation with callback
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![DiscloseOptionTalliesCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: option_tallies_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+#[callback_accounts("disclose_option_tallies")]
+#[derive(Accounts)]
+pub struct DiscloseOptionTalliesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_DISCLOSE_OPTION_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    // Callback accounts
+    #[account(mut)]
+    pub option_tallies: Account<'info, OptionTallies>,
+}
+
+pub fn disclose_option_tallies_callback(
+    ctx: Context<DiscloseOptionTalliesCallback>,
+    output: SignedComputationOutputs<DiscloseOptionTalliesOutput>,
+) -> Result<()> {
+    // Unlock tallies
+    ctx.accounts.option_tallies.locked = false;
+
+    let disclosed = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(DiscloseOptionTalliesOutput { field_0 }) => field_0,
+        Err(_) => {
+            emit_ts!(OptionTalliesDisclosedError {
+                market: ctx.accounts.option_tallies.market,
+            });
+            return Ok(());
+        }
+    };
+
+    let clock = Clock::get()?;
+    let tallies = &mut ctx.accounts.option_tallies;
+    tallies.state_nonce_disclosure = disclosed.nonce;
+    tallies.encrypted_state_disclosure = disclosed.ciphertexts;
+    tallies.disclosed_at_timestamp = Some(clock.unix_timestamp as u64);
+
+    emit_ts!(OptionTalliesDisclosedEvent {
+        market: tallies.market,
+        encrypted_tallies: disclosed.ciphertexts,
+        nonce: disclosed.nonce,
+    });
+
+    Ok(())
+}
@@ -33,8 +33,10 @@ pub struct DoUnstakeEarly<'info> {
         mut,
         seeds = [SHARE_ACCOUNT_SEED, share_account_owner.as_ref(), market.key().as_ref(), &share_account_id.to_le_bytes()],
         bump = share_account.bump,
+        constraint = share_account.staked_at_timestamp.is_some() @ ErrorCode::StakingNotActive,
         constraint = share_account.unstaked_at_timestamp.is_none() @ ErrorCode::AlreadyUnstaked,
-        constraint = share_account.unstakeable_at_timestamp.is_some() @ ErrorCode::UnstakeNotInitiated,
+        constraint = market.cancelled
+            || share_account.unstakeable_at_timestamp.is_some() @ ErrorCode::UnstakeNotInitiated,
         constraint = !share_account.locked @ ErrorCode::Locked,
     )]
     pub share_account: Box<Account<'info, ShareAccount>>,
@@ -82,25 +84,28 @@ pub fn do_unstake_early(
 
     require!(ctx.accounts.market.mint.eq(&ctx.accounts.user_eta.token_mint), ErrorCode::InvalidMint);
 
-    // Enforce staking period is still active
+    // Cancelled markets refund stakes right away, without delay or staking window
     let market = &ctx.accounts.market;
-    let open_timestamp = market.open_timestamp.ok_or_else(|| ErrorCode::MarketNotOpen)?;
-    let clock = Clock::get()?;
-    let current_timestamp = clock.unix_timestamp as u64;
-    let stake_end_timestamp = open_timestamp + market.time_to_stake;
-
-    require!(
-        current_timestamp <= stake_end_timestamp,
-        ErrorCode::StakingNotActive
-    );
-
-    // Enforce unstake delay has passed
-    let unstakeable_at = ctx.accounts.share_account.unstakeable_at_timestamp
-        .ok_or_else(|| ErrorCode::UnstakeNotInitiated)?;
-    require!(
-        current_timestamp >= unstakeable_at,
-        ErrorCode::UnstakeDelayNotMet
-    );
+    if !market.cancelled {
+        // Enforce staking period is still active
+        let open_timestamp = market.open_timestamp.ok_or_else(|| ErrorCode::MarketNotOpen)?;
+        let clock = Clock::get()?;
+        let current_timestamp = clock.unix_timestamp as u64;
+        let stake_end_timestamp = open_timestamp + market.time_to_stake;
+
+        require!(
+            current_timestamp <= stake_end_timestamp,
+            ErrorCode::StakingNotActive
+        );
+
+        // Enforce unstake delay has passed
+        let unstakeable_at = ctx.accounts.share_account.unstakeable_at_timestamp
+            .ok_or_else(|| ErrorCode::UnstakeNotInitiated)?;
+        require!(
+            current_timestamp >= unstakeable_at,
+            ErrorCode::UnstakeDelayNotMet
+        );
+    }
 
     let share_account_key = ctx.accounts.share_account.key();
     let share_account_nonce = ctx.accounts.share_account.state_nonce;
@@ -108,18 +113,17 @@ pub fn do_unstake_early(
     let user_eta_key = ctx.accounts.user_eta.key();
     let user_eta_nonce = ctx.accounts.user_eta.state_nonce;
 
-    // Lock both accounts while MPC computation is pending
+    // Lock accounts while MPC computation is pending
     ctx.accounts.user_eta.locked = true;
     ctx.accounts.share_account.locked = true;
 
-
     // Build args for encrypted computation
     let is_eta_initialized = ctx.accounts.user_eta.is_initialized;
     let args = ArgBuilder::new()
         // Share account encrypted state (Enc<Shared, SharePurchase>)
         .x25519_pubkey(user_pubkey)
         .plaintext_u128(share_account_nonce)
-        .account(share_account_key, 8, 32 * 2)
+        .account(share_account_key, 8, 32 * 3)
 
         // User ETA encrypted state (Enc<Shared, EncryptedTokenBalance>)
         .x25519_pubkey(user_pubkey)
@@ -189,7 +193,7 @@ pub fn unstake_early_callback(
     ctx.accounts.share_account.locked = false;
 
     // Verify output - on error, emit event and return Ok so unlocks persist
-    let new_user_balance = match output.verify_output(
+    let res = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
     ) {
@@ -202,9 +206,20 @@ pub fn unstake_early_callback(
         }
     };
 
+    // Refund would overflow the ETA balance, shares stay staked
+    if res.field_0 {
+        emit_ts!(UnstakedError {
+            user: ctx.accounts.user_eta.owner,
+        });
+        return Ok(());
+    }
+
+    let new_user_balance = res.field_1;
+
     // Mark share account as unstaked
     let clock = Clock::get()?;
     ctx.accounts.share_account.unstaked_at_timestamp = Some(clock.unix_timestamp as u64);
+    ctx.accounts.share_account.tally_pending = true;
 
     // Update user ETA with refunded balance
     ctx.accounts.user_eta.state_nonce = new_user_balance.nonce;
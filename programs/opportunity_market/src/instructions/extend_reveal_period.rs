@@ -11,6 +11,7 @@ pub struct ExtendRevealPeriod<'info> {
         mut,
         constraint = market.creator == authority.key()
             || market.market_authority == Some(authority.key()) @ ErrorCode::Unauthorized,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
     )]
     pub market: Account<'info, OpportunityMarket>,
 }
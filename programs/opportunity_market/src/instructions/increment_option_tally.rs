@@ -15,6 +15,9 @@ pub struct IncrementOptionTally<'info> {
     /// CHECK: this is a permissionless operation
     pub owner: UncheckedAccount<'info>,
 
+    #[account(
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
     pub market: Account<'info, OpportunityMarket>,
 
     #[account(
@@ -38,10 +41,35 @@ pub struct IncrementOptionTally<'info> {
 
 pub fn increment_option_tally(ctx: Context<IncrementOptionTally>, option_index: u16, _share_account_id: u32) -> Result<()> {
     // Check that we are within the reveal window
-    let market = &ctx.accounts.market;
-    let open_timestamp = market.open_timestamp.ok_or(ErrorCode::MarketNotOpen)?;
     let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp as u64;
+    require!(
+        is_within_reveal_window(&ctx.accounts.market, clock.unix_timestamp as u64)?,
+        ErrorCode::MarketNotResolved
+    );
+
+    let revealed_amount = ctx.accounts.share_account.revealed_amount.ok_or(ErrorCode::NotRevealed)?;
+    let user_score = tally_share_account(
+        &ctx.accounts.market,
+        &mut ctx.accounts.share_account,
+        &mut ctx.accounts.option,
+    )?;
+
+    emit_ts!(TallyIncrementedEvent {
+        owner: ctx.accounts.owner.key(),
+        market: ctx.accounts.market.key(),
+        share_account: ctx.accounts.share_account.key(),
+        option: option_index,
+        revealed_amount: revealed_amount,
+        user_score: user_score,
+    });
+
+    Ok(())
+}
+
+// Reveals and tallies are only accepted from the end of staking until the end of the reveal
+// period (exclusive), so the option totals are final once rewards can be claimed
+pub fn is_within_reveal_window(market: &OpportunityMarket, current_time: u64) -> Result<bool> {
+    let open_timestamp = market.open_timestamp.ok_or(ErrorCode::MarketNotOpen)?;
 
     let reveal_start = open_timestamp
         .checked_add(market.time_to_stake)
@@ -50,25 +78,38 @@ pub fn increment_option_tally(ctx: Context<IncrementOptionTally>, option_index:
         .checked_add(market.time_to_reveal)
         .ok_or(ErrorCode::Overflow)?;
 
-    require!(
-        current_time >= reveal_start && current_time <= reveal_end,
-        ErrorCode::MarketNotResolved
-    );
+    Ok(current_time >= reveal_start && current_time < reveal_end)
+}
 
-    let revealed_amount = ctx.accounts.share_account.revealed_amount.ok_or(ErrorCode::NotRevealed)?;
+// Adds a revealed share account to its option's `total_shares` and `total_score` and
+// marks it tallied. Shared by `increment_option_tally` and the reveal callback. Returns the score.
+// Nothing is written unless it succeeds, so the reveal callback can leave a failed tally for
+// `increment_option_tally`.
+pub fn tally_share_account(
+    market: &OpportunityMarket,
+    share_account: &mut ShareAccount,
+    option: &mut OpportunityMarketOption,
+) -> Result<u64> {
+    let open_timestamp = market.open_timestamp.ok_or(ErrorCode::MarketNotOpen)?;
+    let reveal_start = open_timestamp
+        .checked_add(market.time_to_stake)
+        .ok_or(ErrorCode::Overflow)?;
 
-    // Initialize total_shares to 0 if None, then add revealed_amount
-    let current_total = ctx.accounts.option.total_shares.unwrap_or(0);
-    ctx.accounts.option.total_shares = Some(
-        current_total
-            .checked_add(revealed_amount)
-            .ok_or(ErrorCode::Overflow)?
+    let revealed_amount = share_account.revealed_amount.ok_or(ErrorCode::NotRevealed)?;
+    require!(
+        share_account.revealed_option == Some(option.index),
+        ErrorCode::InvalidOptionIndex
     );
 
-    let share_account = &ctx.accounts.share_account;
+    // Initialize total_shares to 0 if None, then add revealed_amount
+    let current_total = option.total_shares.unwrap_or(0);
+    let new_total_shares = current_total
+        .checked_add(revealed_amount)
+        .ok_or(ErrorCode::Overflow)?;
 
-    let staked_at_timestamp = share_account.staked_at_timestamp
-        .ok_or(ErrorCode::StakingNotActive)?;
+    // Stake-weighted average entry time across the initial stake and any top-ups
+    let staked_at_timestamp = share_account.revealed_entry_timestamp
+        .ok_or(ErrorCode::NotRevealed)?;
     let stake_end = share_account.unstaked_at_timestamp
         .unwrap_or(reveal_start);
 
@@ -77,26 +118,21 @@ pub fn increment_option_tally(ctx: Context<IncrementOptionTally>, option_index:
         stake_end,
         staked_at_timestamp,
         revealed_amount,
+        market.earliness_cutoff_seconds,
+        &market.scoring_config,
     )?;
 
-    let current_total_score = ctx.accounts.option.total_score.unwrap_or(0);
+    let current_total_score = option.total_score.unwrap_or(0);
+    let new_total_score = current_total_score
+        .checked_add(user_score)
+        .ok_or(ErrorCode::Overflow)?;
 
-    ctx.accounts.option.total_score = Some(
-        current_total_score.checked_add(user_score).ok_or(ErrorCode::Overflow)?
-    );
+    option.total_shares = Some(new_total_shares);
+    option.total_score = Some(new_total_score);
 
     // Store the user's score on their share account for yield calculation
-    ctx.accounts.share_account.revealed_score = Some(user_score);
-    ctx.accounts.share_account.total_incremented = true;
-
-    emit_ts!(TallyIncrementedEvent {
-        owner: ctx.accounts.owner.key(),
-        market: ctx.accounts.market.key(),
-        share_account: ctx.accounts.share_account.key(),
-        option: option_index,
-        revealed_amount: revealed_amount,
-        user_score: user_score,
-    });
+    share_account.revealed_score = Some(user_score);
+    share_account.total_incremented = true;
 
-    Ok(())
+    Ok(user_score)
 }
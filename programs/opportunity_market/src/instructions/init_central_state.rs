@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
-use crate::state::CentralState;
+use crate::error::ErrorCode;
+use crate::score::validate_earliness_cutoff;
+use crate::state::{CentralState, MAX_PROTOCOL_FEE_BPS, MIN_REWARD_RECLAIM_GRACE_SECONDS};
 
 #[derive(Accounts)]
 pub struct InitCentralState<'info> {
@@ -23,12 +25,25 @@ pub fn init_central_state(
     ctx: Context<InitCentralState>,
     earliness_cutoff_seconds: u64,
     min_option_deposit: u64,
+    reward_reclaim_grace_seconds: u64,
+    protocol_fee_bps: u16,
+    treasury: Pubkey,
 ) -> Result<()> {
+    validate_earliness_cutoff(earliness_cutoff_seconds)?;
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, ErrorCode::InvalidProtocolFee);
+    require!(
+        reward_reclaim_grace_seconds >= MIN_REWARD_RECLAIM_GRACE_SECONDS,
+        ErrorCode::InvalidReclaimGracePeriod
+    );
+
     let central_state = &mut ctx.accounts.central_state;
     central_state.bump = ctx.bumps.central_state;
     central_state.authority = ctx.accounts.payer.key();
     central_state.earliness_cutoff_seconds = earliness_cutoff_seconds;
     central_state.min_option_deposit = min_option_deposit;
+    central_state.reward_reclaim_grace_seconds = reward_reclaim_grace_seconds;
+    central_state.protocol_fee_bps = protocol_fee_bps;
+    central_state.treasury = treasury;
 
     Ok(())
 }
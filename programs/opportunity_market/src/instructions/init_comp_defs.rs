@@ -84,6 +84,45 @@ pub fn unwrap_encrypted_tokens_comp_def(ctx: Context<UnwrapEncryptedTokensCompDe
     Ok(())
 }
 
+#[init_computation_definition_accounts("transfer_encrypted_tokens", payer)]
+#[derive(Accounts)]
+pub struct TransferEncryptedTokensCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn transfer_encrypted_tokens_comp_def(ctx: Context<TransferEncryptedTokensCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/transfer_encrypted_tokens.arcis".to_string(),
+                hash: circuit_hash!("transfer_encrypted_tokens"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
+
 #[init_computation_definition_accounts("buy_opportunity_market_shares", payer)]
 #[derive(Accounts)]
 pub struct BuyOpportunityMarketSharesCompDef<'info> {
@@ -278,3 +317,198 @@ pub fn close_ephemeral_encrypted_token_account_comp_def(ctx: Context<CloseEpheme
     }
     Ok(())
 }
+
+#[init_computation_definition_accounts("increase_stake", payer)]
+#[derive(Accounts)]
+pub struct IncreaseStakeCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn increase_stake_comp_def(ctx: Context<IncreaseStakeCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/increase_stake.arcis".to_string(),
+                hash: circuit_hash!("increase_stake"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
+
+#[init_computation_definition_accounts("disclose_option_tallies", payer)]
+#[derive(Accounts)]
+pub struct DiscloseOptionTalliesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn disclose_option_tallies_comp_def(ctx: Context<DiscloseOptionTalliesCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/disclose_option_tallies.arcis".to_string(),
+                hash: circuit_hash!("disclose_option_tallies"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
+
+#[init_computation_definition_accounts("sync_option_tally", payer)]
+#[derive(Accounts)]
+pub struct SyncOptionTallyCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn sync_option_tally_comp_def(ctx: Context<SyncOptionTallyCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/sync_option_tally.arcis".to_string(),
+                hash: circuit_hash!("sync_option_tally"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
+
+#[init_computation_definition_accounts("delegated_buy_opportunity_market_shares", payer)]
+#[derive(Accounts)]
+pub struct DelegatedBuyOpportunityMarketSharesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn delegated_buy_opportunity_market_shares_comp_def(ctx: Context<DelegatedBuyOpportunityMarketSharesCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/delegated_buy_opportunity_market_shares.arcis".to_string(),
+                hash: circuit_hash!("delegated_buy_opportunity_market_shares"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
+
+#[init_computation_definition_accounts("delegated_add_option_stake", payer)]
+#[derive(Accounts)]
+pub struct DelegatedAddOptionStakeCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    /// CHECK: address_lookup_table, checked by arcium program.
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    pub address_lookup_table: UncheckedAccount<'info>,
+    /// CHECK: lut_program is the Address Lookup Table program.
+    #[account(address = LUT_PROGRAM_ID)]
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn delegated_add_option_stake_comp_def(ctx: Context<DelegatedAddOptionStakeCompDef>) -> Result<()> {
+    #[cfg(feature = "hosted-compdefs")]
+    {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: "https://pub-f4c38b2a6f20431a8856eb3b17373497.r2.dev/delegated_add_option_stake.arcis".to_string(),
+                hash: circuit_hash!("delegated_add_option_stake"),
+            })),
+            None,
+        )?;
+    }
+    #[cfg(not(feature = "hosted-compdefs"))]
+    {
+        init_comp_def(ctx.accounts, None, None)?;
+    }
+    Ok(())
+}
@@ -42,6 +42,7 @@ pub fn init_encrypted_token_account(
     eta.encrypted_state = [[0u8; 32]; 1];
     eta.rent_payer = None;
     eta.is_initialized = false;
+    eta.incoming_transfers_blocked_until = 0;
 
     emit_ts!(EncryptedTokenAccountInitializedEvent {
         encrypted_token_account: eta.key(),
@@ -58,6 +58,7 @@ pub fn init_ephemeral_encrypted_token_account(
     // Track who paid rent so they can be refunded when closing
     eta.rent_payer = Some(ctx.accounts.signer.key());
     eta.is_initialized = false;
+    eta.incoming_transfers_blocked_until = 0;
 
     emit_ts!(EphemeralEncryptedTokenAccountInitializedEvent {
         encrypted_token_account: eta.key(),
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
 use crate::events::{emit_ts, ShareAccountInitializedEvent};
-use crate::state::{OpportunityMarket, ShareAccount};
+use crate::state::{OpportunityMarket, ShareAccount, StakeDelegation};
 use crate::instructions::stake::SHARE_ACCOUNT_SEED;
 
 #[derive(Accounts)]
@@ -10,6 +11,10 @@ pub struct InitShareAccount<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        mut,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
     pub market: Account<'info, OpportunityMarket>,
 
     #[account(
@@ -24,24 +29,54 @@ pub struct InitShareAccount<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn init_share_account(
-    ctx: Context<InitShareAccount>,
+// Initializes a share account of `owner` on the market and counts it as open, until the staking
+// period ends. Share accounts created through a delegation need it unexpired.
+pub fn initialize_share_account(
+    share_account: &mut Account<ShareAccount>,
+    bump: u8,
+    owner: Pubkey,
+    market: &mut Account<OpportunityMarket>,
     state_nonce: u128,
-    _share_account_id: u32,
+    delegation: Option<&StakeDelegation>,
 ) -> Result<()> {
-    let share_account = &mut ctx.accounts.share_account;
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+
+    // A share account opened after staking could never be staked, it would only hold up closing
+    // the market
+    if let Some(open_timestamp) = market.open_timestamp {
+        let stake_end = open_timestamp
+            .checked_add(market.time_to_stake)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(current_time <= stake_end, ErrorCode::StakingNotActive);
+    }
 
-    share_account.bump = ctx.bumps.share_account;
-    share_account.owner = ctx.accounts.signer.key();
-    share_account.market = ctx.accounts.market.key();
+    if let Some(delegation) = delegation {
+        require!(
+            current_time < delegation.expires_at,
+            ErrorCode::DelegationExpired
+        );
+    }
+
+    share_account.bump = bump;
+    share_account.owner = owner;
+    share_account.market = market.key();
     share_account.state_nonce = state_nonce;
     share_account.state_nonce_disclosure = 0; // initialized later TODO: why?
-    share_account.encrypted_state = [[0u8; 32]; 2];
-    share_account.encrypted_state_disclosure = [[0u8; 32]; 2];
+    share_account.encrypted_state = [[0u8; 32]; 3];
+    share_account.encrypted_state_disclosure = [[0u8; 32]; 3];
     share_account.revealed_amount = None;
     share_account.revealed_option = None;
+    share_account.revealed_entry_timestamp = None;
+    share_account.is_tallied_state_initialized = false;
+    share_account.tally_pending = false;
     share_account.locked = false;
 
+    market.open_share_accounts = market
+        .open_share_accounts
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
     emit_ts!(ShareAccountInitializedEvent {
         share_account: share_account.key(),
         owner: share_account.owner,
@@ -50,3 +85,18 @@ pub fn init_share_account(
 
     Ok(())
 }
+
+pub fn init_share_account(
+    ctx: Context<InitShareAccount>,
+    state_nonce: u128,
+    _share_account_id: u32,
+) -> Result<()> {
+    initialize_share_account(
+        &mut ctx.accounts.share_account,
+        ctx.bumps.share_account,
+        ctx.accounts.signer.key(),
+        &mut ctx.accounts.market,
+        state_nonce,
+        None,
+    )
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::instructions::create_stake_delegation::STAKE_DELEGATION_SEED;
+use crate::instructions::init_share_account::initialize_share_account;
+use crate::instructions::stake::SHARE_ACCOUNT_SEED;
+use crate::state::{OpportunityMarket, ShareAccount, StakeDelegation};
+
+#[derive(Accounts)]
+#[instruction(state_nonce: u128, share_account_id: u32)]
+pub struct InitShareAccountAsDelegate<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_DELEGATION_SEED, delegation.encrypted_token_account.as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+    )]
+    pub delegation: Box<Account<'info, StakeDelegation>>,
+
+    #[account(
+        mut,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    #[account(
+        init,
+        payer = delegate,
+        space = 8 + ShareAccount::INIT_SPACE,
+        seeds = [SHARE_ACCOUNT_SEED, delegation.owner.as_ref(), market.key().as_ref(), &share_account_id.to_le_bytes()],
+        bump,
+    )]
+    pub share_account: Box<Account<'info, ShareAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Share account of the delegation's owner, paid for by the delegate. The rent goes to the
+// owner when the share account is closed.
+pub fn init_share_account_as_delegate(
+    ctx: Context<InitShareAccountAsDelegate>,
+    state_nonce: u128,
+    _share_account_id: u32,
+) -> Result<()> {
+    initialize_share_account(
+        &mut ctx.accounts.share_account,
+        ctx.bumps.share_account,
+        ctx.accounts.delegation.owner,
+        &mut ctx.accounts.market,
+        state_nonce,
+        Some(&ctx.accounts.delegation),
+    )
+}
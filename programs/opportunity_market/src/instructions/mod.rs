@@ -1,48 +1,88 @@
+pub mod activate_market;
 pub mod add_market_option;
 pub mod add_market_option_as_creator;
+pub mod add_market_option_as_delegate;
 pub mod stake;
+pub mod stake_as_delegate;
 pub mod claim_pending_deposit;
 pub mod close_ephemeral_encrypted_token_account;
 pub mod unwrap_encrypted_tokens;
+pub mod transfer_encrypted_tokens;
+pub mod close_market;
+pub mod close_market_option;
+pub mod close_market_sponsor;
 pub mod close_share_account;
+pub mod cancel_market;
+pub mod claim_protocol_fees;
 pub mod create_market;
+pub mod create_market_from_template;
+pub mod create_market_template;
+pub mod create_stake_delegation;
+pub mod disclose_option_tallies;
 pub mod extend_reveal_period;
 pub mod init_central_state;
 pub mod init_comp_defs;
+pub mod increase_stake;
 pub mod increment_option_tally;
 pub mod init_share_account;
+pub mod init_share_account_as_delegate;
 pub mod init_encrypted_token_account;
 pub mod init_ephemeral_encrypted_token_account;
 pub mod init_token_vault;
 pub mod wrap_encrypted_tokens;
 pub mod open_market;
+pub mod reclaim_market_rewards;
+pub mod refund_sponsor;
 pub mod reveal_shares;
+pub mod revoke_stake_delegation;
 pub mod select_option;
+pub mod sponsor_market;
+pub mod sync_option_tally;
 pub mod transfer_central_state_authority;
 pub mod unstake_early;
 pub mod do_unstake_early;
 pub mod update_central_state;
 
+pub use activate_market::*;
 pub use add_market_option::*;
 pub use add_market_option_as_creator::*;
+pub use add_market_option_as_delegate::*;
 pub use stake::*;
+pub use stake_as_delegate::*;
 pub use claim_pending_deposit::*;
 pub use close_ephemeral_encrypted_token_account::*;
 pub use unwrap_encrypted_tokens::*;
+pub use transfer_encrypted_tokens::*;
+pub use close_market::*;
+pub use close_market_option::*;
+pub use close_market_sponsor::*;
 pub use close_share_account::*;
+pub use cancel_market::*;
+pub use claim_protocol_fees::*;
 pub use create_market::*;
+pub use create_market_from_template::*;
+pub use create_market_template::*;
+pub use create_stake_delegation::*;
+pub use disclose_option_tallies::*;
 pub use extend_reveal_period::*;
 pub use init_central_state::*;
 pub use init_comp_defs::*;
+pub use increase_stake::*;
 pub use increment_option_tally::*;
 pub use init_share_account::*;
+pub use init_share_account_as_delegate::*;
 pub use init_encrypted_token_account::*;
 pub use init_ephemeral_encrypted_token_account::*;
 pub use init_token_vault::*;
 pub use wrap_encrypted_tokens::*;
 pub use open_market::*;
+pub use reclaim_market_rewards::*;
+pub use refund_sponsor::*;
 pub use reveal_shares::*;
+pub use revoke_stake_delegation::*;
 pub use select_option::*;
+pub use sponsor_market::*;
+pub use sync_option_tally::*;
 pub use transfer_central_state_authority::*;
 pub use unstake_early::*;
 pub use do_unstake_early::*;
@@ -12,15 +12,16 @@ pub struct OpenMarket<'info> {
         mut,
         has_one = creator @ ErrorCode::Unauthorized,
         constraint = market.open_timestamp.is_none() @ ErrorCode::MarketAlreadyOpen,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
     )]
     pub market: Account<'info, OpportunityMarket>,
 
-    #[account(address = market.mint)]
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
 
     /// Market's ATA holding reward tokens
     #[account(
-        associated_token::mint = token_mint,
+        associated_token::mint = reward_mint,
         associated_token::authority = market,
         associated_token::token_program = token_program,
     )]
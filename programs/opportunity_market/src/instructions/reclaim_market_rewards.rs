@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketRewardsReclaimedEvent};
+use crate::instructions::refund_sponsor::sponsorships_refundable;
+use crate::instructions::reveal_shares::require_reveals_settled;
+use crate::state::{OpportunityMarket, OpportunityMarketOption};
+
+// Remaining accounts: the option account of every entry in `market.winning_options`, in order
+#[derive(Accounts)]
+pub struct ReclaimMarketRewards<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        constraint = !market.rewards_reclaimed @ ErrorCode::RewardsAlreadyReclaimed,
+    )]
+    pub market: Account<'info, OpportunityMarket>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Market's ATA holding reward tokens
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Creator's token account to receive the remaining rewards
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = creator,
+        token::token_program = token_program,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Part of the reward pool owed to winning options that have a non-zero tally.
+// `winning_option_accounts` must be the winning options' accounts in `market.winning_options` order.
+pub fn allocated_rewards(
+    market: &OpportunityMarket,
+    market_key: &Pubkey,
+    winning_option_accounts: &[AccountInfo],
+) -> Result<u64> {
+    require!(
+        winning_option_accounts.len() == market.winning_options.len(),
+        ErrorCode::InvalidOptionIndex
+    );
+
+    let reward_pool = (market.reward_amount as u128)
+        .checked_add(market.total_slashed as u128)
+        .ok_or(ErrorCode::Overflow)?;
+    let total_weight: u128 = market.winning_options.iter().map(|w| w.weight as u128).sum();
+
+    let mut allocated: u128 = 0;
+    for (winner, info) in market.winning_options.iter().zip(winning_option_accounts) {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidOptionIndex);
+        let option = OpportunityMarketOption::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(
+            &[b"option", market_key.as_ref(), &winner.option_index.to_le_bytes(), &[option.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidOptionIndex)?;
+        require_keys_eq!(info.key(), expected, ErrorCode::InvalidOptionIndex);
+
+        // Nobody can claim an option's slice without a tallied score
+        if option.total_score.unwrap_or(0) == 0 {
+            continue;
+        }
+        // Same rounding as `close_share_account`
+        let option_reward = reward_pool
+            .checked_mul(winner.weight as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_div(total_weight)
+            .ok_or(ErrorCode::Overflow)?;
+        allocated = allocated.checked_add(option_reward).ok_or(ErrorCode::Overflow)?;
+    }
+
+    Ok(allocated as u64)
+}
+
+pub fn reclaim_market_rewards(ctx: Context<ReclaimMarketRewards>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let allocated = allocated_rewards(&ctx.accounts.market, &market_key, ctx.remaining_accounts)?;
+    let market = &mut ctx.accounts.market;
+
+    let open_timestamp = market.open_timestamp.ok_or(ErrorCode::MarketNotOpen)?;
+    let reclaimable_at = open_timestamp
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .and_then(|t| t.checked_add(market.reward_reclaim_grace_seconds))
+        .ok_or(ErrorCode::Overflow)?;
+
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    require!(current_time >= reclaimable_at, ErrorCode::ReclaimGracePeriodNotOver);
+    require_reveals_settled(market, current_time)?;
+
+    // Tallies and slashing are final after the reveal period. Only the part of the reward pool
+    // nobody can claim goes back (no winner selected, winning options without a tally), tallied
+    // winners keep claiming their slice. Unclaimed slices and protocol fees stay in the ATA.
+    let mut unallocated = market
+        .reward_amount
+        .checked_add(market.total_slashed)
+        .and_then(|pool| pool.checked_sub(allocated))
+        .ok_or(ErrorCode::Overflow)?;
+    // Without a winner, sponsorships not refunded yet stay in the ATA for `refund_sponsor`
+    if sponsorships_refundable(market, current_time)? {
+        unallocated = unallocated
+            .checked_sub(market.total_sponsored)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    let unclaimed = allocated.saturating_sub(market.total_rewards_claimed);
+    let remainder = unallocated.min(
+        ctx.accounts
+            .market_token_ata
+            .amount
+            .saturating_sub(market.protocol_fees_accrued)
+            .saturating_sub(unclaimed),
+    );
+
+    market.rewards_reclaimed = true;
+
+    if remainder > 0 {
+        let seed_key = market.seed_key();
+        let index_bytes = market.index.to_le_bytes();
+        let bump = market.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"opportunity_market",
+            seed_key.as_ref(),
+            &index_bytes,
+            &[bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.market_token_ata.to_account_info(),
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            remainder,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
+
+    emit_ts!(MarketRewardsReclaimedEvent {
+        market: market.key(),
+        creator: market.creator,
+        amount: remainder,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, SponsorRefundedEvent};
+use crate::instructions::sponsor_market::MARKET_SPONSOR_SEED;
+use crate::state::{MarketSponsor, OpportunityMarket};
+
+#[derive(Accounts)]
+pub struct RefundSponsor<'info> {
+    /// Anyone can crank a refund, tokens and rent always go to the sponsor
+    pub signer: Signer<'info>,
+
+    /// CHECK: Receives the sponsorship account's rent, checked against `market_sponsor`
+    #[account(mut, address = market_sponsor.sponsor)]
+    pub sponsor: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SPONSOR_SEED, market.key().as_ref(), sponsor.key().as_ref()],
+        bump = market_sponsor.bump,
+        close = sponsor,
+    )]
+    pub market_sponsor: Box<Account<'info, MarketSponsor>>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Market's ATA holding reward tokens
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sponsor's ATA, receives the refund
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = sponsor,
+        associated_token::token_program = token_program,
+    )]
+    pub sponsor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Whether sponsorships can be taken back: the market never opened, was cancelled, or reached
+/// the end of its reveal period without a winner, so it will never pay them out.
+pub fn sponsorships_refundable(market: &OpportunityMarket, current_time: u64) -> Result<bool> {
+    let Some(open_timestamp) = market.open_timestamp else {
+        return Ok(true);
+    };
+    if market.cancelled {
+        return Ok(true);
+    }
+
+    let reveal_end = open_timestamp
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .ok_or(ErrorCode::Overflow)?;
+    Ok(market.selected_option.is_none() && current_time >= reveal_end)
+}
+
+/// Returns a sponsor's contribution once the market can no longer pay it out, see
+/// `sponsorships_refundable`.
+pub fn refund_sponsor(ctx: Context<RefundSponsor>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    require!(
+        sponsorships_refundable(market, current_time)?,
+        ErrorCode::SponsorshipNotRefundable
+    );
+
+    let amount = ctx.accounts.market_sponsor.amount;
+
+    let seed_key = market.seed_key();
+    let index_bytes = market.index.to_le_bytes();
+    let bump = market.bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"opportunity_market",
+        seed_key.as_ref(),
+        &index_bytes,
+        &[bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.market_token_ata.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.sponsor_token_account.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    market.total_sponsored = market
+        .total_sponsored
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    market.open_sponsorships = market
+        .open_sponsorships
+        .checked_sub(1)
+        .ok_or(ErrorCode::Overflow)?;
+    // A cancelled market pays no rewards. Unopened markets still have to be funded and markets
+    // without a winner leave `reward_amount` for `reclaim_market_rewards`, both shrink with it.
+    if !market.cancelled {
+        market.reward_amount = market
+            .reward_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    emit_ts!(SponsorRefundedEvent {
+        market: market.key(),
+        sponsor: ctx.accounts.sponsor.key(),
+        amount: amount,
+        reward_amount: market.reward_amount,
+    });
+
+    // Sponsorship account will be closed automatically via the close constraint
+    Ok(())
+}
@@ -1,14 +1,28 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 use crate::error::ErrorCode;
-use crate::events::{emit_ts, StakeRevealedError, StakeRevealedEvent};
+use crate::events::{
+    emit_ts, StakeRevealedError, StakeRevealedEvent, TallyIncrementedError, TallyIncrementedEvent,
+};
+use crate::instructions::increment_option_tally::{is_within_reveal_window, tally_share_account};
+use crate::instructions::init_token_vault::TOKEN_VAULT_SEED;
 use crate::instructions::stake::SHARE_ACCOUNT_SEED;
-use crate::state::{OpportunityMarket, ShareAccount, EncryptedTokenAccount};
+use crate::state::{
+    EncryptedTokenAccount, OpportunityMarket, OpportunityMarketOption, ShareAccount, TokenVault,
+    MAX_WINNING_OPTIONS,
+};
 use crate::COMP_DEF_OFFSET_REVEAL_SHARES;
 use crate::{ArciumSignerAccount, ID, ID_CONST};
 
+// Seconds after reveal end after which reveals still pending no longer hold up payouts. A reveal
+// computation that never calls back would otherwise freeze every claim from the slashed pool.
+pub const PENDING_REVEAL_TIMEOUT_SECONDS: u64 = 3_600;
+
 #[queue_computation_accounts("reveal_shares", signer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64, share_account_id: u32)]
@@ -19,23 +33,66 @@ pub struct RevealShares<'info> {
     /// CHECK: Any account, this operation is permissionless.
     pub owner: UncheckedAccount<'info>,
 
+    #[account(
+        mut,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+    )]
     pub market: Box<Account<'info, OpportunityMarket>>,
 
     #[account(
         mut,
         seeds = [SHARE_ACCOUNT_SEED, owner.key().as_ref(), market.key().as_ref(), &share_account_id.to_le_bytes()],
         bump = share_account.bump,
+        constraint = share_account.staked_at_timestamp.is_some() @ ErrorCode::StakingNotActive,
         constraint = share_account.revealed_amount.is_none() @ ErrorCode::AlreadyRevealed,
         constraint = !share_account.locked @ ErrorCode::Locked,
     )]
     pub share_account: Box<Account<'info, ShareAccount>>,
 
+    /// If given, the callback also tallies the share account into this option, provided it is
+    /// the revealed one. Otherwise `increment_option_tally` is still needed.
+    #[account(
+        seeds = [b"option", market.key().as_ref(), &option.index.to_le_bytes()],
+        bump = option.bump,
+    )]
+    pub option: Option<Box<Account<'info, OpportunityMarketOption>>>,
+
     #[account(
         mut,
         constraint = !user_eta.locked @ ErrorCode::Locked,
     )]
     pub user_eta: Box<Account<'info, EncryptedTokenAccount>>,
 
+    #[account(address = market.mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault holding all wrapped tokens, source of slashed stake
+    #[account(
+        seeds = [TOKEN_VAULT_SEED],
+        bump = token_vault.bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenVault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_vault,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Market's ATA, slashed stake is added to the reward pool here. Only needed by markets
+    /// that slash losing stake, those always pay rewards in the staked token.
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
     // Arcium accounts
     #[account(
         init_if_needed,
@@ -72,6 +129,7 @@ pub struct RevealShares<'info> {
 
 // This operation is permissionless:
 // after the staking period has ended and an option has been selected, anyone can reveal anyones vote.
+// The stake is always refunded to the owner, crankers can only credit the owner's regular ETA.
 pub fn reveal_shares(
     ctx: Context<RevealShares>,
     computation_offset: u64,
@@ -81,18 +139,37 @@ pub fn reveal_shares(
 
     require!(ctx.accounts.user_eta.owner.key().eq(&ctx.accounts.owner.key()), ErrorCode::Unauthorized);
     require!(ctx.accounts.market.mint.eq(&ctx.accounts.user_eta.token_mint.key()), ErrorCode::InvalidMint);
+    if ctx.accounts.signer.key() != ctx.accounts.owner.key() {
+        require!(ctx.accounts.user_eta.index == 0, ErrorCode::Unauthorized);
+    }
 
     let market = &ctx.accounts.market;
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp as u64;
 
-    // Check that staking period is over.
+    // Check that staking period is over and the reveal period is not. Later reveals would change
+    // the slashed reward pool while winners are already claiming from it.
     let reveal_start = market
         .open_timestamp
         .ok_or(ErrorCode::MarketNotOpen)?
         .saturating_add(market.time_to_stake);
 
     require!(current_timestamp >= reveal_start, ErrorCode::MarketNotResolved);
+    require!(
+        is_within_reveal_window(market, current_timestamp)?,
+        ErrorCode::RevealPeriodEnded
+    );
+
+    // Slashing needs to know which options won
+    if market.losing_stake_slash_bps > 0 {
+        require!(market.selected_option.is_some(), ErrorCode::MarketNotResolved);
+        require!(ctx.accounts.market_token_ata.is_some(), ErrorCode::MarketTokenAccountRequired);
+    }
+    let mut winning_options = [0u64; MAX_WINNING_OPTIONS];
+    for (slot, winner) in winning_options.iter_mut().zip(market.winning_options.iter()) {
+        *slot = winner.option_index as u64;
+    }
+    let slash_bps = market.losing_stake_slash_bps as u64;
 
     let share_account_key = ctx.accounts.share_account.key();
     let share_account_nonce = ctx.accounts.share_account.state_nonce;
@@ -102,6 +179,10 @@ pub fn reveal_shares(
 
     // Lock ShareAccount while MPC computation is pending
     ctx.accounts.share_account.locked = true;
+    ctx.accounts.market.pending_reveals = ctx.accounts.market
+        .pending_reveals
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
 
     // Lock ETA if going to be modified by callback
     if ctx.accounts.share_account.unstaked_at_timestamp.is_none() {
@@ -115,7 +196,7 @@ pub fn reveal_shares(
         // Share account encrypted state (Enc<Shared, SharePurchase>)
         .x25519_pubkey(user_pubkey)
         .plaintext_u128(share_account_nonce)
-        .account(share_account_key, 8, 32 * 2)
+        .account(share_account_key, 8, 32 * 3)
 
         // User ETA encrypted state (Enc<Shared, EncryptedTokenBalance>)
         .x25519_pubkey(user_pubkey)
@@ -124,6 +205,13 @@ pub fn reveal_shares(
 
         // Is ETA initialized flag
         .plaintext_bool(is_eta_initialized)
+
+        // Winning options (0 = unused slot) and losing stake slash
+        .plaintext_u64(winning_options[0])
+        .plaintext_u64(winning_options[1])
+        .plaintext_u64(winning_options[2])
+        .plaintext_u64(winning_options[3])
+        .plaintext_u64(slash_bps)
         .build();
 
     // Queue computation with callback
@@ -144,6 +232,36 @@ pub fn reveal_shares(
                     pubkey: user_eta_key,
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: ctx.accounts.market.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_vault.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_vault_ata.key(),
+                    is_writable: true,
+                },
+                // The program id stands in for a missing market token account
+                CallbackAccount {
+                    pubkey: ctx.accounts.market_token_ata.as_ref().map_or(ID, |ata| ata.key()),
+                    is_writable: ctx.accounts.market_token_ata.is_some(),
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_mint.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_program.key(),
+                    is_writable: false,
+                },
+                // The program id stands in for a missing option account
+                CallbackAccount {
+                    pubkey: ctx.accounts.option.as_ref().map_or(ID, |option| option.key()),
+                    is_writable: ctx.accounts.option.is_some(),
+                },
             ],
         )?],
         1,
@@ -153,6 +271,25 @@ pub fn reveal_shares(
     Ok(())
 }
 
+// Checks that no reveal can still add slashed stake to the reward pool, or that the pending ones
+// have timed out
+pub fn require_reveals_settled(market: &OpportunityMarket, current_time: u64) -> Result<()> {
+    if market.pending_reveals == 0 {
+        return Ok(());
+    }
+
+    let timeout = market
+        .open_timestamp
+        .ok_or(ErrorCode::MarketNotOpen)?
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .and_then(|t| t.checked_add(PENDING_REVEAL_TIMEOUT_SECONDS))
+        .ok_or(ErrorCode::Overflow)?;
+    require!(current_time >= timeout, ErrorCode::RevealsPending);
+
+    Ok(())
+}
+
 #[callback_accounts("reveal_shares")]
 #[derive(Accounts)]
 pub struct RevealSharesCallback<'info> {
@@ -174,6 +311,17 @@ pub struct RevealSharesCallback<'info> {
     pub share_account: Account<'info, ShareAccount>,
     #[account(mut)]
     pub user_eta: Account<'info, EncryptedTokenAccount>,
+    #[account(mut)]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+    pub token_vault: Box<Account<'info, TokenVault>>,
+    #[account(mut)]
+    pub token_vault_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub market_token_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(mut)]
+    pub option: Option<Box<Account<'info, OpportunityMarketOption>>>,
 }
 
 pub fn reveal_shares_callback(
@@ -185,6 +333,7 @@ pub fn reveal_shares_callback(
     if ctx.accounts.share_account.unstaked_at_timestamp.is_none() {
         ctx.accounts.user_eta.locked = false;
     }
+    ctx.accounts.market.pending_reveals = ctx.accounts.market.pending_reveals.saturating_sub(1);
 
     // Verify output - on error, emit event and return Ok so unlocks persist
     let res = match output.verify_output(
@@ -200,13 +349,90 @@ pub fn reveal_shares_callback(
         }
     };
 
-    let revealed_amount = res.field_0;
-    let revealed_option = res.field_1;
-    let new_user_balance = res.field_2;
+    // Refund would overflow the ETA balance, nothing is revealed. Already unstaked
+    // shares don't credit the ETA, so the flag does not apply to them.
+    if res.field_0 && ctx.accounts.share_account.unstaked_at_timestamp.is_none() {
+        emit_ts!(StakeRevealedError {
+            user: ctx.accounts.user_eta.owner,
+        });
+        return Ok(());
+    }
+
+    let revealed_amount = res.field_1;
+    let revealed_option = res.field_2;
+    let weighted_staked_at = res.field_3;
+    let new_user_balance = res.field_5;
+
+    // Already unstaked shares were refunded in full, there is nothing left to slash
+    let slashed_amount = if ctx.accounts.share_account.unstaked_at_timestamp.is_none() {
+        res.field_4
+    } else {
+        0
+    };
+
+    // Nothing below may return an error, the unlocks above have to persist.
+    // Reveals are only queued for staked share accounts.
+    let Some(first_staked_at) = ctx.accounts.share_account.staked_at_timestamp else {
+        emit_ts!(StakeRevealedError {
+            user: ctx.accounts.user_eta.owner,
+        });
+        return Ok(());
+    };
+
+    // Move the slashed stake out of the wrapped token pool and into the market's reward pool.
+    // Done before anything is written so a failed transfer leaves the share to be revealed again.
+    if slashed_amount > 0 {
+        let new_total_slashed = ctx.accounts.market.total_slashed.checked_add(slashed_amount);
+        let (Some(market_token_ata), Some(new_total_slashed)) =
+            (ctx.accounts.market_token_ata.as_ref(), new_total_slashed)
+        else {
+            emit_ts!(StakeRevealedError {
+                user: ctx.accounts.user_eta.owner,
+            });
+            return Ok(());
+        };
+        let vault_bump = ctx.accounts.token_vault.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            TOKEN_VAULT_SEED,
+            &[vault_bump],
+        ]];
+
+        let transferred = transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.token_vault_ata.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: market_token_ata.to_account_info(),
+                    authority: ctx.accounts.token_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            slashed_amount,
+            ctx.accounts.token_mint.decimals,
+        );
+        if transferred.is_err() {
+            emit_ts!(StakeRevealedError {
+                user: ctx.accounts.user_eta.owner,
+            });
+            return Ok(());
+        }
+
+        ctx.accounts.market.total_slashed = new_total_slashed;
+    }
+
+    // Stake-weighted average of all stake timestamps, so topping up later
+    // only moves the entry time proportionally to the added amount
+    let entry_timestamp = if revealed_amount > 0 {
+        ((weighted_staked_at / revealed_amount as u128) as u64).max(first_staked_at)
+    } else {
+        first_staked_at
+    };
 
     // Update share account with revealed values
     ctx.accounts.share_account.revealed_amount = Some(revealed_amount);
     ctx.accounts.share_account.revealed_option = Some(revealed_option);
+    ctx.accounts.share_account.revealed_entry_timestamp = Some(entry_timestamp);
 
     // Only credit ETA if shares were not already unstaked
     if ctx.accounts.share_account.unstaked_at_timestamp.is_none() {
@@ -222,7 +448,41 @@ pub fn reveal_shares_callback(
         share_account: ctx.accounts.share_account.key(),
         shares_amount: revealed_amount,
         selected_option: revealed_option,
+        entry_timestamp: entry_timestamp,
+        slashed_amount: slashed_amount,
     });
 
+    // Tally in the same step when the revealed option was passed in, as long as the
+    // reveal window is still open. Otherwise `increment_option_tally` remains available.
+    let in_reveal_window = Clock::get()
+        .ok()
+        .and_then(|clock| is_within_reveal_window(&ctx.accounts.market, clock.unix_timestamp as u64).ok())
+        .unwrap_or(false);
+    if let Some(option) = ctx.accounts.option.as_mut() {
+        if option.index == revealed_option
+            && !ctx.accounts.share_account.total_incremented
+            && in_reveal_window
+        {
+            // A score or total that would overflow leaves the share revealed but untallied,
+            // `increment_option_tally` can still be retried
+            match tally_share_account(&ctx.accounts.market, &mut ctx.accounts.share_account, option) {
+                Ok(user_score) => emit_ts!(TallyIncrementedEvent {
+                    owner: ctx.accounts.share_account.owner,
+                    market: ctx.accounts.share_account.market,
+                    share_account: ctx.accounts.share_account.key(),
+                    option: revealed_option,
+                    revealed_amount: revealed_amount,
+                    user_score: user_score,
+                }),
+                Err(_) => emit_ts!(TallyIncrementedError {
+                    owner: ctx.accounts.share_account.owner,
+                    market: ctx.accounts.share_account.market,
+                    share_account: ctx.accounts.share_account.key(),
+                    option: revealed_option,
+                }),
+            }
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, StakeDelegationRevokedEvent};
+use crate::instructions::create_stake_delegation::STAKE_DELEGATION_SEED;
+use crate::state::StakeDelegation;
+
+#[derive(Accounts)]
+pub struct RevokeStakeDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DELEGATION_SEED, delegation.encrypted_token_account.as_ref(), delegation.delegate.as_ref()],
+        bump = delegation.bump,
+        constraint = delegation.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !delegation.locked @ ErrorCode::Locked,
+        close = owner,
+    )]
+    pub delegation: Account<'info, StakeDelegation>,
+}
+
+// Stakes the delegate already made stay in place, they belong to the owner
+pub fn revoke_stake_delegation(ctx: Context<RevokeStakeDelegation>) -> Result<()> {
+    let delegation = &ctx.accounts.delegation;
+
+    emit_ts!(StakeDelegationRevokedEvent {
+        delegation: delegation.key(),
+        owner: delegation.owner,
+        delegate: delegation.delegate,
+    });
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::error::ErrorCode;
 use crate::events::{emit_ts, OptionSelectedEvent};
-use crate::state::OpportunityMarket;
+use crate::state::{OpportunityMarket, WinningOption, MAX_WINNING_OPTIONS};
 
 #[derive(Accounts)]
 pub struct SelectOption<'info> {
@@ -11,18 +11,39 @@ pub struct SelectOption<'info> {
         mut,
         constraint = market.creator == authority.key()
             || market.market_authority == Some(authority.key()) @ ErrorCode::Unauthorized,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+        // Reveals already slashed stake against the winners, they can't be changed afterwards
+        constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
     )]
     pub market: Account<'info, OpportunityMarket>,
 }
 
 pub fn select_option(ctx: Context<SelectOption>, option_index: u16) -> Result<()> {
+    // Single winner takes the whole reward
+    select_winning_options(ctx, vec![WinningOption { option_index, weight: 1 }])
+}
+
+/// Settles the market with one or more winning options, ranked in the given order.
+/// Each option's stakers share `reward_amount * weight / total_weight`.
+pub fn select_winning_options(ctx: Context<SelectOption>, winning_options: Vec<WinningOption>) -> Result<()> {
     let market = &mut ctx.accounts.market;
 
-    // Enforce option exists
     require!(
-        option_index >= 1 && option_index <= market.total_options,
-        ErrorCode::InvalidOptionIndex
+        !winning_options.is_empty() && winning_options.len() <= MAX_WINNING_OPTIONS,
+        ErrorCode::InvalidWinningOptions
     );
+    for (i, winner) in winning_options.iter().enumerate() {
+        // Enforce option exists
+        require!(
+            winner.option_index >= 1 && winner.option_index <= market.total_options,
+            ErrorCode::InvalidOptionIndex
+        );
+        require!(winner.weight > 0, ErrorCode::InvalidWinningOptions);
+        require!(
+            winning_options[..i].iter().all(|w| w.option_index != winner.option_index),
+            ErrorCode::InvalidWinningOptions
+        );
+    }
 
     // Enforce market is open
     let open_timestamp = market.open_timestamp.ok_or_else(|| ErrorCode::MarketNotOpen)?;
@@ -34,6 +55,13 @@ pub fn select_option(ctx: Context<SelectOption>, option_index: u16) -> Result<()
         ErrorCode::InvalidTimestamp
     );
 
+    // Once the reveal period is over without a winner, sponsors may already have been refunded
+    let reveal_end = open_timestamp
+        .checked_add(market.time_to_stake)
+        .and_then(|t| t.checked_add(market.time_to_reveal))
+        .ok_or(ErrorCode::Overflow)?;
+    require!(current_timestamp < reveal_end, ErrorCode::RevealPeriodEnded);
+
     // Check if closing early is allowed
     let stake_end_timestamp = open_timestamp + market.time_to_stake;
     if !market.allow_closing_early {
@@ -48,13 +76,16 @@ pub fn select_option(ctx: Context<SelectOption>, option_index: u16) -> Result<()
         market.time_to_stake = (current_timestamp - open_timestamp).saturating_sub(1);
     }
 
-    // Save the selected option
-    market.selected_option = Some(option_index);
+    // Save the winners, top-ranked one doubles as the selected option
+    let selected_option = winning_options[0].option_index;
+    market.selected_option = Some(selected_option);
+    market.winning_options = winning_options;
 
     emit_ts!(OptionSelectedEvent {
         market: market.key(),
         authority: ctx.accounts.authority.key(),
-        selected_option: option_index,
+        selected_option: selected_option,
+        winning_options: market.winning_options.clone(),
     });
 
     Ok(())
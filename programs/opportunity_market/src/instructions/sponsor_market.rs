@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, MarketSponsoredEvent};
+use crate::state::{MarketSponsor, OpportunityMarket};
+
+pub const MARKET_SPONSOR_SEED: &[u8] = b"market_sponsor";
+
+#[derive(Accounts)]
+pub struct SponsorMarket<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
+        constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+    )]
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    /// Contributions of `sponsor` to this market, created on the first one
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + MarketSponsor::INIT_SPACE,
+        seeds = [MARKET_SPONSOR_SEED, market.key().as_ref(), sponsor.key().as_ref()],
+        bump,
+    )]
+    pub market_sponsor: Box<Account<'info, MarketSponsor>>,
+
+    #[account(address = market.reward_mint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Market's ATA holding reward tokens
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program,
+    )]
+    pub market_token_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The sponsor's token account (source of the contribution)
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = sponsor,
+        token::token_program = token_program,
+    )]
+    pub sponsor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds `amount` to the market's reward pool and records it against the sponsor.
+pub fn sponsor_market(ctx: Context<SponsorMarket>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroSponsorAmount);
+
+    let market = &mut ctx.accounts.market;
+
+    // Same window as cancellation, so sponsorships of an open market stay refundable
+    // for as long as the market can still be cancelled
+    if let Some(open_timestamp) = market.open_timestamp {
+        let stake_end = open_timestamp
+            .checked_add(market.time_to_stake)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp as u64;
+        require!(current_time <= stake_end, ErrorCode::SponsorshipWindowClosed);
+    }
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.sponsor_token_account.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.market_token_ata.to_account_info(),
+                authority: ctx.accounts.sponsor.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    let market_sponsor = &mut ctx.accounts.market_sponsor;
+    // First contribution of this sponsor, `close_market` waits for the account to be closed
+    if market_sponsor.market == Pubkey::default() {
+        market.open_sponsorships = market
+            .open_sponsorships
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    market_sponsor.bump = ctx.bumps.market_sponsor;
+    market_sponsor.market = market.key();
+    market_sponsor.sponsor = ctx.accounts.sponsor.key();
+    market_sponsor.amount = market_sponsor
+        .amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    market.reward_amount = market
+        .reward_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    market.total_sponsored = market
+        .total_sponsored
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit_ts!(MarketSponsoredEvent {
+        market: market.key(),
+        sponsor: market_sponsor.sponsor,
+        amount: amount,
+        sponsor_total: market_sponsor.amount,
+        reward_amount: market.reward_amount,
+    });
+
+    Ok(())
+}
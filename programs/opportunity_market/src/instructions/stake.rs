@@ -3,8 +3,8 @@ use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 use crate::error::ErrorCode;
-use crate::events::{emit_ts, StakedError, StakedEvent};
-use crate::state::{OpportunityMarket, ShareAccount, EncryptedTokenAccount};
+use crate::events::{emit_ts, DelegatedStakeEvent, StakeErrorReason, StakedError, StakedEvent};
+use crate::state::{OpportunityMarket, ShareAccount, EncryptedTokenAccount, StakeDelegation};
 use crate::COMP_DEF_OFFSET_BUY_OPPORTUNITY_MARKET_SHARES;
 use crate::{ID, ID_CONST, ArciumSignerAccount};
 
@@ -20,6 +20,7 @@ pub struct Stake<'info> {
     #[account(
         constraint = market.open_timestamp.is_some() @ ErrorCode::MarketNotOpen,
         constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
     )]
     pub market: Box<Account<'info, OpportunityMarket>>,
 
@@ -73,22 +74,16 @@ pub struct Stake<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-pub fn stake(
-    ctx: Context<Stake>,
-    computation_offset: u64,
-    _share_account_id: u32,
-    amount_ciphertext: [u8; 32],
-    selected_option_ciphertext: [u8; 32],
-    input_nonce: u128,
-    authorized_reader_nonce: u128,
-) -> Result<()> {
-    let user_pubkey = ctx.accounts.user_eta.user_pubkey;
-
-    require!(ctx.accounts.market.mint.eq(&ctx.accounts.user_eta.token_mint), ErrorCode::InvalidMint);
+// Encrypted input of a stake, by the owner or by a delegate under its own x25519 key
+pub struct StakeInput {
+    pub pubkey: [u8; 32],
+    pub nonce: u128,
+    pub amount_ciphertext: [u8; 32],
+    pub authorized_reader_nonce: u128,
+}
 
-    // Enforce staking period is active
-    let market = &ctx.accounts.market;
-    let authorized_reader_pubkey = market.authorized_reader_pubkey;
+// Current timestamp, provided the market's staking period is active
+pub fn require_staking_active(market: &OpportunityMarket) -> Result<u64> {
     let open_timestamp = market.open_timestamp.ok_or_else(|| ErrorCode::MarketNotOpen)?;
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp as u64;
@@ -99,40 +94,161 @@ pub fn stake(
         ErrorCode::StakingNotActive
     );
 
-    // Capture timestamp when the buy is queued
-    ctx.accounts.share_account.staked_at_timestamp = Some(current_timestamp);
+    Ok(current_timestamp)
+}
+
+// Marks the share account staked at `staked_at` and locks the accounts the stake computation
+// writes. Stakes through a delegation also lock it, and need it unexpired.
+pub fn begin_stake(
+    user_eta: &mut EncryptedTokenAccount,
+    share_account: &mut ShareAccount,
+    delegation: Option<&mut StakeDelegation>,
+    staked_at: u64,
+) -> Result<()> {
+    if let Some(delegation) = delegation {
+        require!(staked_at < delegation.expires_at, ErrorCode::DelegationExpired);
+        delegation.locked = true;
+    }
 
-    // Lock both accounts while MPC computation is pending
-    ctx.accounts.user_eta.locked = true;
-    ctx.accounts.share_account.locked = true;
+    // Capture timestamp when the buy is queued
+    share_account.staked_at_timestamp = Some(staked_at);
 
-    let user_eta_key = ctx.accounts.user_eta.key();
-    let user_eta_nonce = ctx.accounts.user_eta.state_nonce;
+    // Lock accounts while MPC computation is pending
+    user_eta.locked = true;
+    share_account.locked = true;
 
-    // Build args for encrypted computation
-    let args = ArgBuilder::new()
-        // User's trade input (Enc<Shared, BuySharesInput>)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(input_nonce)
-        .encrypted_u64(amount_ciphertext)
-        .encrypted_u16(selected_option_ciphertext)
+    Ok(())
+}
 
+// Args every stake circuit takes after the stake input: the authorized reader context, then the
+// owner's ETA and the share account context, both encrypted to the owner
+pub fn stake_account_args(
+    args: ArgBuilder,
+    market: &OpportunityMarket,
+    authorized_reader_nonce: u128,
+    user_eta: &Account<EncryptedTokenAccount>,
+    share_account: &ShareAccount,
+) -> ArgBuilder {
+    args
         // Authorized reader context (Shared)
-        .x25519_pubkey(authorized_reader_pubkey)
+        .x25519_pubkey(market.authorized_reader_pubkey)
         .plaintext_u128(authorized_reader_nonce)
 
-        // User's ETA (Enc<Shared, EncryptedTokenBalance>)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(user_eta_nonce)
-        .account(user_eta_key, 8, 32 * 1)
+        // Owner's ETA (Enc<Shared, EncryptedTokenBalance>)
+        .x25519_pubkey(user_eta.user_pubkey)
+        .plaintext_u128(user_eta.state_nonce)
+        .account(user_eta.key(), 8, 32 * 1)
+
+        // Share account context, encrypted to the owner
+        .x25519_pubkey(user_eta.user_pubkey)
+        .plaintext_u128(share_account.state_nonce)
+}
+
+// Args the delegated stake circuits take last, none for the owner's own stakes
+pub fn delegation_args(
+    args: ArgBuilder,
+    user_eta: &EncryptedTokenAccount,
+    delegation: Option<&Account<StakeDelegation>>,
+) -> ArgBuilder {
+    let Some(delegation) = delegation else {
+        return args;
+    };
+    args
+        // Delegation's spend so far (Enc<Shared, DelegationSpend>), encrypted to the owner
+        .x25519_pubkey(user_eta.user_pubkey)
+        .plaintext_u128(delegation.state_nonce)
+        .account(delegation.key(), 8, 32 * 1)
+        .plaintext_bool(delegation.is_initialized)
+
+        // Plaintext: delegation's spending cap
+        .plaintext_u64(delegation.spending_cap)
+}
+
+// Args of buy_opportunity_market_shares, or of its delegated variant when staking through a
+// delegation
+pub fn buy_shares_args(
+    input: &StakeInput,
+    selected_option_ciphertext: [u8; 32],
+    market: &OpportunityMarket,
+    user_eta: &Account<EncryptedTokenAccount>,
+    share_account: &ShareAccount,
+    staked_at: u64,
+    delegation: Option<&Account<StakeDelegation>>,
+) -> ArgBuilder {
+    let args = ArgBuilder::new()
+        // Trade input (Enc<Shared, BuySharesInput>)
+        .x25519_pubkey(input.pubkey)
+        .plaintext_u128(input.nonce)
+        .encrypted_u64(input.amount_ciphertext)
+        .encrypted_u16(selected_option_ciphertext);
+    let args = stake_account_args(args, market, input.authorized_reader_nonce, user_eta, share_account)
+        // Plaintext: stake timestamp, weights the amount for average entry time
+        .plaintext_u64(staked_at)
+
+        // Plaintext: market's stake limits
+        .plaintext_u64(market.min_stake)
+        .plaintext_u64(market.max_stake.unwrap_or(u64::MAX));
+    delegation_args(args, user_eta, delegation)
+}
+
+// Accounts the stake callbacks write, in the order of their callback accounts
+pub fn stake_callback_accounts(
+    user_eta: Pubkey,
+    share_account: Pubkey,
+    option: Option<Pubkey>,
+    delegation: Option<Pubkey>,
+) -> Vec<CallbackAccount> {
+    [Some(user_eta), Some(share_account), option, delegation]
+        .into_iter()
+        .flatten()
+        .map(|pubkey| CallbackAccount {
+            pubkey,
+            is_writable: true,
+        })
+        .collect()
+}
+
+pub fn stake(
+    ctx: Context<Stake>,
+    computation_offset: u64,
+    _share_account_id: u32,
+    amount_ciphertext: [u8; 32],
+    selected_option_ciphertext: [u8; 32],
+    input_nonce: u128,
+    authorized_reader_nonce: u128,
+) -> Result<()> {
+    require!(ctx.accounts.market.mint.eq(&ctx.accounts.user_eta.token_mint), ErrorCode::InvalidMint);
+
+    // Enforce staking period is active
+    let current_timestamp = require_staking_active(&ctx.accounts.market)?;
+    begin_stake(&mut ctx.accounts.user_eta, &mut ctx.accounts.share_account, None, current_timestamp)?;
 
-        // Share account context (Mxe for output encryption)
-        .x25519_pubkey(user_pubkey)
-        .plaintext_u128(ctx.accounts.share_account.state_nonce)
-        .build();
+    // Build args for encrypted computation
+    let input = StakeInput {
+        pubkey: ctx.accounts.user_eta.user_pubkey,
+        nonce: input_nonce,
+        amount_ciphertext,
+        authorized_reader_nonce,
+    };
+    let args = buy_shares_args(
+        &input,
+        selected_option_ciphertext,
+        &ctx.accounts.market,
+        &ctx.accounts.user_eta,
+        &ctx.accounts.share_account,
+        current_timestamp,
+        None,
+    )
+    .build();
 
     // Queue computation with callback
     ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    let callback_accounts = stake_callback_accounts(
+        ctx.accounts.user_eta.key(),
+        ctx.accounts.share_account.key(),
+        None,
+        None,
+    );
     queue_computation(
         ctx.accounts,
         computation_offset,
@@ -140,16 +256,7 @@ pub fn stake(
         vec![BuyOpportunityMarketSharesCallback::callback_ix(
             computation_offset,
             &ctx.accounts.mxe_account,
-            &[
-                CallbackAccount {
-                    pubkey: user_eta_key,
-                    is_writable: true,
-                },
-                CallbackAccount {
-                    pubkey: ctx.accounts.share_account.key(),
-                    is_writable: true,
-                },
-            ],
+            &callback_accounts,
         )?],
         1,
         0,
@@ -182,66 +289,121 @@ pub struct BuyOpportunityMarketSharesCallback<'info> {
     pub share_account: Account<'info, ShareAccount>,
 }
 
-pub fn buy_opportunity_market_shares_callback(
-    ctx: Context<BuyOpportunityMarketSharesCallback>,
-    output: SignedComputationOutputs<BuyOpportunityMarketSharesOutput>,
-) -> Result<()> {
+// Outputs the stake circuits share, the delegation's new spend only for delegated stakes
+pub struct StakeOutput {
+    pub error_code: u8,
+    pub new_user_balance: SharedEncryptedStruct<1>,
+    pub bought_shares: SharedEncryptedStruct<3>,
+    pub bought_shares_disclosure: SharedEncryptedStruct<3>,
+    pub new_spend: Option<SharedEncryptedStruct<1>>,
+}
+
+// Unlocks the stake's accounts, then applies the stake computation's output, or rolls the stake
+// back when the computation failed (`None`) or rejected the stake. Returns whether the stake
+// went through.
+pub fn settle_stake(
+    output: Option<StakeOutput>,
+    user_eta: &mut Account<EncryptedTokenAccount>,
+    share_account: &mut Account<ShareAccount>,
+    mut delegation: Option<&mut Account<StakeDelegation>>,
+) -> Result<bool> {
     // Unlock accounts
-    ctx.accounts.user_encrypted_token_account.locked = false;
-    ctx.accounts.share_account.locked = false;
+    user_eta.locked = false;
+    share_account.locked = false;
+    if let Some(delegation) = delegation.as_deref_mut() {
+        delegation.locked = false;
+    }
 
-    // Verify output - on error, rollback and return Ok so mutations persist
-    let res = match output.verify_output(
-        &ctx.accounts.cluster_account,
-        &ctx.accounts.computation_account,
-    ) {
-        Ok(BuyOpportunityMarketSharesOutput { field_0 }) => field_0,
-        Err(_) => {
-            // Rollback
-            ctx.accounts.share_account.staked_at_timestamp = None;
-            emit_ts!(StakedError {
-                user: ctx.accounts.user_encrypted_token_account.owner,
-            });
-            return Ok(());
-        }
+    let reason = match &output {
+        Some(output) => StakeErrorReason::from_code(output.error_code),
+        None => Some(StakeErrorReason::ComputationFailed),
     };
-
-    if res.field_0 {
+    let (Some(output), None) = (output, reason) else {
         // Rollback
-        ctx.accounts.share_account.staked_at_timestamp = None;
+        share_account.staked_at_timestamp = None;
         emit_ts!(StakedError {
-            user: ctx.accounts.user_encrypted_token_account.owner,
+            user: user_eta.owner,
+            reason: reason.unwrap_or(StakeErrorReason::ComputationFailed),
         });
-        return Ok(());
-    }
+        return Ok(false);
+    };
 
-    let new_user_balance = res.field_1;
-    let bought_shares_mxe = res.field_2;
-    let bought_shares_shared = res.field_3;
+    let new_user_balance = output.new_user_balance;
+    let bought_shares = output.bought_shares;
+    let bought_shares_disclosure = output.bought_shares_disclosure;
 
     // Update user balance to <previous balance> - <bought shares>
-    ctx.accounts.user_encrypted_token_account.state_nonce = new_user_balance.nonce;
-    ctx.accounts.user_encrypted_token_account.encrypted_state = new_user_balance.ciphertexts;
-    ctx.accounts.user_encrypted_token_account.is_initialized = true;
+    user_eta.state_nonce = new_user_balance.nonce;
+    user_eta.encrypted_state = new_user_balance.ciphertexts;
+    user_eta.is_initialized = true;
 
     // Update share account to the value of bought shares
-    ctx.accounts.share_account.state_nonce = bought_shares_mxe.nonce;
-    ctx.accounts.share_account.encrypted_state = bought_shares_mxe.ciphertexts;
-    ctx.accounts.share_account.state_nonce_disclosure = bought_shares_shared.nonce;
-    ctx.accounts.share_account.encrypted_state_disclosure = bought_shares_shared.ciphertexts;
+    share_account.state_nonce = bought_shares.nonce;
+    share_account.encrypted_state = bought_shares.ciphertexts;
+    share_account.state_nonce_disclosure = bought_shares_disclosure.nonce;
+    share_account.encrypted_state_disclosure = bought_shares_disclosure.ciphertexts;
+    share_account.user_pubkey = user_eta.user_pubkey;
+
+    // The option tallies catch up through `sync_option_tally`
+    share_account.tally_pending = true;
 
     emit_ts!(StakedEvent {
-        user: ctx.accounts.user_encrypted_token_account.owner,
-        market: ctx.accounts.share_account.market,
-        encrypted_token_account: ctx.accounts.user_encrypted_token_account.key(),
-        share_account: ctx.accounts.share_account.key(),
-        share_encrypted_state: bought_shares_mxe.ciphertexts,
-        share_state_nonce: bought_shares_mxe.nonce,
-        share_encrypted_state_disclosure: bought_shares_shared.ciphertexts,
-        share_state_disclosure_nonce: bought_shares_shared.nonce,
+        user: user_eta.owner,
+        market: share_account.market,
+        encrypted_token_account: user_eta.key(),
+        share_account: share_account.key(),
+        share_encrypted_state: bought_shares.ciphertexts,
+        share_state_nonce: bought_shares.nonce,
+        share_encrypted_state_disclosure: bought_shares_disclosure.ciphertexts,
+        share_state_disclosure_nonce: bought_shares_disclosure.nonce,
         encrypted_eta_balance: new_user_balance.ciphertexts[0],
         eta_balance_nonce: new_user_balance.nonce,
     });
 
+    if let (Some(delegation), Some(new_spend)) = (delegation, output.new_spend) {
+        // Count the stake against the delegation's spending cap
+        delegation.state_nonce = new_spend.nonce;
+        delegation.encrypted_state = new_spend.ciphertexts;
+        delegation.is_initialized = true;
+
+        emit_ts!(DelegatedStakeEvent {
+            delegation: delegation.key(),
+            owner: delegation.owner,
+            delegate: delegation.delegate,
+            share_account: share_account.key(),
+            encrypted_spent: new_spend.ciphertexts[0],
+            spent_nonce: new_spend.nonce,
+        });
+    }
+
+    Ok(true)
+}
+
+pub fn buy_opportunity_market_shares_callback(
+    ctx: Context<BuyOpportunityMarketSharesCallback>,
+    output: SignedComputationOutputs<BuyOpportunityMarketSharesOutput>,
+) -> Result<()> {
+    // Verify output - on error, settle_stake rolls back and we return Ok so mutations persist
+    let output = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(BuyOpportunityMarketSharesOutput { field_0 }) => Some(StakeOutput {
+            error_code: field_0.field_0,
+            new_user_balance: field_0.field_1,
+            bought_shares: field_0.field_2,
+            bought_shares_disclosure: field_0.field_3,
+            new_spend: None,
+        }),
+        Err(_) => None,
+    };
+
+    settle_stake(
+        output,
+        &mut ctx.accounts.user_encrypted_token_account,
+        &mut ctx.accounts.share_account,
+        None,
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, OptionTallySyncedError, OptionTallySyncedEvent};
+use crate::instructions::create_market::OPTION_TALLIES_SEED;
+use crate::instructions::stake::SHARE_ACCOUNT_SEED;
+use crate::state::{OpportunityMarket, OptionTallies, ShareAccount, MAX_TALLIED_OPTIONS};
+use crate::COMP_DEF_OFFSET_SYNC_OPTION_TALLY;
+use crate::{ArciumSignerAccount, ID, ID_CONST};
+
+// Offset of `encrypted_tallied_state` in the share account:
+// discriminator, encrypted_state, state_nonce, bump, owner, market, encrypted_state_disclosure, state_nonce_disclosure
+const TALLIED_STATE_OFFSET: u32 = 8 + 32 * 3 + 16 + 1 + 32 + 32 + 32 * 3 + 16;
+
+#[queue_computation_accounts("sync_option_tally", signer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, share_account_id: u32)]
+pub struct SyncOptionTally<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Any account, this operation is permissionless.
+    pub owner: UncheckedAccount<'info>,
+
+    pub market: Box<Account<'info, OpportunityMarket>>,
+
+    #[account(
+        mut,
+        seeds = [SHARE_ACCOUNT_SEED, owner.key().as_ref(), market.key().as_ref(), &share_account_id.to_le_bytes()],
+        bump = share_account.bump,
+        constraint = share_account.tally_pending @ ErrorCode::TallyUpToDate,
+        constraint = !share_account.locked @ ErrorCode::Locked,
+    )]
+    pub share_account: Box<Account<'info, ShareAccount>>,
+
+    #[account(
+        mut,
+        seeds = [OPTION_TALLIES_SEED, market.key().as_ref()],
+        bump = option_tallies.bump,
+        constraint = !option_tallies.locked @ ErrorCode::Locked,
+    )]
+    pub option_tallies: Box<Account<'info, OptionTallies>>,
+
+    // Arcium accounts
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = signer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SYNC_OPTION_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+// This operation is permissionless: moves the change left by a stake, stake increase or unstake
+// into the market's option tallies. Stakes only flag their share account, so they never wait on
+// each other for the tallies, only the syncs queue up behind one another.
+pub fn sync_option_tally(
+    ctx: Context<SyncOptionTally>,
+    computation_offset: u64,
+    _share_account_id: u32,
+) -> Result<()> {
+    let share_account_key = ctx.accounts.share_account.key();
+    let option_tallies_key = ctx.accounts.option_tallies.key();
+
+    let share_account = &ctx.accounts.share_account;
+    let user_pubkey = share_account.user_pubkey;
+    let share_state_nonce = share_account.state_nonce;
+    let tallied_state_nonce = share_account.tallied_state_nonce;
+    let is_tallied_initialized = share_account.is_tallied_state_initialized;
+    let is_unstaked = share_account.unstaked_at_timestamp.is_some();
+
+    let option_tallies_nonce = ctx.accounts.option_tallies.state_nonce;
+    let is_tallies_initialized = ctx.accounts.option_tallies.is_initialized;
+
+    // Lock accounts while MPC computation is pending
+    ctx.accounts.share_account.locked = true;
+    ctx.accounts.option_tallies.locked = true;
+
+    // Build args for encrypted computation
+    let args = ArgBuilder::new()
+        // Share account state (Enc<Shared, SharePurchase>)
+        .x25519_pubkey(user_pubkey)
+        .plaintext_u128(share_state_nonce)
+        .account(share_account_key, 8, 32 * 3)
+
+        // Stake already in the tallies (Enc<Mxe, TalliedStake>)
+        .plaintext_u128(tallied_state_nonce)
+        .account(share_account_key, TALLIED_STATE_OFFSET, 32 * 1)
+        .plaintext_bool(is_tallied_initialized)
+
+        // Plaintext: whether the stake has been withdrawn
+        .plaintext_bool(is_unstaked)
+
+        // Market's running option tallies (Enc<Mxe, OptionTallies>)
+        .plaintext_u128(option_tallies_nonce)
+        .account(option_tallies_key, 8, 32 * MAX_TALLIED_OPTIONS as u32)
+        .plaintext_bool(is_tallies_initialized)
+        .build();
+
+    // Queue computation with callback
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![SyncOptionTallyCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: share_account_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: option_tallies_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+#[callback_accounts("sync_option_tally")]
+#[derive(Accounts)]
+pub struct SyncOptionTallyCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SYNC_OPTION_TALLY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    // Callback accounts
+    #[account(mut)]
+    pub share_account: Account<'info, ShareAccount>,
+    #[account(mut)]
+    pub option_tallies: Account<'info, OptionTallies>,
+}
+
+pub fn sync_option_tally_callback(
+    ctx: Context<SyncOptionTallyCallback>,
+    output: SignedComputationOutputs<SyncOptionTallyOutput>,
+) -> Result<()> {
+    // Unlock accounts
+    ctx.accounts.share_account.locked = false;
+    ctx.accounts.option_tallies.locked = false;
+
+    // Verify output - on error, emit event and return Ok so unlocks persist.
+    // The share account stays pending, the sync can be retried.
+    let res = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(SyncOptionTallyOutput { field_0 }) => field_0,
+        Err(_) => {
+            emit_ts!(OptionTallySyncedError {
+                market: ctx.accounts.option_tallies.market,
+                share_account: ctx.accounts.share_account.key(),
+            });
+            return Ok(());
+        }
+    };
+
+    // Tally would overflow, nothing was moved
+    if res.field_0 {
+        emit_ts!(OptionTallySyncedError {
+            market: ctx.accounts.option_tallies.market,
+            share_account: ctx.accounts.share_account.key(),
+        });
+        return Ok(());
+    }
+
+    let new_tallied = res.field_1;
+    let new_tallies = res.field_2;
+
+    let share_account = &mut ctx.accounts.share_account;
+    share_account.tallied_state_nonce = new_tallied.nonce;
+    share_account.encrypted_tallied_state = new_tallied.ciphertexts;
+    share_account.is_tallied_state_initialized = true;
+    share_account.tally_pending = false;
+
+    let tallies = &mut ctx.accounts.option_tallies;
+    tallies.state_nonce = new_tallies.nonce;
+    tallies.encrypted_state = new_tallies.ciphertexts;
+    tallies.is_initialized = true;
+
+    emit_ts!(OptionTallySyncedEvent {
+        market: tallies.market,
+        share_account: share_account.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::error::ErrorCode;
+use crate::events::{emit_ts, EncryptedTokensTransferredError, EncryptedTokensTransferredEvent};
+use crate::state::EncryptedTokenAccount;
+
+use crate::COMP_DEF_OFFSET_TRANSFER_ENCRYPTED_TOKENS;
+use crate::{ID, ID_CONST, ArciumSignerAccount};
+
+// After an incoming transfer settles, the recipient ETA accepts no other incoming transfer for
+// this long. Every transfer locks the recipient until its callback, the cooldown guarantees the
+// owner a window to use the ETA however often others send to it.
+pub const INCOMING_TRANSFER_COOLDOWN_SECONDS: u64 = 30;
+
+#[queue_computation_accounts("transfer_encrypted_tokens", signer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct TransferEncryptedTokens<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Sender's ETA, debited by the transfer amount
+    #[account(
+        mut,
+        constraint = sender_encrypted_token_account.owner == signer.key() @ ErrorCode::Unauthorized,
+        constraint = sender_encrypted_token_account.token_mint == token_mint.key() @ ErrorCode::InvalidMint,
+        constraint = sender_encrypted_token_account.is_initialized @ ErrorCode::InvalidAccountState,
+        constraint = !sender_encrypted_token_account.locked @ ErrorCode::Locked,
+    )]
+    pub sender_encrypted_token_account: Box<Account<'info, EncryptedTokenAccount>>,
+
+    /// Recipient's ETA, credited by the transfer amount
+    #[account(
+        mut,
+        constraint = recipient_encrypted_token_account.key() != sender_encrypted_token_account.key() @ ErrorCode::InvalidAccountState,
+        constraint = recipient_encrypted_token_account.token_mint == token_mint.key() @ ErrorCode::InvalidMint,
+        constraint = !recipient_encrypted_token_account.locked @ ErrorCode::Locked,
+    )]
+    pub recipient_encrypted_token_account: Box<Account<'info, EncryptedTokenAccount>>,
+
+    // Arcium accounts
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = signer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Box<Account<'info, ArciumSignerAccount>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TRANSFER_ENCRYPTED_TOKENS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+pub fn transfer_encrypted_tokens(
+    ctx: Context<TransferEncryptedTokens>,
+    computation_offset: u64,
+    amount_ciphertext: [u8; 32],
+    input_nonce: u128,
+) -> Result<()> {
+    let sender_eta = &mut ctx.accounts.sender_encrypted_token_account;
+    let sender_pubkey = sender_eta.user_pubkey;
+    let sender_eta_key = sender_eta.key();
+    let sender_eta_nonce = sender_eta.state_nonce;
+
+    let recipient_eta = &mut ctx.accounts.recipient_encrypted_token_account;
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp as u64 >= recipient_eta.incoming_transfers_blocked_until,
+        ErrorCode::IncomingTransferCooldown
+    );
+    let recipient_pubkey = recipient_eta.user_pubkey;
+    let recipient_eta_key = recipient_eta.key();
+    let recipient_eta_nonce = recipient_eta.state_nonce;
+    let is_recipient_initialized = recipient_eta.is_initialized;
+
+    // Lock both ETAs while MPC computation is pending
+    ctx.accounts.sender_encrypted_token_account.locked = true;
+    ctx.accounts.recipient_encrypted_token_account.locked = true;
+
+    // Build args for encrypted computation
+    // Circuit: transfer_encrypted_tokens(input_ctx, sender_eta_ctx, recipient_eta_ctx, is_recipient_initialized)
+    let args = ArgBuilder::new()
+        // Sender's transfer input (Enc<Shared, TransferInput>)
+        .x25519_pubkey(sender_pubkey)
+        .plaintext_u128(input_nonce)
+        .encrypted_u64(amount_ciphertext)
+        // Sender ETA encrypted state
+        .x25519_pubkey(sender_pubkey)
+        .plaintext_u128(sender_eta_nonce)
+        .account(sender_eta_key, 8, 32 * 1)
+        // Recipient ETA encrypted state, re-encrypted for the recipient's key
+        .x25519_pubkey(recipient_pubkey)
+        .plaintext_u128(recipient_eta_nonce)
+        .account(recipient_eta_key, 8, 32 * 1)
+        // Is recipient ETA initialized flag
+        .plaintext_bool(is_recipient_initialized)
+        .build();
+
+    // Queue computation with callback
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![TransferEncryptedTokensCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: sender_eta_key,
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: recipient_eta_key,
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+#[callback_accounts("transfer_encrypted_tokens")]
+#[derive(Accounts)]
+pub struct TransferEncryptedTokensCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_TRANSFER_ENCRYPTED_TOKENS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    // Callback accounts (order must match CallbackAccount vec above)
+    #[account(mut)]
+    pub sender_encrypted_token_account: Account<'info, EncryptedTokenAccount>,
+
+    #[account(mut)]
+    pub recipient_encrypted_token_account: Account<'info, EncryptedTokenAccount>,
+}
+
+pub fn transfer_encrypted_tokens_callback(
+    ctx: Context<TransferEncryptedTokensCallback>,
+    output: SignedComputationOutputs<TransferEncryptedTokensOutput>,
+) -> Result<()> {
+    let sender_eta = &mut ctx.accounts.sender_encrypted_token_account;
+    let recipient_eta = &mut ctx.accounts.recipient_encrypted_token_account;
+
+    // Unlock both accounts, the recipient's cooldown starts now
+    sender_eta.locked = false;
+    recipient_eta.locked = false;
+    let clock = Clock::get()?;
+    recipient_eta.incoming_transfers_blocked_until = (clock.unix_timestamp as u64)
+        .checked_add(INCOMING_TRANSFER_COOLDOWN_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // Output is (bool, Enc<Shared, EncryptedTokenBalance>, Enc<Shared, EncryptedTokenBalance>)
+    // field_0 = error boolean (true = insufficient balance or recipient balance overflow)
+    // field_1 = updated sender balance
+    // field_2 = updated recipient balance
+    let res = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(TransferEncryptedTokensOutput { field_0 }) => field_0,
+        Err(_) => {
+            emit_ts!(EncryptedTokensTransferredError {
+                sender: sender_eta.owner,
+                recipient: recipient_eta.owner,
+            });
+            return Ok(());
+        }
+    };
+
+    // Insufficient balance or overflow, roll back by leaving both encrypted states untouched
+    if res.field_0 {
+        emit_ts!(EncryptedTokensTransferredError {
+            sender: sender_eta.owner,
+            recipient: recipient_eta.owner,
+        });
+        return Ok(());
+    }
+
+    let sender_balance = res.field_1;
+    let recipient_balance = res.field_2;
+
+    // No SPL token transfer needed - tokens stay in the common TokenVault
+    sender_eta.state_nonce = sender_balance.nonce;
+    sender_eta.encrypted_state = sender_balance.ciphertexts;
+
+    recipient_eta.state_nonce = recipient_balance.nonce;
+    recipient_eta.encrypted_state = recipient_balance.ciphertexts;
+    recipient_eta.is_initialized = true;
+
+    emit_ts!(EncryptedTokensTransferredEvent {
+        sender: sender_eta.owner,
+        recipient: recipient_eta.owner,
+        sender_encrypted_token_account: sender_eta.key(),
+        recipient_encrypted_token_account: recipient_eta.key(),
+        sender_encrypted_new_balance: sender_balance.ciphertexts[0],
+        sender_nonce: sender_balance.nonce,
+        recipient_encrypted_new_balance: recipient_balance.ciphertexts[0],
+        recipient_nonce: recipient_balance.nonce,
+    });
+
+    Ok(())
+}
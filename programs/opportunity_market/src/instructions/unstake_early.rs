@@ -14,6 +14,7 @@ pub struct UnstakeEarly<'info> {
     #[account(
         constraint = market.open_timestamp.is_some() @ ErrorCode::MarketNotOpen,
         constraint = market.selected_option.is_none() @ ErrorCode::WinnerAlreadySelected,
+        constraint = !market.cancelled @ ErrorCode::MarketCancelled,
     )]
     pub market: Box<Account<'info, OpportunityMarket>>,
 
@@ -6,7 +6,7 @@ use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 use crate::error::ErrorCode;
-use crate::events::{emit_ts, EncryptedTokensWrappedEvent};
+use crate::events::{emit_ts, EncryptedTokensWrappedError, EncryptedTokensWrappedEvent};
 use crate::state::{EncryptedTokenAccount, TokenVault};
 use crate::instructions::init_token_vault::TOKEN_VAULT_SEED;
 use crate::COMP_DEF_OFFSET_WRAP_ENCRYPTED_TOKENS;
@@ -177,7 +177,7 @@ pub fn wrap_encrypted_tokens_callback(
     ctx: Context<WrapEncryptedTokensCallback>,
     output: SignedComputationOutputs<WrapEncryptedTokensOutput>,
 ) -> Result<()> {
-    let encrypted_balance = match output.verify_output(
+    let res = match output.verify_output(
         &ctx.accounts.cluster_account,
         &ctx.accounts.computation_account,
     ) {
@@ -193,6 +193,19 @@ pub fn wrap_encrypted_tokens_callback(
     // Check that pending deposit exists. User could have withdrawn funds.
     require!(eta.pending_deposit > 0 && eta.locked, ErrorCode::InsufficientBalance);
 
+    // Balance would overflow: keep the ETA locked with its pending deposit,
+    // the user gets the tokens back via `claim_pending_deposit`
+    if res.field_0 {
+        emit_ts!(EncryptedTokensWrappedError {
+            user: eta.owner,
+            encrypted_token_account: eta.key(),
+            pending_deposit: eta.pending_deposit,
+        });
+        return Ok(());
+    }
+
+    let encrypted_balance = res.field_1;
+
     // Save deposit amount before clearing
     let deposit_amount = eta.pending_deposit;
 
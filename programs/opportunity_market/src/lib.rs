@@ -15,11 +15,18 @@ pub use state::*;
 
 pub const COMP_DEF_OFFSET_WRAP_ENCRYPTED_TOKENS: u32 = comp_def_offset("wrap_encrypted_tokens");
 pub const COMP_DEF_OFFSET_UNWRAP_ENCRYPTED_TOKENS: u32 = comp_def_offset("unwrap_encrypted_tokens");
+pub const COMP_DEF_OFFSET_TRANSFER_ENCRYPTED_TOKENS: u32 = comp_def_offset("transfer_encrypted_tokens");
 pub const COMP_DEF_OFFSET_BUY_OPPORTUNITY_MARKET_SHARES: u32 = comp_def_offset("buy_opportunity_market_shares");
 pub const COMP_DEF_OFFSET_REVEAL_SHARES: u32 = comp_def_offset("reveal_shares");
+pub const COMP_DEF_OFFSET_INCREASE_STAKE: u32 = comp_def_offset("increase_stake");
 pub const COMP_DEF_OFFSET_UNSTAKE_EARLY: u32 = comp_def_offset("unstake_early");
 pub const COMP_DEF_OFFSET_ADD_OPTION_STAKE: u32 = comp_def_offset("add_option_stake");
 pub const COMP_DEF_OFFSET_CLOSE_EPHEMERAL_ENCRYPTED_TOKEN_ACCOUNT: u32 = comp_def_offset("close_ephemeral_encrypted_token_account");
+pub const COMP_DEF_OFFSET_DISCLOSE_OPTION_TALLIES: u32 = comp_def_offset("disclose_option_tallies");
+pub const COMP_DEF_OFFSET_SYNC_OPTION_TALLY: u32 = comp_def_offset("sync_option_tally");
+pub const COMP_DEF_OFFSET_DELEGATED_BUY_OPPORTUNITY_MARKET_SHARES: u32 =
+    comp_def_offset("delegated_buy_opportunity_market_shares");
+pub const COMP_DEF_OFFSET_DELEGATED_ADD_OPTION_STAKE: u32 = comp_def_offset("delegated_add_option_stake");
 
 declare_id!("opppkAuEoNg8W2bi6WGshmL8NWG2D4ATQWSgyhgTcSz");
 
@@ -35,6 +42,10 @@ pub mod opportunity_market {
         instructions::unwrap_encrypted_tokens_comp_def(ctx)
     }
 
+    pub fn transfer_encrypted_tokens_comp_def(ctx: Context<TransferEncryptedTokensCompDef>) -> Result<()> {
+        instructions::transfer_encrypted_tokens_comp_def(ctx)
+    }
+
     pub fn reveal_shares_comp_def(ctx: Context<RevealSharesCompDef>) -> Result<()> {
         instructions::reveal_shares_comp_def(ctx)
     }
@@ -47,12 +58,44 @@ pub mod opportunity_market {
         instructions::add_option_stake_comp_def(ctx)
     }
 
+    pub fn increase_stake_comp_def(ctx: Context<IncreaseStakeCompDef>) -> Result<()> {
+        instructions::increase_stake_comp_def(ctx)
+    }
+
+    pub fn disclose_option_tallies_comp_def(ctx: Context<DiscloseOptionTalliesCompDef>) -> Result<()> {
+        instructions::disclose_option_tallies_comp_def(ctx)
+    }
+
+    pub fn sync_option_tally_comp_def(ctx: Context<SyncOptionTallyCompDef>) -> Result<()> {
+        instructions::sync_option_tally_comp_def(ctx)
+    }
+
+    pub fn delegated_buy_opportunity_market_shares_comp_def(
+        ctx: Context<DelegatedBuyOpportunityMarketSharesCompDef>,
+    ) -> Result<()> {
+        instructions::delegated_buy_opportunity_market_shares_comp_def(ctx)
+    }
+
+    pub fn delegated_add_option_stake_comp_def(ctx: Context<DelegatedAddOptionStakeCompDef>) -> Result<()> {
+        instructions::delegated_add_option_stake_comp_def(ctx)
+    }
+
     pub fn init_central_state(
         ctx: Context<InitCentralState>,
         earliness_cutoff_seconds: u64,
         min_option_deposit: u64,
+        reward_reclaim_grace_seconds: u64,
+        protocol_fee_bps: u16,
+        treasury: Pubkey,
     ) -> Result<()> {
-        instructions::init_central_state(ctx, earliness_cutoff_seconds, min_option_deposit)
+        instructions::init_central_state(
+            ctx,
+            earliness_cutoff_seconds,
+            min_option_deposit,
+            reward_reclaim_grace_seconds,
+            protocol_fee_bps,
+            treasury,
+        )
     }
 
     pub fn transfer_central_state_authority(
@@ -66,8 +109,18 @@ pub mod opportunity_market {
         ctx: Context<UpdateCentralState>,
         earliness_cutoff_seconds: u64,
         min_option_deposit: u64,
+        reward_reclaim_grace_seconds: u64,
+        protocol_fee_bps: u16,
+        treasury: Pubkey,
     ) -> Result<()> {
-        instructions::update_central_state(ctx, earliness_cutoff_seconds, min_option_deposit)
+        instructions::update_central_state(
+            ctx,
+            earliness_cutoff_seconds,
+            min_option_deposit,
+            reward_reclaim_grace_seconds,
+            protocol_fee_bps,
+            treasury,
+        )
     }
 
     pub fn create_market(
@@ -80,6 +133,12 @@ pub mod opportunity_market {
         unstake_delay_seconds: u64,
         authorized_reader_pubkey: [u8; 32],
         allow_closing_early: bool,
+        earliness_cutoff_seconds: Option<u64>,
+        scoring_config: ScoringConfig,
+        losing_stake_slash_bps: u16,
+        min_stake: u64,
+        max_stake: Option<u64>,
+        planned_open_timestamp: Option<u64>,
     ) -> Result<()> {
         instructions::create_market(
             ctx,
@@ -91,9 +150,30 @@ pub mod opportunity_market {
             unstake_delay_seconds,
             authorized_reader_pubkey,
             allow_closing_early,
+            earliness_cutoff_seconds,
+            scoring_config,
+            losing_stake_slash_bps,
+            min_stake,
+            max_stake,
+            planned_open_timestamp,
         )
     }
 
+    pub fn create_market_template(
+        ctx: Context<CreateMarketTemplate>,
+        template_id: u64,
+        params: MarketParams,
+    ) -> Result<()> {
+        instructions::create_market_template(ctx, template_id, params)
+    }
+
+    pub fn create_market_from_template(
+        ctx: Context<CreateMarketFromTemplate>,
+        planned_open_timestamp: Option<u64>,
+    ) -> Result<()> {
+        instructions::create_market_from_template(ctx, planned_open_timestamp)
+    }
+
     pub fn add_market_option(
         ctx: Context<AddMarketOption>,
         computation_offset: u64,
@@ -132,14 +212,54 @@ pub mod opportunity_market {
         instructions::add_market_option_callback(ctx, output)
     }
 
+    pub fn close_market_option(ctx: Context<CloseMarketOption>, option_index: u16) -> Result<()> {
+        instructions::close_market_option(ctx, option_index)
+    }
+
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market(ctx)
+    }
+
+    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+        instructions::cancel_market(ctx)
+    }
+
+    pub fn reclaim_market_rewards(ctx: Context<ReclaimMarketRewards>) -> Result<()> {
+        instructions::reclaim_market_rewards(ctx)
+    }
+
+    pub fn claim_protocol_fees(ctx: Context<ClaimProtocolFees>) -> Result<()> {
+        instructions::claim_protocol_fees(ctx)
+    }
+
     pub fn open_market(ctx: Context<OpenMarket>, open_timestamp: u64) -> Result<()> {
         instructions::open_market(ctx, open_timestamp)
     }
 
+    pub fn activate_market(ctx: Context<ActivateMarket>) -> Result<()> {
+        instructions::activate_market(ctx)
+    }
+
+    pub fn sponsor_market(ctx: Context<SponsorMarket>, amount: u64) -> Result<()> {
+        instructions::sponsor_market(ctx, amount)
+    }
+
+    pub fn refund_sponsor(ctx: Context<RefundSponsor>) -> Result<()> {
+        instructions::refund_sponsor(ctx)
+    }
+
+    pub fn close_market_sponsor(ctx: Context<CloseMarketSponsor>) -> Result<()> {
+        instructions::close_market_sponsor(ctx)
+    }
+
     pub fn select_option(ctx: Context<SelectOption>, option_index: u16) -> Result<()> {
         instructions::select_option(ctx, option_index)
     }
 
+    pub fn select_winning_options(ctx: Context<SelectOption>, winning_options: Vec<WinningOption>) -> Result<()> {
+        instructions::select_winning_options(ctx, winning_options)
+    }
+
     pub fn extend_reveal_period(ctx: Context<ExtendRevealPeriod>, new_time_to_reveal: u64) -> Result<()> {
         instructions::extend_reveal_period(ctx, new_time_to_reveal)
     }
@@ -219,6 +339,23 @@ pub mod opportunity_market {
         instructions::unwrap_encrypted_tokens_callback(ctx, output)
     }
 
+    pub fn transfer_encrypted_tokens(
+        ctx: Context<TransferEncryptedTokens>,
+        computation_offset: u64,
+        amount_ciphertext: [u8; 32],
+        input_nonce: u128,
+    ) -> Result<()> {
+        instructions::transfer_encrypted_tokens(ctx, computation_offset, amount_ciphertext, input_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "transfer_encrypted_tokens")]
+    pub fn transfer_encrypted_tokens_callback(
+        ctx: Context<TransferEncryptedTokensCallback>,
+        output: SignedComputationOutputs<TransferEncryptedTokensOutput>,
+    ) -> Result<()> {
+        instructions::transfer_encrypted_tokens_callback(ctx, output)
+    }
+
     pub fn buy_opportunity_market_shares_comp_def(ctx: Context<BuyOpportunityMarketSharesCompDef>) -> Result<()> {
         instructions::buy_opportunity_market_shares_comp_def(ctx)
     }
@@ -250,6 +387,32 @@ pub mod opportunity_market {
     ) -> Result<()> {
         instructions::buy_opportunity_market_shares_callback(ctx, output)
     }
+    pub fn increase_stake(
+        ctx: Context<IncreaseStake>,
+        computation_offset: u64,
+        share_account_id: u32,
+        amount_ciphertext: [u8; 32],
+        input_nonce: u128,
+        authorized_reader_nonce: u128,
+    ) -> Result<()> {
+        instructions::increase_stake(
+            ctx,
+            computation_offset,
+            share_account_id,
+            amount_ciphertext,
+            input_nonce,
+            authorized_reader_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "increase_stake")]
+    pub fn increase_stake_callback(
+        ctx: Context<IncreaseStakeCallback>,
+        output: SignedComputationOutputs<IncreaseStakeOutput>,
+    ) -> Result<()> {
+        instructions::increase_stake_callback(ctx, output)
+    }
+
     pub fn reveal_shares(
         ctx: Context<RevealShares>,
         computation_offset: u64,
@@ -290,6 +453,122 @@ pub mod opportunity_market {
         instructions::unstake_early_callback(ctx, output)
     }
 
+    pub fn disclose_option_tallies(
+        ctx: Context<DiscloseOptionTallies>,
+        computation_offset: u64,
+        authorized_reader_nonce: u128,
+    ) -> Result<()> {
+        instructions::disclose_option_tallies(ctx, computation_offset, authorized_reader_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "disclose_option_tallies")]
+    pub fn disclose_option_tallies_callback(
+        ctx: Context<DiscloseOptionTalliesCallback>,
+        output: SignedComputationOutputs<DiscloseOptionTalliesOutput>,
+    ) -> Result<()> {
+        instructions::disclose_option_tallies_callback(ctx, output)
+    }
+
+    pub fn sync_option_tally(
+        ctx: Context<SyncOptionTally>,
+        computation_offset: u64,
+        share_account_id: u32,
+    ) -> Result<()> {
+        instructions::sync_option_tally(ctx, computation_offset, share_account_id)
+    }
+
+    #[arcium_callback(encrypted_ix = "sync_option_tally")]
+    pub fn sync_option_tally_callback(
+        ctx: Context<SyncOptionTallyCallback>,
+        output: SignedComputationOutputs<SyncOptionTallyOutput>,
+    ) -> Result<()> {
+        instructions::sync_option_tally_callback(ctx, output)
+    }
+
+    pub fn create_stake_delegation(
+        ctx: Context<CreateStakeDelegation>,
+        delegate: Pubkey,
+        spending_cap: u64,
+        expires_at: u64,
+        state_nonce: u128,
+    ) -> Result<()> {
+        instructions::create_stake_delegation(ctx, delegate, spending_cap, expires_at, state_nonce)
+    }
+
+    pub fn revoke_stake_delegation(ctx: Context<RevokeStakeDelegation>) -> Result<()> {
+        instructions::revoke_stake_delegation(ctx)
+    }
+
+    pub fn init_share_account_as_delegate(
+        ctx: Context<InitShareAccountAsDelegate>,
+        state_nonce: u128,
+        share_account_id: u32,
+    ) -> Result<()> {
+        instructions::init_share_account_as_delegate(ctx, state_nonce, share_account_id)
+    }
+
+    pub fn stake_as_delegate(
+        ctx: Context<StakeAsDelegate>,
+        computation_offset: u64,
+        share_account_id: u32,
+        amount_ciphertext: [u8; 32],
+        selected_option_ciphertext: [u8; 32],
+        delegate_pubkey: [u8; 32],
+        input_nonce: u128,
+        authorized_reader_nonce: u128,
+    ) -> Result<()> {
+        instructions::stake_as_delegate(
+            ctx,
+            computation_offset,
+            share_account_id,
+            amount_ciphertext,
+            selected_option_ciphertext,
+            delegate_pubkey,
+            input_nonce,
+            authorized_reader_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "delegated_buy_opportunity_market_shares")]
+    pub fn delegated_buy_opportunity_market_shares_callback(
+        ctx: Context<DelegatedBuyOpportunityMarketSharesCallback>,
+        output: SignedComputationOutputs<DelegatedBuyOpportunityMarketSharesOutput>,
+    ) -> Result<()> {
+        instructions::delegated_buy_opportunity_market_shares_callback(ctx, output)
+    }
+
+    pub fn add_market_option_as_delegate(
+        ctx: Context<AddMarketOptionAsDelegate>,
+        computation_offset: u64,
+        option_index: u16,
+        share_account_id: u32,
+        name: String,
+        amount_ciphertext: [u8; 32],
+        delegate_pubkey: [u8; 32],
+        input_nonce: u128,
+        authorized_reader_nonce: u128,
+    ) -> Result<()> {
+        instructions::add_market_option_as_delegate(
+            ctx,
+            computation_offset,
+            option_index,
+            share_account_id,
+            name,
+            amount_ciphertext,
+            delegate_pubkey,
+            input_nonce,
+            authorized_reader_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "delegated_add_option_stake")]
+    pub fn delegated_add_option_stake_callback(
+        ctx: Context<DelegatedAddOptionStakeCallback>,
+        output: SignedComputationOutputs<DelegatedAddOptionStakeOutput>,
+    ) -> Result<()> {
+        instructions::add_market_option_as_delegate_callback(ctx, output)
+    }
+
     pub fn close_ephemeral_encrypted_token_account_comp_def(
         ctx: Context<CloseEphemeralEncryptedTokenAccountCompDef>,
     ) -> Result<()> {
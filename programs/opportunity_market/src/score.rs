@@ -1,17 +1,124 @@
 use crate::error::ErrorCode;
+use crate::state::ScoringConfig;
 use anchor_lang::prelude::*;
 
-// At this point no more boost for being early
-pub const EARLINESS_INTERSECTION_POINT_SECONDS: u64 = 86_400;
+// Upper bound for a market's earliness cutoff (one year)
+pub const MAX_EARLINESS_CUTOFF_SECONDS: u64 = 365 * 86_400;
 
 // Fixed-point scale factor to avoid decimal division
 pub const PRECISION: u64 = 10_000;
 
+pub fn validate_earliness_cutoff(earliness_cutoff_seconds: u64) -> Result<()> {
+    require!(
+        earliness_cutoff_seconds > 0 && earliness_cutoff_seconds <= MAX_EARLINESS_CUTOFF_SECONDS,
+        ErrorCode::InvalidEarlinessCutoff
+    );
+    Ok(())
+}
+
+// Curves must stay within [1.0x..2.0x] and never increase over time
+pub fn validate_scoring_config(scoring_config: &ScoringConfig) -> Result<()> {
+    match scoring_config {
+        ScoringConfig::Linear | ScoringConfig::Flat => {}
+        ScoringConfig::ExponentialDecay { half_life_seconds } => {
+            require!(
+                *half_life_seconds > 0 && *half_life_seconds <= MAX_EARLINESS_CUTOFF_SECONDS,
+                ErrorCode::InvalidScoringConfig
+            );
+        }
+        ScoringConfig::StepTiers { tiers } => {
+            let mut prev_until = 0u64;
+            let mut prev_factor = 2 * PRECISION;
+            let mut seen_unused = false;
+            for tier in tiers.iter() {
+                if tier.until_seconds == 0 {
+                    seen_unused = true;
+                    continue;
+                }
+                // Unused tiers may only trail the used ones
+                require!(!seen_unused, ErrorCode::InvalidScoringConfig);
+                require!(
+                    tier.until_seconds > prev_until
+                        && tier.until_seconds <= MAX_EARLINESS_CUTOFF_SECONDS,
+                    ErrorCode::InvalidScoringConfig
+                );
+                require!(
+                    tier.factor >= PRECISION && tier.factor <= prev_factor,
+                    ErrorCode::InvalidScoringConfig
+                );
+                prev_until = tier.until_seconds;
+                prev_factor = tier.factor;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Earliness factor scaled by PRECISION, range [PRECISION..2*PRECISION] i.e. [1.0x..2.0x]
+pub fn calculate_earliness_factor(
+    scoring_config: &ScoringConfig,
+    stake_since_opening: u64,
+    earliness_cutoff_seconds: u64,
+) -> Result<u64> {
+    let earliness_cutoff_seconds = earliness_cutoff_seconds.max(1);
+
+    let factor = match scoring_config {
+        // earliness_factor = 2 - x / x_n
+        // Clamped so staking after the cutoff gives factor = 1.0
+        ScoringConfig::Linear => {
+            let elapsed = (stake_since_opening.min(earliness_cutoff_seconds) as u128)
+                * PRECISION as u128
+                / earliness_cutoff_seconds as u128;
+            (2 * PRECISION)
+                .checked_sub(elapsed as u64)
+                .ok_or(ErrorCode::Overflow)?
+        }
+
+        // earliness_factor = 1 + 2^(-x / half_life), interpolated linearly within each half-life
+        ScoringConfig::ExponentialDecay { half_life_seconds } => {
+            if stake_since_opening >= earliness_cutoff_seconds {
+                PRECISION
+            } else {
+                let half_life_seconds = (*half_life_seconds).max(1);
+                let halvings = stake_since_opening / half_life_seconds;
+                let remainder = stake_since_opening % half_life_seconds;
+
+                let bonus = if halvings >= u64::BITS as u64 {
+                    0
+                } else {
+                    let bonus_start = PRECISION >> halvings;
+                    let bonus_end = bonus_start >> 1;
+                    let decayed = ((bonus_start - bonus_end) as u128)
+                        .checked_mul(remainder as u128)
+                        .ok_or(ErrorCode::Overflow)?
+                        / half_life_seconds as u128;
+                    bonus_start - decayed as u64
+                };
+
+                PRECISION.checked_add(bonus).ok_or(ErrorCode::Overflow)?
+            }
+        }
+
+        ScoringConfig::StepTiers { tiers } => tiers
+            .iter()
+            .filter(|tier| tier.until_seconds > 0)
+            .find(|tier| stake_since_opening < tier.until_seconds)
+            .map(|tier| tier.factor)
+            .unwrap_or(PRECISION),
+
+        ScoringConfig::Flat => PRECISION,
+    };
+
+    Ok(factor)
+}
+
 pub fn calculate_user_score_components(
     market_opened: u64,
     market_closed: u64,
     user_staked_at: u64,
     stake_amount: u64,
+    earliness_cutoff_seconds: u64,
+    scoring_config: &ScoringConfig,
 ) -> Result<(u64, u64, u64)> {
     let total_market_time = market_closed
         .checked_sub(market_opened)
@@ -26,18 +133,8 @@ pub fn calculate_user_score_components(
         .checked_sub(user_staked_at)
         .ok_or(ErrorCode::Overflow)?;
 
-    // earliness_factor = 2 - x / x_n, scaled by PRECISION
-    // Range: [PRECISION..2*PRECISION] i.e. [1.0x..2.0x]
-    // Clamped so staking after the intersection point gives factor = 1.0
-    let earliness_factor = (2 * PRECISION)
-        .checked_sub(
-            stake_since_opening
-                .min(EARLINESS_INTERSECTION_POINT_SECONDS)
-                .checked_mul(PRECISION)
-                .ok_or(ErrorCode::Overflow)?
-                / EARLINESS_INTERSECTION_POINT_SECONDS,
-        )
-        .ok_or(ErrorCode::Overflow)?;
+    let earliness_factor =
+        calculate_earliness_factor(scoring_config, stake_since_opening, earliness_cutoff_seconds)?;
 
     let stake_time_percentage = (actual_stake_duration as u128)
         .checked_mul(100)
@@ -54,9 +151,17 @@ pub fn calculate_user_score(
     market_closed: u64,
     user_staked_at: u64,
     stake_amount: u64,
+    earliness_cutoff_seconds: u64,
+    scoring_config: &ScoringConfig,
 ) -> Result<u64> {
-    let (amount, time_pct, earliness) =
-        calculate_user_score_components(market_opened, market_closed, user_staked_at, stake_amount)?;
+    let (amount, time_pct, earliness) = calculate_user_score_components(
+        market_opened,
+        market_closed,
+        user_staked_at,
+        stake_amount,
+        earliness_cutoff_seconds,
+        scoring_config,
+    )?;
 
     // score = amount * time_pct * earliness / PRECISION
     // Use u128 intermediate to avoid overflow
@@ -66,7 +171,135 @@ pub fn calculate_user_score(
         .checked_mul(earliness as u128)
         .ok_or(ErrorCode::Overflow)?
         .checked_div(PRECISION as u128)
-        .ok_or(ErrorCode::Overflow)? as u64;
+        .ok_or(ErrorCode::Overflow)?;
+
+    let user_score = u64::try_from(user_score).map_err(|_| ErrorCode::Overflow)?;
 
     Ok(user_score)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ScoreTier, MAX_SCORE_TIERS};
+
+    const CUTOFF: u64 = 86_400;
+
+    fn step_tiers(tiers: &[(u64, u64)]) -> ScoringConfig {
+        let mut padded = [ScoreTier {
+            until_seconds: 0,
+            factor: 0,
+        }; MAX_SCORE_TIERS];
+        for (slot, &(until_seconds, factor)) in padded.iter_mut().zip(tiers) {
+            *slot = ScoreTier {
+                until_seconds,
+                factor,
+            };
+        }
+        ScoringConfig::StepTiers { tiers: padded }
+    }
+
+    fn configs() -> [ScoringConfig; 4] {
+        [
+            ScoringConfig::Linear,
+            ScoringConfig::ExponentialDecay {
+                half_life_seconds: CUTOFF / 4,
+            },
+            step_tiers(&[(3_600, 2 * PRECISION), (CUTOFF / 2, 15_000), (CUTOFF, 12_000)]),
+            ScoringConfig::Flat,
+        ]
+    }
+
+    fn factor(config: &ScoringConfig, stake_since_opening: u64, cutoff: u64) -> u64 {
+        calculate_earliness_factor(config, stake_since_opening, cutoff).unwrap()
+    }
+
+    #[test]
+    fn factor_at_opening() {
+        let expected = [2 * PRECISION, 2 * PRECISION, 2 * PRECISION, PRECISION];
+        for (config, expected) in configs().iter().zip(expected) {
+            validate_scoring_config(config).unwrap();
+            assert_eq!(factor(config, 0, CUTOFF), expected, "{config:?}");
+        }
+    }
+
+    #[test]
+    fn factor_at_and_after_cutoff_is_one() {
+        for config in configs() {
+            for stake_since_opening in [CUTOFF, CUTOFF + 1, u64::MAX] {
+                assert_eq!(factor(&config, stake_since_opening, CUTOFF), PRECISION, "{config:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn factor_does_not_overflow_at_u64_max() {
+        let configs = [
+            ScoringConfig::Linear,
+            ScoringConfig::ExponentialDecay {
+                half_life_seconds: u64::MAX,
+            },
+            ScoringConfig::ExponentialDecay { half_life_seconds: 1 },
+            step_tiers(&[(u64::MAX, 2 * PRECISION)]),
+            ScoringConfig::Flat,
+        ];
+        for config in configs {
+            for (stake_since_opening, cutoff) in [(u64::MAX, u64::MAX), (u64::MAX - 1, u64::MAX), (u64::MAX, 0)] {
+                let factor = factor(&config, stake_since_opening, cutoff);
+                assert!((PRECISION..=2 * PRECISION).contains(&factor), "{config:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn factor_never_increases_over_time() {
+        let mut configs = configs().to_vec();
+        configs.extend([1, 7, CUTOFF, u64::MAX].map(|half_life_seconds| ScoringConfig::ExponentialDecay {
+            half_life_seconds,
+        }));
+        for config in configs {
+            let mut prev = 2 * PRECISION;
+            for stake_since_opening in (0..=CUTOFF + 100).step_by(97).chain([u64::MAX]) {
+                let factor = factor(&config, stake_since_opening, CUTOFF);
+                assert!(factor <= prev, "{config:?} increased at {stake_since_opening}");
+                assert!(factor >= PRECISION, "{config:?}");
+                prev = factor;
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let invalid = [
+            ScoringConfig::ExponentialDecay { half_life_seconds: 0 },
+            ScoringConfig::ExponentialDecay {
+                half_life_seconds: MAX_EARLINESS_CUTOFF_SECONDS + 1,
+            },
+            // Increasing factor
+            step_tiers(&[(100, 12_000), (200, 15_000)]),
+            // Factor above 2.0x or below 1.0x
+            step_tiers(&[(100, 2 * PRECISION + 1)]),
+            step_tiers(&[(100, PRECISION - 1)]),
+            // Tier boundaries not increasing
+            step_tiers(&[(200, 15_000), (200, 12_000)]),
+            step_tiers(&[(MAX_EARLINESS_CUTOFF_SECONDS + 1, 15_000)]),
+            // Unused tier before a used one
+            step_tiers(&[(100, 15_000), (0, 0), (200, 12_000)]),
+        ];
+        for config in invalid {
+            assert!(validate_scoring_config(&config).is_err(), "{config:?}");
+        }
+        validate_scoring_config(&step_tiers(&[])).unwrap();
+    }
+
+    #[test]
+    fn user_score_does_not_wrap() {
+        // Earliest possible stake of the whole supply for the whole market
+        assert!(calculate_user_score(0, u64::MAX, 0, u64::MAX, CUTOFF, &ScoringConfig::Linear).is_err());
+        assert_eq!(
+            calculate_user_score(0, u64::MAX, u64::MAX, u64::MAX, CUTOFF, &ScoringConfig::Flat).unwrap(),
+            u64::MAX
+        );
+        assert!(calculate_user_score(10, 5, 10, 1, CUTOFF, &ScoringConfig::Flat).is_err());
+    }
+}
@@ -1,5 +1,73 @@
 use anchor_lang::prelude::*;
 
+pub const MAX_SCORE_TIERS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct ScoreTier {
+    // Seconds since market open until which this tier applies. 0 marks an unused tier.
+    pub until_seconds: u64,
+
+    // Earliness factor for stakes within this tier, scaled by `score::PRECISION`
+    pub factor: u64,
+}
+
+// Earliness curve used when scoring stakes. Every curve yields a factor in [1.0x..2.0x]
+// that multiplies the stake's time-in-market percentage.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ScoringConfig {
+    // 2 - x / earliness_cutoff_seconds
+    Linear,
+
+    // 1 + 2^(-x / half_life_seconds), dropping to 1.0x at earliness_cutoff_seconds
+    ExponentialDecay { half_life_seconds: u64 },
+
+    // Factor of the first tier whose `until_seconds` has not passed yet, 1.0x after the last tier
+    StepTiers { tiers: [ScoreTier; MAX_SCORE_TIERS] },
+
+    // No earliness boost, score is stake weighted by time in market only
+    Flat,
+}
+
+pub const MAX_WINNING_OPTIONS: usize = 4;
+
+// Protocol fee cap, 10% of every reward payout
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+// Lower bound for `reward_reclaim_grace_seconds`, winners get at least a day to claim
+pub const MIN_REWARD_RECLAIM_GRACE_SECONDS: u64 = 86_400;
+
+pub const MAX_LOSING_STAKE_SLASH_BPS: u16 = 10_000;
+
+// Options whose running stake is tracked in `OptionTallies`, markets cannot add more options
+pub const MAX_TALLIED_OPTIONS: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct WinningOption {
+    pub option_index: u16,
+
+    // Relative share of `reward_amount` paid out to this option's stakers
+    pub weight: u16,
+}
+
+// Market parameters stored by a `MarketTemplate` and applied to every market created from it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct MarketParams {
+    pub reward_amount: u64,
+    pub time_to_stake: u64,
+    pub time_to_reveal: u64,
+    pub market_authority: Option<Pubkey>,
+    pub unstake_delay_seconds: u64,
+    pub authorized_reader_pubkey: [u8; 32],
+    pub allow_closing_early: bool,
+
+    // `None` uses the central state's cutoff at the time each market is created
+    pub earliness_cutoff_seconds: Option<u64>,
+    pub scoring_config: ScoringConfig,
+    pub losing_stake_slash_bps: u16,
+    pub min_stake: u64,
+    pub max_stake: Option<u64>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct TokenVault {
@@ -20,14 +88,23 @@ pub struct CentralState {
 
     // Minimum deposit required when creating a market option
     pub min_option_deposit: u64,
+
+    // Seconds after the reveal period ends before creators can reclaim unclaimed rewards
+    pub reward_reclaim_grace_seconds: u64,
+
+    // Fee taken from every reward payout, in basis points
+    pub protocol_fee_bps: u16,
+
+    // Owner of the token accounts protocol fees are paid out to
+    pub treasury: Pubkey,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct OpportunityMarket {
     pub bump: u8,
-    pub creator: Pubkey,      // part of PDA seed
-    pub index: u64,           // part of PDA seed
+    pub creator: Pubkey,      // part of PDA seed, unless created from a template
+    pub index: u64,           // part of PDA seed, the series index for markets of a template
     pub total_options: u16,
 
     // If set, means market is funded and ready to be opened for staking.
@@ -35,34 +112,124 @@ pub struct OpportunityMarket {
     // `open_timestamp`, `time_to_stake` and `time_to_reveal`
     pub open_timestamp: Option<u64>,
 
+    // Open time set at creation. Once the market ATA holds `reward_amount`, anyone can
+    // open the market at this time through `activate_market`.
+    pub planned_open_timestamp: Option<u64>,
+
     // Seconds from open_timestamp
     pub time_to_stake: u64,
 
     // Seconds from open_timestamp + time_to_stake
     pub time_to_reveal: u64,
 
+    // Top-ranked winning option, set once the market is settled
     pub selected_option: Option<u16>,
 
+    // All winning options in rank order, each splitting `reward_amount` by weight
+    #[max_len(MAX_WINNING_OPTIONS)]
+    pub winning_options: Vec<WinningOption>,
+
     // Reward to be shared with stakers (in SPL token base units)
     pub reward_amount: u64,
 
+    // Part of `reward_amount` contributed through `sponsor_market` and not refunded yet.
+    // Held in the market ATA next to the creator's funding, never refunded to the creator.
+    pub total_sponsored: u64,
+
+    // Rewards paid out to winners so far
+    pub total_rewards_claimed: u64,
+
+    // Seconds after reveal end before the creator may reclaim what's left of `reward_amount`
+    pub reward_reclaim_grace_seconds: u64,
+
+    // Set once the creator has reclaimed the unallocated remainder, tallied winners can still claim
+    pub rewards_reclaimed: bool,
+
+    // Protocol fee snapshotted from the central state at creation, in basis points
+    pub protocol_fee_bps: u16,
+
+    // Fees withheld from reward payouts, held in the market ATA until `claim_protocol_fees`
+    pub protocol_fees_accrued: u64,
+
+    // Share of stake on non-winning options kept at reveal and added to the reward pool,
+    // in basis points. 0 refunds losing stakes in full.
+    pub losing_stake_slash_bps: u16,
+
+    // Slashed stake moved from the token vault into the market ATA so far
+    pub total_slashed: u64,
+
+    // Reveals queued and not called back yet. Once the reveal period is over and none are
+    // pending, `total_slashed` is final and rewards can be claimed.
+    pub pending_reveals: u32,
+
     // Optional authority that can manage the market (select winning option, extend reveal period)
     pub market_authority: Option<Pubkey>,
 
-    // SPL token mint for this market (vote tokens and rewards)
+    // SPL token mint staked in this market (vote tokens)
     pub mint: Pubkey,
 
+    // SPL token mint the reward is paid in, held in the market's ATA for this mint.
+    // Equal to `mint` unless the market was created with a separate reward token.
+    pub reward_mint: Pubkey,
+
     // Score component configuration
     pub earliness_cutoff_seconds: u64,
+    pub scoring_config: ScoringConfig,
 
     // Unstake delay seconds
     pub unstake_delay_seconds: u64,
 
+    // Bounds on the staked amount, checked inside the MPC computation. `min_stake` applies to the
+    // initial `stake`, `max_stake` to the position including `increase_stake` top-ups.
+    // 0 and `None` leave the respective side unbounded, with a `min_stake` of 0 even empty stakes pass.
+    pub min_stake: u64,
+    pub max_stake: Option<u64>,
+
     // Public key for voluntary disclosure of encrypted stake data
     pub authorized_reader_pubkey: [u8; 32],
 
     // If false, market can only be closed after stake period ends
     pub allow_closing_early: bool,
+
+    // Set by `cancel_market`. Rewards are returned and stakers can unstake without delay.
+    pub cancelled: bool,
+
+    // Share accounts initialized for this market and not yet closed
+    pub open_share_accounts: u32,
+
+    // `MarketSponsor` accounts of this market not yet refunded or closed
+    pub open_sponsorships: u32,
+
+    // Options closed via `close_market_option`, the market closes once all are gone
+    pub closed_options: u16,
+
+    // Template the market was created from and its position in the template's series
+    pub template: Option<Pubkey>,
+    pub series_index: Option<u64>,
+}
+
+impl OpportunityMarket {
+    // Key the market PDA is derived from along with `index`: the template for markets of a
+    // series, the creator otherwise
+    pub fn seed_key(&self) -> Pubkey {
+        self.template.unwrap_or(self.creator)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MarketTemplate {
+    pub bump: u8,
+    pub creator: Pubkey,   // part of PDA seed
+    pub template_id: u64,  // part of PDA seed
+
+    // SPL token mints staked in and rewarded with by every market in the series
+    pub mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub params: MarketParams,
+
+    // Series index given to the next market created from this template
+    pub next_series_index: u64,
 }
 
 #[account]
@@ -87,25 +254,70 @@ pub struct EncryptedTokenAccount {
 
     // Whether this ETA has been initialized with encrypted state via a callback
     pub is_initialized: bool,
+
+    // Incoming transfers are rejected until then, so a sender can't keep the ETA locked
+    pub incoming_transfers_blocked_until: u64,
+}
+
+// Lets `delegate` stake from `encrypted_token_account` on the owner's behalf, up to `spending_cap`
+// in total and until `expires_at`. Shares stay owned by, and refund to, the ETA owner.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeDelegation {
+    pub encrypted_state: [[u8; 32]; 1],  // amount staked by the delegate so far, encrypted to the owner
+    pub state_nonce: u128,
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub encrypted_token_account: Pubkey,
+    pub delegate: Pubkey,
+    pub spending_cap: u64,
+    pub expires_at: u64,
+
+    // Whether this delegation has been written with encrypted state via a callback
+    pub is_initialized: bool,
+
+    // Locked while waiting for Arcium MPC callback
+    pub locked: bool,
+}
+
+// A sponsor's contribution to a market's reward pool. Refundable while the market is unopened,
+// once it is cancelled or once its reveal period ended without a winner, otherwise it is paid
+// out as part of `reward_amount`.
+#[account]
+#[derive(InitSpace)]
+pub struct MarketSponsor {
+    pub bump: u8,
+    pub market: Pubkey,   // part of PDA seed
+    pub sponsor: Pubkey,  // part of PDA seed
+    pub amount: u64,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct ShareAccount {
-    pub encrypted_state: [[u8; 32]; 2],  // stake amount and option
+    pub encrypted_state: [[u8; 32]; 3],  // stake amount, option and amount-weighted stake time
     pub state_nonce: u128,
     pub bump: u8,
     pub owner: Pubkey,
     pub market: Pubkey,
 
-    pub encrypted_state_disclosure: [[u8; 32];2],
+    pub encrypted_state_disclosure: [[u8; 32]; 3],
     pub state_nonce_disclosure: u128,
+
+    // Stake already counted in the market's `OptionTallies`, encrypted to the MXE. Kept ahead of
+    // the `Option` fields so `sync_option_tally` can read it at a fixed offset.
+    pub encrypted_tallied_state: [[u8; 32]; 1],
+    pub tallied_state_nonce: u128,
+
     pub staked_at_timestamp: Option<u64>,
     pub unstaked_at_timestamp: Option<u64>,
 
     pub revealed_amount: Option<u64>,
     pub revealed_option: Option<u16>,
 
+    // Stake-weighted average of all stake timestamps, set on reveal and used for scoring
+    pub revealed_entry_timestamp: Option<u64>,
+
     // Amount that scales by time-in-market.
     pub revealed_score: Option<u64>,
 
@@ -113,6 +325,16 @@ pub struct ShareAccount {
 
     pub unstakeable_at_timestamp: Option<u64>,
 
+    // x25519 key of the ETA the share state was last encrypted to
+    pub user_pubkey: [u8; 32],
+
+    // Whether `encrypted_tallied_state` has been written by a `sync_option_tally` callback
+    pub is_tallied_state_initialized: bool,
+
+    // Set by every stake, increase and unstake, cleared once `sync_option_tally` has moved the
+    // change into the market's `OptionTallies`
+    pub tally_pending: bool,
+
     // Locked while waiting for Arcium MPC callback
     pub locked: bool,
 }
@@ -138,6 +360,29 @@ pub struct OpportunityMarketOption {
     pub initialized: bool,
 }
 
+#[account]
+#[derive(InitSpace)]
+pub struct OptionTallies {
+    // Staked amount per option, option i at index i - 1, encrypted to the MXE.
+    // Updated by `sync_option_tally` after every stake and unstake while the market is open.
+    pub encrypted_state: [[u8; 32]; MAX_TALLIED_OPTIONS],
+    pub state_nonce: u128,
+    pub bump: u8,
+    pub market: Pubkey,
+
+    // Whether this account has been written with encrypted state via a callback
+    pub is_initialized: bool,
+
+    // Locked while waiting for Arcium MPC callback
+    pub locked: bool,
+
+    // Snapshot of the tallies encrypted to the market's `authorized_reader_pubkey`,
+    // written by `disclose_option_tallies`
+    pub encrypted_state_disclosure: [[u8; 32]; MAX_TALLIED_OPTIONS],
+    pub state_nonce_disclosure: u128,
+    pub disclosed_at_timestamp: Option<u64>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct OpportunityMarketShare {
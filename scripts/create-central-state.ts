@@ -30,6 +30,8 @@ const PROGRAM_ID = address(process.env.PROGRAM_ID);
 const RPC_URL = process.env.RPC_URL;
 const EARLINESS_CUTOFF_SECONDS = BigInt(config.earlinessCutoffSeconds);
 const MIN_OPTION_DEPOSIT = BigInt(config.minOptionDeposit);
+const REWARD_RECLAIM_GRACE_SECONDS = BigInt(config.rewardReclaimGraceSeconds);
+const PROTOCOL_FEE_BPS = config.protocolFeeBps;
 
 function readSecretKey(path: string): Uint8Array {
   const file = fs.readFileSync(path);
@@ -67,6 +69,10 @@ async function main() {
   console.log(`Program: ${PROGRAM_ID}`);
   console.log(`Payer:   ${payer.address}`);
 
+  // Fees go to the payer unless a separate treasury is given
+  const treasury = process.env.TREASURY ? address(process.env.TREASURY) : payer.address;
+  console.log(`Treasury: ${treasury}`);
+
   const [centralStateAddress] = await getCentralStateAddress(PROGRAM_ID);
   const existing = await fetchMaybeCentralState(rpc, centralStateAddress);
 
@@ -81,7 +87,10 @@ async function main() {
     {
       payer,
       earlinessCutoffSeconds: EARLINESS_CUTOFF_SECONDS,
-      minOptionDeposit: MIN_OPTION_DEPOSIT
+      minOptionDeposit: MIN_OPTION_DEPOSIT,
+      rewardReclaimGraceSeconds: REWARD_RECLAIM_GRACE_SECONDS,
+      protocolFeeBps: PROTOCOL_FEE_BPS,
+      treasury,
     },
     { programAddress: PROGRAM_ID }
   );
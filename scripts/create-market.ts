@@ -88,6 +88,8 @@ async function main() {
     unstakeDelaySeconds: BigInt(config.unstakeDelaySeconds),
     authorizedReaderPubkey,
     allowClosingEarly: config.allowClosingEarly,
+    earlinessCutoffSeconds:
+      config.earlinessCutoffSeconds !== null ? BigInt(config.earlinessCutoffSeconds) : null,
     programAddress: PROGRAM_ID,
   });
 
@@ -18,6 +18,7 @@ import {
   closeEphemeralEncryptedTokenAccount,
   wrapEncryptedTokens,
   unwrapEncryptedTokens,
+  transferEncryptedTokens,
   randomComputationOffset,
   randomStateNonce,
   fetchEncryptedTokenAccount,
@@ -30,7 +31,8 @@ import { initializeAllCompDefs } from "./utils/comp-defs";
 import { sendTransaction } from "./utils/transaction";
 import { createMintAndFundAccount, createAta } from "./utils/spl-token";
 import { nonceToBytes } from "./utils/nonce";
-import { getArciumEnv, getMXEPublicKey } from "@arcium-hq/client";
+import { getArciumEnv, getMXEPublicKey, deserializeLE } from "@arcium-hq/client";
+import { randomBytes } from "crypto";
 import { OpportunityMarket } from "../target/types/opportunity_market";
 import * as fs from "fs";
 import * as os from "os";
@@ -73,6 +75,7 @@ describe("Encrypted Token Account (SPL)", () => {
     await initializeAllCompDefs(rpc, sendAndConfirm, secretKey, programId, [
       "wrap_encrypted_tokens",
       "unwrap_encrypted_tokens",
+      "transfer_encrypted_tokens",
       "close_ephemeral_encrypted_token_account",
     ]);
     mxePublicKey = await getMXEPublicKey(provider, program.programId);
@@ -543,4 +546,108 @@ describe("Encrypted Token Account (SPL)", () => {
     const ephemeralEtaAfter = await rpc.getAccountInfo(ephemeralEtaAddress).send();
     expect(ephemeralEtaAfter.value).to.be.null;
   });
+
+  it("can transfer encrypted tokens between ETAs", async () => {
+    // Sender and recipient each have their own wallet and x25519 keypair
+    const sender = await generateKeyPairSigner();
+    const recipient = await generateKeyPairSigner();
+    for (const signer of [sender, recipient]) {
+      await airdrop({
+        recipientAddress: signer.address,
+        lamports: lamports(2_000_000_000n),
+        commitment: "confirmed",
+      });
+    }
+
+    const splAmount = 100_000_000n;
+    const { mint, ata: senderAta } = await createMintAndFundAccount(
+      rpc,
+      sendAndConfirm,
+      sender,
+      sender.address,
+      splAmount,
+    );
+
+    const senderKeypair = generateX25519Keypair();
+    const recipientKeypair = generateX25519Keypair();
+
+    for (const [signer, keypair] of [[sender, senderKeypair], [recipient, recipientKeypair]] as const) {
+      const initEtaIx = await initEncryptedTokenAccount({
+        signer,
+        tokenMint: mint.address,
+        userPubkey: keypair.publicKey,
+        stateNonce: randomStateNonce(),
+      });
+      await sendTransaction(rpc, sendAndConfirm, signer, [initEtaIx], {
+        label: "initEncryptedTokenAccount",
+      });
+    }
+
+    const [senderEtaAddress] = await getEncryptedTokenAccountAddress(mint.address, sender.address, programId);
+    const [recipientEtaAddress] = await getEncryptedTokenAccountAddress(mint.address, recipient.address, programId);
+
+    await createTokenVaultAta(sender, mint.address);
+
+    // Sender wraps tokens, recipient's ETA stays uninitialized
+    const wrapAmount = 50_000_000n;
+    const wrapOffset = randomComputationOffset();
+    const wrapIx = await wrapEncryptedTokens(
+      {
+        signer: sender,
+        tokenMint: mint.address,
+        encryptedTokenAccount: senderEtaAddress,
+        signerTokenAccount: senderAta,
+        tokenProgram: TOKEN_PROGRAM_ADDRESS,
+        amount: wrapAmount,
+      },
+      {
+        clusterOffset: arciumEnv.arciumClusterOffset,
+        computationOffset: wrapOffset,
+      },
+    );
+    await sendTransaction(rpc, sendAndConfirm, sender, [wrapIx], {
+      label: "wrapEncryptedTokens",
+    });
+    await awaitComputationFinalization(rpc, wrapOffset);
+
+    const cipher = createCipher(senderKeypair.secretKey, mxePublicKey);
+    async function transfer(amount: bigint, label: string) {
+      const inputNonce = randomBytes(16);
+      const [amountCiphertext] = cipher.encrypt([amount], inputNonce);
+      const offset = randomComputationOffset();
+      const ix = await transferEncryptedTokens(
+        {
+          signer: sender,
+          tokenMint: mint.address,
+          senderEncryptedTokenAccount: senderEtaAddress,
+          recipientEncryptedTokenAccount: recipientEtaAddress,
+          amountCiphertext,
+          inputNonce: deserializeLE(inputNonce),
+        },
+        {
+          clusterOffset: arciumEnv.arciumClusterOffset,
+          computationOffset: offset,
+        },
+      );
+      await sendTransaction(rpc, sendAndConfirm, sender, [ix], { label });
+      await awaitComputationFinalization(rpc, offset);
+    }
+
+    // Transfer part of the balance
+    const transferAmount = 20_000_000n;
+    await transfer(transferAmount, "transferEncryptedTokens");
+
+    expect(await decryptEtaBalance(senderEtaAddress, senderKeypair.secretKey)).to.equal(wrapAmount - transferAmount);
+    expect(await decryptEtaBalance(recipientEtaAddress, recipientKeypair.secretKey)).to.equal(transferAmount);
+
+    // Transferring more than the remaining balance leaves both ETAs unchanged and unlocked
+    await transfer(wrapAmount, "transferEncryptedTokens (insufficient balance)");
+
+    expect(await decryptEtaBalance(senderEtaAddress, senderKeypair.secretKey)).to.equal(wrapAmount - transferAmount);
+    expect(await decryptEtaBalance(recipientEtaAddress, recipientKeypair.secretKey)).to.equal(transferAmount);
+    const senderEta = await fetchEncryptedTokenAccount(rpc, senderEtaAddress);
+    const recipientEta = await fetchEncryptedTokenAccount(rpc, recipientEtaAddress);
+    expect(senderEta.data.locked).to.be.false;
+    expect(recipientEta.data.locked).to.be.false;
+  });
 });
@@ -1,6 +1,6 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { address, some, isSome, isNone, createSolanaRpc, createSolanaRpcSubscriptions, sendAndConfirmTransactionFactory  } from "@solana/kit";
+import { address, some, isSome, isNone, createSolanaRpc, createSolanaRpcSubscriptions, sendAndConfirmTransactionFactory, createKeyPairSignerFromBytes, type KeyPairSigner } from "@solana/kit";
 import { fetchToken } from "@solana-program/token";
 import { expect } from "chai";
 
@@ -9,10 +9,25 @@ import { TestRunner } from "./utils/test-runner";
 import { initializeAllCompDefs } from "./utils/comp-defs";
 import { sleepUntilOnChainTimestamp } from "./utils/sleep";
 import { shouldThrowCustomError } from "./utils/errors";
+import { sendTransaction } from "./utils/transaction";
+import {
+  fetchCentralState,
+  fetchMaybeCentralState,
+  getCentralStateAddress,
+  getInitCentralStateInstructionAsync,
+  getUpdateCentralStateInstructionAsync,
+} from "../js/src";
 import { generateX25519Keypair, X25519Keypair } from "../js/src/x25519/keypair";
 import {
   OPPORTUNITY_MARKET_ERROR__CLOSING_EARLY_NOT_ALLOWED,
+  OPPORTUNITY_MARKET_ERROR__RECLAIM_GRACE_PERIOD_NOT_OVER,
+  OPPORTUNITY_MARKET_ERROR__REWARDS_ALREADY_RECLAIMED,
+  OPPORTUNITY_MARKET_ERROR__MARKET_CANCELLED,
+  OPPORTUNITY_MARKET_ERROR__SHARE_ACCOUNTS_STILL_OPEN,
+  OPPORTUNITY_MARKET_ERROR__OPTIONS_STILL_OPEN,
   OPPORTUNITY_MARKET_ERROR__UNSTAKE_DELAY_NOT_MET,
+  OPPORTUNITY_MARKET_ERROR__INVALID_WINNING_OPTIONS,
+  OPPORTUNITY_MARKET_ERROR__PROTOCOL_FEES_UNCLAIMED,
 } from "../js/src/generated/errors/opportunityMarket";
 
 import * as fs from "fs";
@@ -44,17 +59,62 @@ describe("OpportunityMarket", () => {
 
   const programId = address(program.programId.toBase58());
 
+  const rpc = createSolanaRpc(RPC_URL);
+  const rpcSubscriptions = createSolanaRpcSubscriptions(WS_URL);
+  const sendAndConfirmTransaction = sendAndConfirmTransactionFactory({ rpc, rpcSubscriptions });
+
+  // Owner wallet, also the central state authority and treasury
+  let owner: KeyPairSigner;
+
+  /**
+   * Update the protocol fee, keeping the rest of the central state as is.
+   */
+  async function setProtocolFee(protocolFeeBps: number): Promise<void> {
+    const [centralStateAddress] = await getCentralStateAddress(programId);
+    const centralState = await fetchCentralState(rpc, centralStateAddress);
+    const ix = await getUpdateCentralStateInstructionAsync(
+      {
+        authority: owner,
+        earlinessCutoffSeconds: centralState.data.earlinessCutoffSeconds,
+        minOptionDeposit: centralState.data.minOptionDeposit,
+        rewardReclaimGraceSeconds: centralState.data.rewardReclaimGraceSeconds,
+        protocolFeeBps,
+        treasury: centralState.data.treasury,
+      },
+      { programAddress: programId }
+    );
+    await sendTransaction(rpc, sendAndConfirmTransaction, owner, [ix], {
+      label: `Set protocol fee to ${protocolFeeBps} bps`,
+    });
+  }
+
   before(async () => {
     // Load owner secret key
     const file = fs.readFileSync(`${os.homedir()}/.config/solana/id.json`);
     const secretKey = new Uint8Array(JSON.parse(file.toString()));
+    owner = await createKeyPairSignerFromBytes(secretKey);
 
     // Initialize all computation definitions
-    const rpc = createSolanaRpc(RPC_URL);
-    const rpcSubscriptions = createSolanaRpcSubscriptions(WS_URL);
-    const sendAndConfirmTransaction = sendAndConfirmTransactionFactory({ rpc, rpcSubscriptions });
-
     await initializeAllCompDefs(rpc, sendAndConfirmTransaction, secretKey, programId);
+
+    // Initialize central state with the owner as authority, so tests can update it
+    const [centralStateAddress] = await getCentralStateAddress(programId);
+    if (!(await fetchMaybeCentralState(rpc, centralStateAddress)).exists) {
+      const ix = await getInitCentralStateInstructionAsync(
+        {
+          payer: owner,
+          earlinessCutoffSeconds: 86_400n,
+          minOptionDeposit: 1n,
+          rewardReclaimGraceSeconds: 0n,
+          protocolFeeBps: 0,
+          treasury: owner.address,
+        },
+        { programAddress: programId }
+      );
+      await sendTransaction(rpc, sendAndConfirmTransaction, owner, [ix], {
+        label: "Init central state",
+      });
+    }
   });
 
   it("passes full opportunity market flow", async () => {
@@ -564,4 +624,489 @@ describe("OpportunityMarket", () => {
     expect(market.data.selectedOption).to.deep.equal(some(optionA));
   });
 
+  it("returns unclaimed rewards to the creator after the grace period", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const numParticipants = 2;
+    const observer = loadObserverKeypair();
+
+    const runner = await TestRunner.initialize(provider, programId, {
+      rpcUrl: RPC_URL,
+      wsUrl: WS_URL,
+      numParticipants,
+      airdropLamports: 2_000_000_000n,
+      initialTokenAmount: 2_000_000_000n,
+      marketConfig: {
+        rewardAmount: marketFundingAmount,
+        timeToStake: 60n,
+        timeToReveal: 20n,
+        authorizedReaderPubkey: observer.publicKey,
+      },
+    });
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const wrapAmount = 100_000_000n;
+    for (const userId of runner.participants) {
+      await runner.initEncryptedTokenAccount(userId);
+      await runner.wrapEncryptedTokens(userId, wrapAmount);
+    }
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    await runner.addOptionAsCreator("Option B");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    // Both participants back the winning option
+    const [claimer, absentee] = runner.participants;
+    const [claimerShareAccount, absenteeShareAccount] = await runner.stakeOnOptionBatch([
+      { userId: claimer, amount: 50n, optionIndex: optionA },
+      { userId: absentee, amount: 50n, optionIndex: optionA },
+    ]);
+
+    await runner.selectOption(optionA);
+
+    await runner.revealSharesBatch([
+      { userId: claimer, shareAccountId: claimerShareAccount },
+      { userId: absentee, shareAccountId: absenteeShareAccount },
+    ]);
+    await runner.incrementOptionTallyBatch([
+      { userId: claimer, optionIndex: optionA, shareAccountId: claimerShareAccount },
+      { userId: absentee, optionIndex: optionA, shareAccountId: absenteeShareAccount },
+    ]);
+
+    // Reclaiming is not possible while the reveal period is running
+    await shouldThrowCustomError(
+      () => runner.reclaimMarketRewards(),
+      OPPORTUNITY_MARKET_ERROR__RECLAIM_GRACE_PERIOD_NOT_OVER
+    );
+
+    const timeToReveal = Number(runner.getTimeToReveal());
+    await sleepUntilOnChainTimestamp(new Date().getTime() / 1000 + timeToReveal);
+
+    const rpc = runner.getRpc();
+    const marketAta = await runner.getMarketAta();
+
+    // Only one of the winners claims
+    const claimerBalanceBefore = (await fetchToken(rpc, runner.getUserTokenAccount(claimer))).data.amount;
+    await runner.closeShareAccount(claimer, optionA, claimerShareAccount);
+    const claimerGain =
+      (await fetchToken(rpc, runner.getUserTokenAccount(claimer))).data.amount - claimerBalanceBefore;
+    expect(claimerGain > 0n && claimerGain < marketFundingAmount).to.be.true;
+
+    let market = await runner.fetchMarket();
+    expect(market.data.totalRewardsClaimed).to.equal(claimerGain);
+
+    // Creator reclaims everything that was not paid out (test central state has no grace period)
+    const creatorBalanceBefore = (await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount;
+    await runner.reclaimMarketRewards();
+    const creatorGain =
+      (await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount - creatorBalanceBefore;
+
+    expect(creatorGain).to.equal(marketFundingAmount - claimerGain);
+    expect((await fetchToken(rpc, marketAta)).data.amount).to.equal(0n);
+
+    market = await runner.fetchMarket();
+    expect(market.data.rewardsReclaimed).to.be.true;
+
+    // Late claims close the share account without paying out
+    const absenteeBalanceBefore = (await fetchToken(rpc, runner.getUserTokenAccount(absentee))).data.amount;
+    await runner.closeShareAccount(absentee, optionA, absenteeShareAccount);
+    const absenteeBalanceAfter = (await fetchToken(rpc, runner.getUserTokenAccount(absentee))).data.amount;
+    expect(absenteeBalanceAfter).to.equal(absenteeBalanceBefore);
+    expect(await runner.accountExists(await runner.getShareAccountAddress(absentee, absenteeShareAccount))).to.be.false;
+
+    await shouldThrowCustomError(
+      () => runner.reclaimMarketRewards(),
+      OPPORTUNITY_MARKET_ERROR__REWARDS_ALREADY_RECLAIMED
+    );
+  });
+
+  it("refunds rewards and stakes when the market is cancelled", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const observer = loadObserverKeypair();
+
+    const runner = await TestRunner.initialize(provider, programId, {
+      rpcUrl: RPC_URL,
+      wsUrl: WS_URL,
+      numParticipants: 2,
+      airdropLamports: 2_000_000_000n,
+      initialTokenAmount: 2_000_000_000n,
+      marketConfig: {
+        rewardAmount: marketFundingAmount,
+        timeToStake: 120n,
+        timeToReveal: 20n,
+        unstakeDelaySeconds: 3600n,
+        authorizedReaderPubkey: observer.publicKey,
+      },
+    });
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const [staker, executor] = runner.participants;
+    const wrapAmount = 100_000_000n;
+    const stakeAmount = 40_000_000n;
+    await runner.initEncryptedTokenAccount(staker);
+    await runner.initEncryptedTokenAccount(executor);
+    await runner.wrapEncryptedTokens(staker, wrapAmount);
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    const { optionIndex: optionB } = await runner.addOptionAsCreator("Option B");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    const shareAccountId = await runner.stakeOnOption(staker, stakeAmount, optionA);
+    expect(await runner.decryptEtaBalance(staker)).to.equal(wrapAmount - stakeAmount);
+
+    // Cancelling returns the full reward to the creator
+    const rpc = runner.getRpc();
+    const creatorBalanceBefore = (await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount;
+    await runner.cancelMarket();
+    const creatorBalanceAfter = (await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount;
+    expect(creatorBalanceAfter - creatorBalanceBefore).to.equal(marketFundingAmount);
+    expect((await fetchToken(rpc, await runner.getMarketAta())).data.amount).to.equal(0n);
+
+    const market = await runner.fetchMarket();
+    expect(market.data.cancelled).to.be.true;
+
+    // No further staking or resolution on a cancelled market
+    await shouldThrowCustomError(
+      () => runner.selectOption(optionA),
+      OPPORTUNITY_MARKET_ERROR__MARKET_CANCELLED
+    );
+
+    // Stake is refunded right away, without initiating an unstake or waiting for the delay
+    await runner.doUnstakeEarly(executor, staker, shareAccountId);
+    expect(await runner.decryptEtaBalance(staker)).to.equal(wrapAmount);
+
+    const shareAccount = await runner.fetchShareAccountData(staker, shareAccountId);
+    expect(isSome(shareAccount.data.unstakedAtTimestamp)).to.be.true;
+
+    // Options can't be closed while the share account still references them
+    await shouldThrowCustomError(
+      () => runner.closeMarketOption(optionA),
+      OPPORTUNITY_MARKET_ERROR__SHARE_ACCOUNTS_STILL_OPEN
+    );
+
+    // Refunded share accounts can be closed without revealing
+    await runner.closeShareAccount(staker, optionA, shareAccountId);
+    expect(await runner.accountExists(await runner.getShareAccountAddress(staker, shareAccountId))).to.be.false;
+    expect((await runner.fetchMarket()).data.openShareAccounts).to.equal(0);
+
+    // Market can only be closed after all of its options
+    await runner.closeMarketOption(optionA);
+    await shouldThrowCustomError(
+      () => runner.closeMarket(),
+      OPPORTUNITY_MARKET_ERROR__OPTIONS_STILL_OPEN
+    );
+    await runner.closeMarketOption(optionB);
+    await runner.closeMarket();
+
+    expect(await runner.accountExists(await runner.getOptionAddress(optionA))).to.be.false;
+    expect(await runner.accountExists(await runner.getOptionAddress(optionB))).to.be.false;
+    expect(await runner.accountExists(await runner.getMarketAta())).to.be.false;
+    expect(await runner.accountExists(runner.market)).to.be.false;
+  });
+
+  it("allows topping up an existing stake", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const observer = loadObserverKeypair();
+
+    const runner = await TestRunner.initialize(provider, programId, {
+      rpcUrl: RPC_URL,
+      wsUrl: WS_URL,
+      numParticipants: 1,
+      airdropLamports: 2_000_000_000n,
+      initialTokenAmount: 2_000_000_000n,
+      marketConfig: {
+        rewardAmount: marketFundingAmount,
+        timeToStake: 60n,
+        timeToReveal: 20n,
+        authorizedReaderPubkey: observer.publicKey,
+      },
+    });
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const user = runner.participants[0];
+    const wrapAmount = 100_000_000n;
+    const initialStake = 30_000_000n;
+    const topUp = 10_000_000n;
+    await runner.initEncryptedTokenAccount(user);
+    await runner.wrapEncryptedTokens(user, wrapAmount);
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    await runner.addOptionAsCreator("Option B");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    const shareAccountId = await runner.stakeOnOption(user, initialStake, optionA);
+    await runner.increaseStake(user, shareAccountId, topUp);
+
+    // Amount grows, option stays the same, both for the owner and the authorized reader
+    const decrypted = runner.decryptStakeAmount(user, shareAccountId);
+    expect(decrypted.amount).to.equal(initialStake + topUp);
+    expect(decrypted.optionIndex).to.equal(BigInt(optionA));
+    const disclosed = runner.decryptDisclosedStakeAmount(user, shareAccountId, observer);
+    expect(disclosed.amount).to.equal(initialStake + topUp);
+    expect(disclosed.optionIndex).to.equal(BigInt(optionA));
+    expect(await runner.decryptEtaBalance(user)).to.equal(wrapAmount - initialStake - topUp);
+
+    await runner.selectOption(optionA);
+    await runner.revealShares(user, shareAccountId);
+
+    // Entry time is the stake-weighted average, between the first stake and the top-up
+    const shareAccount = await runner.fetchShareAccountData(user, shareAccountId);
+    expect(shareAccount.data.revealedAmount).to.deep.equal(some(initialStake + topUp));
+    if (!isSome(shareAccount.data.stakedAtTimestamp) || !isSome(shareAccount.data.revealedEntryTimestamp)) {
+      throw new Error("Expected stake and entry timestamps to be set");
+    }
+    const firstStakedAt = shareAccount.data.stakedAtTimestamp.value;
+    const entryTimestamp = shareAccount.data.revealedEntryTimestamp.value;
+    expect(entryTimestamp >= firstStakedAt).to.be.true;
+    expect(entryTimestamp <= BigInt(Math.floor(Date.now() / 1000))).to.be.true;
+
+    await runner.incrementOptionTally(user, optionA, shareAccountId);
+    const optionAccount = await runner.fetchOptionData(optionA);
+    expect(optionAccount.data.totalShares).to.deep.equal(some(initialStake + topUp));
+  });
+
+  it("splits rewards between several weighted winning options", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const observer = loadObserverKeypair();
+
+    const runner = await TestRunner.initialize(provider, programId, {
+      rpcUrl: RPC_URL,
+      wsUrl: WS_URL,
+      numParticipants: 3,
+      airdropLamports: 2_000_000_000n,
+      initialTokenAmount: 2_000_000_000n,
+      marketConfig: {
+        rewardAmount: marketFundingAmount,
+        timeToStake: 60n,
+        timeToReveal: 20n,
+        authorizedReaderPubkey: observer.publicKey,
+      },
+    });
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const wrapAmount = 100_000_000n;
+    for (const userId of runner.participants) {
+      await runner.initEncryptedTokenAccount(userId);
+      await runner.wrapEncryptedTokens(userId, wrapAmount);
+    }
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    const { optionIndex: optionB } = await runner.addOptionAsCreator("Option B");
+    const { optionIndex: optionC } = await runner.addOptionAsCreator("Option C");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    const [userA, userB, userC] = runner.participants;
+    const stakes = [
+      { userId: userA, amount: 50_000_000n, optionIndex: optionA },
+      { userId: userB, amount: 50_000_000n, optionIndex: optionB },
+      { userId: userC, amount: 50_000_000n, optionIndex: optionC },
+    ];
+    const shareAccountIds = await runner.stakeOnOptionBatch(stakes);
+
+    // Invalid settlements are rejected
+    await shouldThrowCustomError(
+      () => runner.selectWinningOptions([{ optionIndex: optionA, weight: 1 }, { optionIndex: optionA, weight: 1 }]),
+      OPPORTUNITY_MARKET_ERROR__INVALID_WINNING_OPTIONS
+    );
+    await shouldThrowCustomError(
+      () => runner.selectWinningOptions([{ optionIndex: optionA, weight: 0 }]),
+      OPPORTUNITY_MARKET_ERROR__INVALID_WINNING_OPTIONS
+    );
+
+    // Option A ranks first with 3/4 of the reward, option B gets 1/4, option C nothing
+    await runner.selectWinningOptions([
+      { optionIndex: optionA, weight: 3 },
+      { optionIndex: optionB, weight: 1 },
+    ]);
+
+    const market = await runner.fetchMarket();
+    expect(market.data.selectedOption).to.deep.equal(some(optionA));
+    expect(market.data.winningOptions).to.deep.equal([
+      { optionIndex: optionA, weight: 3 },
+      { optionIndex: optionB, weight: 1 },
+    ]);
+
+    const reveals = stakes.map((s, i) => ({ userId: s.userId, shareAccountId: shareAccountIds[i] }));
+    await runner.revealSharesBatch(reveals);
+    await runner.incrementOptionTallyBatch(
+      stakes.map((s, i) => ({ userId: s.userId, optionIndex: s.optionIndex, shareAccountId: shareAccountIds[i] }))
+    );
+
+    const timeToReveal = Number(runner.getTimeToReveal());
+    await sleepUntilOnChainTimestamp(new Date().getTime() / 1000 + timeToReveal);
+
+    // Each option has a single staker, so they receive the option's whole slice
+    const rpc = runner.getRpc();
+    const gains: bigint[] = [];
+    for (const [i, s] of stakes.entries()) {
+      const before = (await fetchToken(rpc, runner.getUserTokenAccount(s.userId))).data.amount;
+      await runner.closeShareAccount(s.userId, s.optionIndex, shareAccountIds[i]);
+      gains.push((await fetchToken(rpc, runner.getUserTokenAccount(s.userId))).data.amount - before);
+    }
+
+    expect(gains[0]).to.equal((marketFundingAmount * 3n) / 4n);
+    expect(gains[1]).to.equal(marketFundingAmount / 4n);
+    expect(gains[2]).to.equal(0n);
+  });
+
+  it("withholds protocol fees from rewards and pays them to the treasury", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const protocolFeeBps = 1_000; // 10%
+    const observer = loadObserverKeypair();
+
+    // Markets snapshot the fee at creation, reset it right after so other tests are unaffected
+    await setProtocolFee(protocolFeeBps);
+    let runner: TestRunner;
+    try {
+      runner = await TestRunner.initialize(provider, programId, {
+        rpcUrl: RPC_URL,
+        wsUrl: WS_URL,
+        numParticipants: 1,
+        airdropLamports: 2_000_000_000n,
+        initialTokenAmount: 2_000_000_000n,
+        marketConfig: {
+          rewardAmount: marketFundingAmount,
+          timeToStake: 60n,
+          timeToReveal: 20n,
+          authorizedReaderPubkey: observer.publicKey,
+        },
+      });
+    } finally {
+      await setProtocolFee(0);
+    }
+
+    let market = await runner.fetchMarket();
+    expect(market.data.protocolFeeBps).to.equal(protocolFeeBps);
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const [user] = runner.participants;
+    await runner.initEncryptedTokenAccount(user);
+    await runner.wrapEncryptedTokens(user, 100_000_000n);
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    await runner.addOptionAsCreator("Option B");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    const shareAccountId = await runner.stakeOnOption(user, 50_000_000n, optionA);
+    await runner.selectOption(optionA);
+    await runner.revealShares(user, shareAccountId);
+    await runner.incrementOptionTally(user, optionA, shareAccountId);
+
+    const timeToReveal = Number(runner.getTimeToReveal());
+    await sleepUntilOnChainTimestamp(new Date().getTime() / 1000 + timeToReveal);
+
+    // The only winner receives the whole reward minus the fee
+    const expectedFee = (marketFundingAmount * BigInt(protocolFeeBps)) / 10_000n;
+    const userBalanceBefore = (await fetchToken(rpc, runner.getUserTokenAccount(user))).data.amount;
+    await runner.closeShareAccount(user, optionA, shareAccountId);
+    const userGain = (await fetchToken(rpc, runner.getUserTokenAccount(user))).data.amount - userBalanceBefore;
+    expect(userGain).to.equal(marketFundingAmount - expectedFee);
+
+    market = await runner.fetchMarket();
+    expect(market.data.protocolFeesAccrued).to.equal(expectedFee);
+    expect(market.data.totalRewardsClaimed).to.equal(marketFundingAmount);
+
+    // Reclaiming leaves the withheld fees alone
+    const creatorBalanceBefore = (await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount;
+    await runner.reclaimMarketRewards();
+    expect((await fetchToken(rpc, runner.getCreatorTokenAccount())).data.amount).to.equal(creatorBalanceBefore);
+
+    // Fees can only be paid out to the treasury
+    await runner.closeMarketOption(optionA);
+    await runner.closeMarketOption(optionA + 1);
+    await shouldThrowCustomError(
+      () => runner.closeMarket(),
+      OPPORTUNITY_MARKET_ERROR__PROTOCOL_FEES_UNCLAIMED
+    );
+
+    const treasuryTokenAccount = await runner.createTokenAccount(owner.address);
+    await runner.claimProtocolFees(treasuryTokenAccount);
+    expect((await fetchToken(rpc, treasuryTokenAccount)).data.amount).to.equal(expectedFee);
+
+    market = await runner.fetchMarket();
+    expect(market.data.protocolFeesAccrued).to.equal(0n);
+    await runner.closeMarket();
+  });
+
+  it("slashes losing stakes into the winners' reward pool", async () => {
+    const marketFundingAmount = 1_000_000_000n;
+    const observer = loadObserverKeypair();
+
+    const runner = await TestRunner.initialize(provider, programId, {
+      rpcUrl: RPC_URL,
+      wsUrl: WS_URL,
+      numParticipants: 2,
+      airdropLamports: 2_000_000_000n,
+      initialTokenAmount: 2_000_000_000n,
+      marketConfig: {
+        rewardAmount: marketFundingAmount,
+        timeToStake: 60n,
+        timeToReveal: 20n,
+        authorizedReaderPubkey: observer.publicKey,
+        losingStakeSlashBps: 5_000, // 50%
+      },
+    });
+
+    await runner.fundMarket();
+    const openTimestamp = await runner.openMarket();
+
+    const wrapAmount = 100_000_000n;
+    for (const userId of runner.participants) {
+      await runner.initEncryptedTokenAccount(userId);
+      await runner.wrapEncryptedTokens(userId, wrapAmount);
+    }
+
+    const { optionIndex: optionA } = await runner.addOptionAsCreator("Option A");
+    const { optionIndex: optionB } = await runner.addOptionAsCreator("Option B");
+
+    await sleepUntilOnChainTimestamp(Number(openTimestamp) + ONCHAIN_TIMESTAMP_BUFFER_SECONDS);
+
+    const [winner, loser] = runner.participants;
+    const stakeAmount = 50_000_000n;
+    const [winnerShareAccount, loserShareAccount] = await runner.stakeOnOptionBatch([
+      { userId: winner, amount: stakeAmount, optionIndex: optionA },
+      { userId: loser, amount: stakeAmount, optionIndex: optionB },
+    ]);
+
+    await runner.selectOption(optionA);
+    await runner.revealSharesBatch([
+      { userId: winner, shareAccountId: winnerShareAccount },
+      { userId: loser, shareAccountId: loserShareAccount },
+    ]);
+
+    // Winner gets the full stake back, loser only half of it
+    const slashed = stakeAmount / 2n;
+    expect(await runner.decryptEtaBalance(winner)).to.equal(wrapAmount);
+    expect(await runner.decryptEtaBalance(loser)).to.equal(wrapAmount - slashed);
+
+    const market = await runner.fetchMarket();
+    expect(market.data.totalSlashed).to.equal(slashed);
+    const rpc = runner.getRpc();
+    expect((await fetchToken(rpc, await runner.getMarketAta())).data.amount).to.equal(marketFundingAmount + slashed);
+
+    await runner.incrementOptionTally(winner, optionA, winnerShareAccount);
+
+    const timeToReveal = Number(runner.getTimeToReveal());
+    await sleepUntilOnChainTimestamp(new Date().getTime() / 1000 + timeToReveal);
+
+    // The only winner takes the reward plus the slashed stake
+    const balanceBefore = (await fetchToken(rpc, runner.getUserTokenAccount(winner))).data.amount;
+    await runner.closeShareAccount(winner, optionA, winnerShareAccount);
+    const gain = (await fetchToken(rpc, runner.getUserTokenAccount(winner))).data.amount - balanceBefore;
+    expect(gain).to.equal(marketFundingAmount + slashed);
+  });
 });
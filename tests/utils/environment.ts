@@ -244,8 +244,11 @@ export async function createTestEnvironment(
   // Initialize central state
   const initCentralStateIx = await getInitCentralStateInstructionAsync({
     payer: creatorAccount.keypair,
-    earlinessCutoffSeconds: 0n,
+    earlinessCutoffSeconds: 86_400n,
     minOptionDeposit: 1n,
+    rewardReclaimGraceSeconds: 0n,
+    protocolFeeBps: 0,
+    treasury: creatorAccount.keypair.address,
   });
 
   const { value: csBlockhash } = await rpc.getLatestBlockhash({ commitment: "confirmed" }).send();
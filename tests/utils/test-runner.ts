@@ -24,6 +24,7 @@ import {
 } from "@solana-program/token";
 import {
   createMarket,
+  type ScoringConfigArgs,
   fetchOpportunityMarket,
   fetchMaybeCentralState,
   randomComputationOffset,
@@ -37,10 +38,17 @@ import {
   addMarketOptionAsCreator,
   initShareAccount,
   stake,
+  increaseStake,
   selectOption,
+  selectWinningOptions,
   revealShares,
   incrementOptionTally,
   closeShareAccount,
+  reclaimMarketRewards,
+  claimProtocolFees,
+  cancelMarket,
+  closeMarket,
+  closeMarketOption,
   unstakeEarly as unstakeEarlyIx,
   doUnstakeEarly as doUnstakeEarlyIx,
   openMarket as openMarketIx,
@@ -91,6 +99,9 @@ interface MarketConfig {
   unstakeDelaySeconds: bigint;
   authorizedReaderPubkey: Uint8Array;
   allowClosingEarly: boolean;
+  earlinessCutoffSeconds: bigint | null;
+  scoringConfig: ScoringConfigArgs;
+  losingStakeSlashBps: number;
 }
 
 export interface TestRunnerConfig {
@@ -142,6 +153,9 @@ const DEFAULT_CONFIG: Required<TestRunnerConfig> = {
     timeToReveal: 60n, // 1 minute
     unstakeDelaySeconds: 10n, // 10 seconds
     allowClosingEarly: true, // Allow market to be closed before stake period ends
+    earlinessCutoffSeconds: null, // Use central state default
+    scoringConfig: { __kind: "Linear" },
+    losingStakeSlashBps: 0,
   },
 };
 
@@ -370,8 +384,11 @@ export class TestRunner {
       console.log("Initializing central state...");
       const initCentralStateIx = await getInitCentralStateInstructionAsync({
         payer: runner.marketCreator.solanaKeypair,
-        earlinessCutoffSeconds: 0n,
+        earlinessCutoffSeconds: 86_400n,
         minOptionDeposit: 1n,
+        rewardReclaimGraceSeconds: 0n,
+        protocolFeeBps: 0,
+        treasury: runner.marketCreator.solanaKeypair.address,
       });
 
       await sendTransaction(runner.rpc, runner.sendAndConfirm, runner.marketCreator.solanaKeypair, [initCentralStateIx], {
@@ -397,6 +414,9 @@ export class TestRunner {
       unstakeDelaySeconds: marketConfig.unstakeDelaySeconds,
       authorizedReaderPubkey: marketConfig.authorizedReaderPubkey,
       allowClosingEarly: marketConfig.allowClosingEarly,
+      earlinessCutoffSeconds: marketConfig.earlinessCutoffSeconds,
+      scoringConfig: marketConfig.scoringConfig,
+      losingStakeSlashBps: marketConfig.losingStakeSlashBps,
     });
 
     await sendTransaction(runner.rpc, runner.sendAndConfirm, runner.marketCreator.solanaKeypair, [createMarketIx], {
@@ -537,6 +557,95 @@ export class TestRunner {
     });
   }
 
+  async selectWinningOptions(winningOptions: { optionIndex: number; weight: number }[]): Promise<void> {
+    const ix = selectWinningOptions({
+      authority: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      winningOptions,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: "Select winning options",
+    });
+  }
+
+  async cancelMarket(): Promise<void> {
+    const ix = await cancelMarket({
+      authority: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      tokenMint: this.mint.address,
+      creatorTokenAccount: this.marketCreator.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: "Cancel market",
+    });
+  }
+
+  async closeMarketOption(optionIndex: number): Promise<void> {
+    const option = await this.fetchOptionData(optionIndex);
+    const ix = await closeMarketOption({
+      creator: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      optionCreator: option.data.creator,
+      optionIndex,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: `Close market option ${optionIndex}`,
+    });
+  }
+
+  async closeMarket(): Promise<void> {
+    const ix = await closeMarket({
+      creator: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      tokenMint: this.mint.address,
+      creatorTokenAccount: this.marketCreator.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: "Close market",
+    });
+  }
+
+  async reclaimMarketRewards(): Promise<void> {
+    const ix = await reclaimMarketRewards({
+      creator: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      tokenMint: this.mint.address,
+      creatorTokenAccount: this.marketCreator.tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: "Reclaim market rewards",
+    });
+  }
+
+  async claimProtocolFees(treasuryTokenAccount: Address): Promise<void> {
+    const ix = await claimProtocolFees({
+      signer: this.marketCreator.solanaKeypair,
+      market: this.marketAddress,
+      tokenMint: this.mint.address,
+      treasuryTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+    });
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, [ix], {
+      label: "Claim protocol fees",
+    });
+  }
+
+  /**
+   * Create an ATA of the market mint for an arbitrary owner, paid by the market creator.
+   */
+  async createTokenAccount(owner: Address): Promise<Address> {
+    return createAta(this.rpc, this.sendAndConfirm, this.marketCreator.solanaKeypair, this.mint.address, owner);
+  }
+
   // ============================================================================
   // ETA Operations
   // ============================================================================
@@ -792,6 +901,48 @@ export class TestRunner {
     return shareAccountId;
   }
 
+  async increaseStake(userId: Address, shareAccountId: number, amount: bigint): Promise<void> {
+    const user = this.getUser(userId);
+    this.assertEtaInitialized(user);
+    const shareAccount = this.getShareAccountInfo(userId, shareAccountId);
+
+    const cipher = createCipher(user.x25519Keypair.secretKey, this.mxePublicKey);
+    const inputNonce = randomBytes(16);
+    const [amountCiphertext] = cipher.encrypt([amount], inputNonce);
+    const computationOffset = randomComputationOffset();
+
+    const [userEta] = await getEncryptedTokenAccountAddress(this.mint.address, userId);
+
+    const ix = await increaseStake(
+      {
+        signer: user.solanaKeypair,
+        market: this.marketAddress,
+        userEta,
+        shareAccountId,
+        amountCiphertext,
+        inputNonce: deserializeLE(inputNonce),
+        authorizedReaderNonce: deserializeLE(randomBytes(16)),
+      },
+      this.getArciumConfig(computationOffset)
+    );
+
+    await sendTransaction(this.rpc, this.sendAndConfirm, user.solanaKeypair, [ix], {
+      label: `Increase stake`,
+    });
+
+    const result = await awaitComputationFinalization(this.rpc, computationOffset);
+    this.assertComputationSucceeded(result, "increaseStake");
+
+    // Refresh stored share account info with the new encrypted state
+    const [shareAccountAddress] = await getShareAccountAddressPda(userId, this.marketAddress, shareAccountId);
+    const shareAccountData = await fetchShareAccount(this.rpc, shareAccountAddress);
+    shareAccount.amount += amount;
+    shareAccount.encryptedState = shareAccountData.data.encryptedState;
+    shareAccount.stateNonce = shareAccountData.data.stateNonce;
+    shareAccount.encryptedStateDisclosure = shareAccountData.data.encryptedStateDisclosure;
+    shareAccount.stateNonceDisclosure = shareAccountData.data.stateNonceDisclosure;
+  }
+
   async revealSharesBatch(reveals: RevealRequest[]): Promise<void> {
     // Group reveals by user to handle ETA locking correctly
     // Each reveal locks the ETA until callback completes, so same-user reveals must be sequential
@@ -819,6 +970,8 @@ export class TestRunner {
               market: this.marketAddress,
               userEta,
               shareAccountId: r.shareAccountId,
+              tokenMint: this.mint.address,
+              tokenProgram: TOKEN_PROGRAM_ADDRESS,
             },
             this.getArciumConfig(computationOffset)
           );
@@ -963,6 +1116,11 @@ export class TestRunner {
     return this.getUser(userId).x25519Keypair;
   }
 
+  /** Get the market creator's token account address */
+  getCreatorTokenAccount(): Address {
+    return this.marketCreator.tokenAccount;
+  }
+
   /** Get a user's token account address */
   getUserTokenAccount(userId: Address): Address {
     return this.getUser(userId).tokenAccount;